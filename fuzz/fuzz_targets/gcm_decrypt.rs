@@ -0,0 +1,36 @@
+//! Decrypts arbitrary ciphertexts with arbitrary tags. Decryption must never panic, must zero the buffer
+//! when it rejects the tag, and when it accepts it, the plaintext must encrypt back to the same ciphertext
+//! and tag
+
+#![no_main]
+
+use aes_crypto::gcm::AesGcm;
+use aes_crypto::AesEncrypt;
+use aes_crypto_fuzz::{GcmFn, GcmInput};
+use libfuzzer_sys::fuzz_target;
+
+struct Decrypt<'a>(&'a GcmInput<'a>);
+
+impl GcmFn for Decrypt<'_> {
+    fn call<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(self, gcm: &AesGcm<E, KEY_LEN>) {
+        let input = self.0;
+        let mut data = input.message.to_vec();
+
+        match gcm.decrypt(input.nonce, input.aad, &mut data, &input.tag) {
+            Ok(()) => {
+                let mut ciphertext = vec![0; data.len()];
+                let mut tag = [0; 16];
+                gcm.encrypt(input.nonce, input.aad, &data, &mut ciphertext, &mut tag);
+                assert_eq!(ciphertext, input.message);
+                assert_eq!(tag, input.tag);
+            }
+            Err(_) => assert!(data.iter().all(|&x| x == 0), "plaintext released"),
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(input) = GcmInput::parse(data) {
+        input.with_gcm(Decrypt(&input));
+    }
+});
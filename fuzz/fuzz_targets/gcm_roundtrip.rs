@@ -0,0 +1,47 @@
+//! Encrypts an arbitrary message and checks that it decrypts back, and that flipping any bit of the tag
+//! (chosen by the ignored tag field of the input) makes decryption fail
+
+#![no_main]
+
+use aes_crypto::gcm::AesGcm;
+use aes_crypto::AesEncrypt;
+use aes_crypto_fuzz::{GcmFn, GcmInput};
+use libfuzzer_sys::fuzz_target;
+
+struct RoundTrip<'a>(&'a GcmInput<'a>);
+
+impl GcmFn for RoundTrip<'_> {
+    fn call<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(self, gcm: &AesGcm<E, KEY_LEN>) {
+        let input = self.0;
+        // encryption panics on an empty nonce, and decryption must reject it
+        if input.nonce.is_empty() {
+            let mut data = input.message.to_vec();
+            assert!(gcm
+                .decrypt(input.nonce, input.aad, &mut data, &input.tag)
+                .is_err());
+            return;
+        }
+
+        let mut data = vec![0; input.message.len()];
+        let mut tag = [0; 16];
+        gcm.encrypt(input.nonce, input.aad, input.message, &mut data, &mut tag);
+        let ciphertext = data.clone();
+
+        assert!(gcm.decrypt(input.nonce, input.aad, &mut data, &tag).is_ok());
+        assert_eq!(data, input.message);
+
+        let bit = input.tag[0] % 128;
+        tag[usize::from(bit / 8)] ^= 1 << (bit % 8);
+        let mut data = ciphertext;
+        assert!(gcm
+            .decrypt(input.nonce, input.aad, &mut data, &tag)
+            .is_err());
+        assert!(data.iter().all(|&x| x == 0));
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(input) = GcmInput::parse(data) {
+        input.with_gcm(RoundTrip(&input));
+    }
+});
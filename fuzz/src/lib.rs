@@ -0,0 +1,68 @@
+//! Input parsing shared by the fuzz targets. An input is laid out as
+//!
+//! | bytes   | meaning                                                 |
+//! |---------|---------------------------------------------------------|
+//! | 1       | key size: 16, 24 or 32 bytes, by the byte modulo 3       |
+//! | 1       | nonce length, 0 to 64 bytes (by the byte modulo 65)      |
+//! | 1       | AAD length, 0 to 255 bytes                              |
+//! | 16      | tag                                                     |
+//! | ...     | key, nonce and AAD                                      |
+//! | rest    | the message                                             |
+//!
+//! so the corpus seeds can be written by hand from the test vectors
+
+use aes_crypto::gcm::AesGcm;
+use aes_crypto::{Aes128Enc, Aes192Enc, Aes256Enc, AesEncrypt};
+
+pub struct GcmInput<'a> {
+    pub key: &'a [u8],
+    pub nonce: &'a [u8],
+    pub aad: &'a [u8],
+    pub tag: [u8; 16],
+    pub message: &'a [u8],
+}
+
+impl<'a> GcmInput<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let (&[key_size, nonce_len, aad_len], data) = data.split_first_chunk()?;
+        let (tag, data) = data.split_first_chunk::<16>()?;
+
+        let key_len = [16, 24, 32][usize::from(key_size % 3)];
+        let nonce_len = usize::from(nonce_len % 65);
+        let aad_len = usize::from(aad_len);
+        if data.len() < key_len + nonce_len + aad_len {
+            return None;
+        }
+
+        let (key, data) = data.split_at(key_len);
+        let (nonce, data) = data.split_at(nonce_len);
+        let (aad, message) = data.split_at(aad_len);
+        Some(Self {
+            key,
+            nonce,
+            aad,
+            tag: *tag,
+            message,
+        })
+    }
+
+    /// Runs `f` with an `AesGcm` of the right key size
+    pub fn with_gcm(&self, f: impl GcmFn) {
+        match self.key.len() {
+            16 => f.call(&AesGcm::new(Aes128Enc::from(
+                <[u8; 16]>::try_from(self.key).unwrap(),
+            ))),
+            24 => f.call(&AesGcm::new(Aes192Enc::from(
+                <[u8; 24]>::try_from(self.key).unwrap(),
+            ))),
+            _ => f.call(&AesGcm::new(Aes256Enc::from(
+                <[u8; 32]>::try_from(self.key).unwrap(),
+            ))),
+        }
+    }
+}
+
+/// A function generic over the key size, as closures cannot be
+pub trait GcmFn {
+    fn call<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(self, gcm: &AesGcm<E, KEY_LEN>);
+}
@@ -0,0 +1,145 @@
+use core::fmt::{self, Display, Formatter};
+use core::hint::black_box;
+
+use crate::{AesBlock, AesEncrypt};
+
+/// The error returned by authenticated decryption when the tag does not match
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AuthError;
+
+impl Display for AuthError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("authentication tag mismatch")
+    }
+}
+
+impl core::error::Error for AuthError {}
+
+/// Compares two tags in constant time
+#[inline]
+#[must_use]
+pub(crate) fn tags_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0, |acc, (&x, &y)| acc | (x ^ y));
+    black_box(diff) == 0
+}
+
+/// Finishes an in-place detached decryption. The tags are compared in constant time, and if they do not
+/// match, `buf` (which holds the unauthenticated plaintext) is zeroed before returning the error, so it
+/// can never be used accidentally. AEAD modes built on this crate should release plaintext only through
+/// this function
+///
+/// # Errors
+///
+/// Returns [`AuthError`] if the tags do not match
+#[inline]
+pub fn verify_and_release(
+    buf: &mut [u8],
+    expected: &[u8],
+    computed: &[u8],
+) -> Result<(), AuthError> {
+    if tags_eq(expected, computed) {
+        Ok(())
+    } else {
+        buf.fill(0);
+        Err(AuthError)
+    }
+}
+
+/// Deterministically derives a 96-bit nonce from `context` (e.g. a message counter or a unique message
+/// identifier), by computing a length-prefixed CBC-MAC of it with `key`.
+///
+/// `key` must be dedicated to nonce derivation, and independent of any AES-GCM key: with the same key, this
+/// CBC-MAC and the encryptions GCM makes for its hash key and pre-counter blocks would share one
+/// permutation.
+///
+/// The CBC-MAC is truncated to 96 bits, so by the birthday bound, nonces collide with a probability of
+/// about `n^2 / 2^97` after `n` distinct contexts, i.e. collisions become likely after about `2^48`
+/// contexts, and the number of contexts per key should stay far below that (e.g. `2^32` gives a
+/// probability of about `2^-33`). Within that bound, this turns the problem of never repeating a nonce
+/// into the (often easier) problem of never repeating a context. It is only a convenience: if contexts can
+/// repeat, use a misuse-resistant mode like AES-GCM-SIV instead
+#[must_use]
+pub fn derive_nonce<const KEY_LEN: usize>(
+    key: &impl AesEncrypt<KEY_LEN>,
+    context: &[u8],
+) -> [u8; 12] {
+    let mut acc = key.encrypt_block(AesBlock::from(context.len() as u128));
+    for chunk in context.chunks(16) {
+        let mut block = [0; 16];
+        block[..chunk.len()].copy_from_slice(chunk);
+        acc = key.encrypt_block(acc ^ block.into());
+    }
+
+    let mut nonce = [0; 12];
+    nonce.copy_from_slice(&acc.to_bytes()[..12]);
+    nonce
+}
+
+/// The error returned by the AEAD variants that write into a [`heapless::Vec`]
+#[cfg(feature = "heapless")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HeaplessError {
+    /// The output does not fit in the capacity of the vector
+    Capacity,
+    /// The tag does not match (or the input is too short to hold one)
+    Auth,
+}
+
+#[cfg(feature = "heapless")]
+impl Display for HeaplessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Capacity => "the output does not fit in the buffer",
+            Self::Auth => "authentication tag mismatch",
+        })
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl core::error::Error for HeaplessError {}
+
+#[cfg(feature = "heapless")]
+impl From<AuthError> for HeaplessError {
+    fn from(_: AuthError) -> Self {
+        Self::Auth
+    }
+}
+
+/// Allocates the `ciphertext || tag` output for `plaintext` in a [`heapless::Vec`], and fills it with
+/// `encrypt`, which is given the ciphertext and tag parts
+#[cfg(feature = "heapless")]
+pub(crate) fn encrypt_to_heapless<const N: usize>(
+    plaintext: &[u8],
+    encrypt: impl FnOnce(&mut [u8], &mut [u8; 16]),
+) -> Result<heapless::Vec<u8, N>, HeaplessError> {
+    let mut out = heapless::Vec::new();
+    out.resize(plaintext.len() + 16, 0)
+        .map_err(|()| HeaplessError::Capacity)?;
+
+    let (ciphertext, tag) = out.split_at_mut(plaintext.len());
+    encrypt(ciphertext, tag.try_into().unwrap());
+    Ok(out)
+}
+
+/// Splits `ciphertext || tag`, copies the ciphertext into a [`heapless::Vec`], and decrypts it in place
+/// with `decrypt`. On failure the vector (zeroed by [`verify_and_release`]) is dropped, so no
+/// unauthenticated plaintext is returned
+#[cfg(feature = "heapless")]
+pub(crate) fn decrypt_to_heapless<const N: usize>(
+    ciphertext: &[u8],
+    decrypt: impl FnOnce(&mut [u8], &[u8; 16]) -> Result<(), AuthError>,
+) -> Result<heapless::Vec<u8, N>, HeaplessError> {
+    let Some(len) = ciphertext.len().checked_sub(16) else {
+        return Err(HeaplessError::Auth);
+    };
+    let (ciphertext, tag) = ciphertext.split_at(len);
+
+    let mut out = heapless::Vec::new();
+    out.extend_from_slice(ciphertext)
+        .map_err(|()| HeaplessError::Capacity)?;
+    decrypt(&mut out, tag.try_into().unwrap())?;
+    Ok(out)
+}
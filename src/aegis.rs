@@ -0,0 +1,179 @@
+//! The AEGIS-128L authenticated cipher, as specified in the [CFRG draft](https://datatracker.ietf.org/doc/draft-irtf-cfrg-aegis-aead/).
+//! It uses the AES round function directly (so it has no key schedule), and is much faster than AES-GCM
+//! on CPUs with AES instructions. The nonce is 128 bits, and the tag is the 128-bit variant
+//!
+//! ```
+//! # use aes_crypto::aegis;
+//! let (key, nonce) = ([1; 16], [2; 16]);
+//! let plaintext = *b"some secret message";
+//!
+//! let mut ciphertext = [0; 19];
+//! let mut tag = [0; 16];
+//! aegis::encrypt(&key, &nonce, b"header", &plaintext, &mut ciphertext, &mut tag);
+//!
+//! aegis::decrypt(&key, &nonce, b"header", &mut ciphertext, &tag).unwrap();
+//! assert_eq!(ciphertext, plaintext);
+//! ```
+
+use crate::aead::{verify_and_release, AuthError};
+use crate::{AesBlock, AesBlockX4};
+
+const C0: AesBlock = AesBlock::new([
+    0x00, 0x01, 0x01, 0x02, 0x03, 0x05, 0x08, 0x0d, 0x15, 0x22, 0x37, 0x59, 0x90, 0xe9, 0x79, 0x62,
+]);
+const C1: AesBlock = AesBlock::new([
+    0xdb, 0x3d, 0x18, 0x55, 0x6d, 0xc2, 0x2f, 0xf1, 0x20, 0x11, 0x31, 0x42, 0x73, 0xb5, 0x28, 0xdd,
+]);
+
+struct State([AesBlock; 8]);
+
+impl State {
+    fn new(key: AesBlock, nonce: AesBlock) -> Self {
+        let mut state = Self([
+            key ^ nonce,
+            C1,
+            C0,
+            C1,
+            key ^ nonce,
+            key ^ C0,
+            key ^ C1,
+            key ^ C0,
+        ]);
+        for _ in 0..10 {
+            state.update(nonce, key);
+        }
+        state
+    }
+
+    /// Every register is updated with one AES round of its predecessor, keyed by itself, so the two halves
+    /// of the state are updated as two independent 4-lane rounds
+    #[inline]
+    fn update(&mut self, m0: AesBlock, m1: AesBlock) {
+        let [s0, s1, s2, s3, s4, s5, s6, s7] = self.0;
+        let lo = AesBlockX4::from((s7, s0, s1, s2)).enc(AesBlockX4::from((s0 ^ m0, s1, s2, s3)));
+        let hi = AesBlockX4::from((s3, s4, s5, s6)).enc(AesBlockX4::from((s4 ^ m1, s5, s6, s7)));
+        (self.0[0], self.0[1], self.0[2], self.0[3]) = lo.into();
+        (self.0[4], self.0[5], self.0[6], self.0[7]) = hi.into();
+    }
+
+    #[inline]
+    fn keystream(&self) -> (AesBlock, AesBlock) {
+        let s = &self.0;
+        (s[6] ^ s[1] ^ (s[2] & s[3]), s[2] ^ s[5] ^ (s[6] & s[7]))
+    }
+
+    fn absorb(&mut self, data: &[u8]) {
+        let mut chunks = data.chunks_exact(32);
+        for chunk in &mut chunks {
+            self.update(
+                AesBlock::try_from(&chunk[..16]).unwrap(),
+                AesBlock::try_from(&chunk[16..]).unwrap(),
+            );
+        }
+        let remainder = chunks.remainder();
+        if !remainder.is_empty() {
+            let mut block = [0; 32];
+            block[..remainder.len()].copy_from_slice(remainder);
+            self.absorb(&block);
+        }
+    }
+
+    fn encrypt(&mut self, data: &mut [u8]) {
+        let mut chunks = data.chunks_exact_mut(32);
+        for chunk in &mut chunks {
+            let (z0, z1) = self.keystream();
+            let t0 = AesBlock::try_from(&chunk[..16]).unwrap();
+            let t1 = AesBlock::try_from(&chunk[16..]).unwrap();
+            (t0 ^ z0).store_to(&mut chunk[..16]);
+            (t1 ^ z1).store_to(&mut chunk[16..]);
+            self.update(t0, t1);
+        }
+        let remainder = chunks.into_remainder();
+        if !remainder.is_empty() {
+            let mut block = [0; 32];
+            block[..remainder.len()].copy_from_slice(remainder);
+            self.encrypt(&mut block);
+            remainder.copy_from_slice(&block[..remainder.len()]);
+        }
+    }
+
+    fn decrypt(&mut self, data: &mut [u8]) {
+        let mut chunks = data.chunks_exact_mut(32);
+        for chunk in &mut chunks {
+            let (z0, z1) = self.keystream();
+            let out0 = AesBlock::try_from(&chunk[..16]).unwrap() ^ z0;
+            let out1 = AesBlock::try_from(&chunk[16..]).unwrap() ^ z1;
+            out0.store_to(&mut chunk[..16]);
+            out1.store_to(&mut chunk[16..]);
+            self.update(out0, out1);
+        }
+        let remainder = chunks.into_remainder();
+        if !remainder.is_empty() {
+            // the state is updated with the zero-padded plaintext, not the decrypted padding
+            let (z0, z1) = self.keystream();
+            let mut block = [0; 32];
+            block[..remainder.len()].copy_from_slice(remainder);
+            let out0 = AesBlock::try_from(&block[..16]).unwrap() ^ z0;
+            let out1 = AesBlock::try_from(&block[16..]).unwrap() ^ z1;
+            out0.store_to(&mut block[..16]);
+            out1.store_to(&mut block[16..]);
+            block[remainder.len()..].fill(0);
+            remainder.copy_from_slice(&block[..remainder.len()]);
+            self.absorb(&block);
+        }
+    }
+
+    fn finalize(mut self, aad_len: usize, msg_len: usize) -> [u8; 16] {
+        let lengths = ((aad_len as u128 * 8) | ((msg_len as u128 * 8) << 64)).to_le_bytes();
+        let t = self.0[2] ^ lengths.into();
+        for _ in 0..7 {
+            self.update(t, t);
+        }
+        let s = &self.0;
+        (s[0] ^ s[1] ^ s[2] ^ s[3] ^ s[4] ^ s[5] ^ s[6]).into()
+    }
+}
+
+/// Encrypts `plaintext` into `out`, authenticating it together with `aad`, and writes the tag to `tag`
+///
+/// # Panics
+///
+/// Panics if `out` is not the same length as `plaintext`
+pub fn encrypt(
+    key: &[u8; 16],
+    nonce: &[u8; 16],
+    aad: &[u8],
+    plaintext: &[u8],
+    out: &mut [u8],
+    tag: &mut [u8; 16],
+) {
+    assert_eq!(plaintext.len(), out.len());
+    let mut state = State::new(key.into(), nonce.into());
+    state.absorb(aad);
+
+    out.copy_from_slice(plaintext);
+    state.encrypt(out);
+
+    *tag = state.finalize(aad.len(), out.len());
+}
+
+/// Decrypts `data` in place, checking it and `aad` against `tag`. The tags are compared in constant time,
+/// and on failure `data` is zeroed, so the unauthenticated plaintext is never exposed
+///
+/// # Errors
+///
+/// Returns [`AuthError`] if the tag does not match
+pub fn decrypt(
+    key: &[u8; 16],
+    nonce: &[u8; 16],
+    aad: &[u8],
+    data: &mut [u8],
+    tag: &[u8; 16],
+) -> Result<(), AuthError> {
+    let mut state = State::new(key.into(), nonce.into());
+    state.absorb(aad);
+    state.decrypt(data);
+
+    let computed = state.finalize(aad.len(), data.len());
+    verify_and_release(data, tag, &computed)
+}
@@ -69,6 +69,13 @@ impl AesBlock {
         unsafe { mem::transmute(value) }
     }
 
+    #[inline]
+    #[must_use]
+    pub const fn to_bytes(self) -> [u8; 16] {
+        // using transmute in simd is safe
+        unsafe { mem::transmute(self) }
+    }
+
     #[inline]
     pub fn store_to(self, dst: &mut [u8]) {
         assert!(dst.len() >= 16);
@@ -99,13 +106,106 @@ impl AesBlock {
         }
     }
 
+    /// Shifts the block left by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shl_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shl_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) << 16);
+    /// ```
+    #[inline]
+    pub fn shl_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        // the first byte in memory is the most significant one
+        let value = u128::from_le_bytes(self.to_bytes());
+        Self::new((value >> (8 * N)).to_le_bytes())
+    }
+
+    /// Shifts the block right by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shr_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shr_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) >> 16);
+    /// ```
+    #[inline]
+    pub fn shr_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        let value = u128::from_le_bytes(self.to_bytes());
+        Self::new((value << (8 * N)).to_le_bytes())
+    }
+
+    /// Reverses the order of the bits within each byte of the block, keeping the order of the bytes
+    /// unchanged. This is the bit-reflection between the GHASH and POLYVAL conventions, and is not the
+    /// same as reversing all 128 bits of the block
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from(*b"\x01\x02\x0f\xf0abcdefghijkl");
+    /// let expected = b"\x01\x02\x0f\xf0abcdefghijkl".map(u8::reverse_bits);
+    /// assert_eq!(block.reverse_bits_per_byte(), AesBlock::from(expected));
+    /// ```
+    #[inline]
+    pub fn reverse_bits_per_byte(self) -> Self {
+        Self(unsafe { vrbitq_u8(self.0) })
+    }
+
+    /// Rotates column `c` of the state down by `c` positions, moving the byte in row `r` and column `c` to row
+    /// `(r + c) % 4`. This is the column-wise analogue of `ShiftRows`, as used in some AES-based designs.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 7, 4, 5, 6, 10, 11, 8, 9, 13, 14, 15, 12][i]` of `self`
+    #[inline]
+    pub fn rotate_diagonal(self) -> Self {
+        #[cfg(not(target_arch = "arm"))]
+        {
+            Self(unsafe { vqtbl1q_u8(self.0, vld1q_u8(crate::ROTATE_DIAGONAL.as_ptr())) })
+        }
+        #[cfg(target_arch = "arm")]
+        {
+            let bytes = self.to_bytes();
+            Self::new(crate::ROTATE_DIAGONAL.map(|i| bytes[i as usize]))
+        }
+    }
+
+    /// The inverse of [`AesBlock::rotate_diagonal`], rotating column `c` of the state up by `c` positions.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 5, 6, 7, 4, 10, 11, 8, 9, 15, 12, 13, 14][i]` of `self`
+    #[inline]
+    pub fn inv_rotate_diagonal(self) -> Self {
+        #[cfg(not(target_arch = "arm"))]
+        {
+            Self(unsafe { vqtbl1q_u8(self.0, vld1q_u8(crate::INV_ROTATE_DIAGONAL.as_ptr())) })
+        }
+        #[cfg(target_arch = "arm")]
+        {
+            let bytes = self.to_bytes();
+            Self::new(crate::INV_ROTATE_DIAGONAL.map(|i| bytes[i as usize]))
+        }
+    }
+
     #[inline(always)]
     fn aese(self, round_key: Self) -> Self {
         Self(unsafe { vaeseq_u8(self.0, round_key.0) })
     }
 
+    /// Performs one round of AES encryption function with the round key added first, and not at the end
+    /// (`AddRoundKey`->`ShiftRows`->`SubBytes`->`MixColumns`), which is a single `aese`+`aesmc` pair
     #[inline(always)]
-    pub(crate) fn pre_enc(self, round_key: Self) -> Self {
+    pub fn pre_enc(self, round_key: Self) -> Self {
         self.aese(round_key).mc()
     }
 
@@ -120,8 +220,11 @@ impl AesBlock {
         Self(unsafe { vaesdq_u8(self.0, round_key.0) })
     }
 
+    /// Performs one round of AES decryption function with the round key added first, and not at the end
+    /// (`AddRoundKey`->`InvShiftRows`->`InvSubBytes`->`InvMixColumn`s), which is a single `aesd`+`aesimc`
+    /// pair
     #[inline(always)]
-    pub(crate) fn pre_dec(self, round_key: Self) -> Self {
+    pub fn pre_dec(self, round_key: Self) -> Self {
         self.aesd(round_key).imc()
     }
 
@@ -154,6 +257,34 @@ impl AesBlock {
     pub fn imc(self) -> Self {
         Self(unsafe { vaesimcq_u8(self.0) })
     }
+
+    /// Computes the carryless (GF(2) polynomial) product of the two blocks, treated as big-endian 128-bit
+    /// integers, returning the high and low halves of the 256-bit result
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let (hi, lo) = AesBlock::from(0b11).clmul(AesBlock::from(0b11));
+    /// assert_eq!((u128::from(hi), u128::from(lo)), (0, 0b101));
+    /// ```
+    #[inline]
+    pub fn clmul(self, other: Self) -> (Self, Self) {
+        #[cfg(not(target_arch = "arm"))]
+        {
+            let (a, b) = (u128::from(self), u128::from(other));
+            #[allow(clippy::cast_possible_truncation)]
+            let mul = |x: u128, y: u128| unsafe { vmull_p64(x as u64, y as u64) };
+
+            let lo = mul(a, b);
+            let hi = mul(a >> 64, b >> 64);
+            let mid = mul(a, b >> 64) ^ mul(a >> 64, b);
+            ((hi ^ (mid >> 64)).into(), (lo ^ (mid << 64)).into())
+        }
+        #[cfg(target_arch = "arm")]
+        {
+            let (hi, lo) = crate::clmul_soft(self.into(), other.into());
+            (hi.into(), lo.into())
+        }
+    }
 }
 
 #[inline(always)]
@@ -1,5 +1,4 @@
 use core::ops::{BitAnd, BitOr, BitXor, Not};
-use core::{mem, slice};
 
 #[inline(always)]
 const fn rep(x: u8) -> u128 {
@@ -281,6 +280,12 @@ impl AesBlock {
         Self(u128::from_ne_bytes(value))
     }
 
+    #[inline]
+    #[must_use]
+    pub const fn to_bytes(self) -> [u8; 16] {
+        self.0.to_ne_bytes()
+    }
+
     #[inline]
     pub fn store_to(self, dst: &mut [u8]) {
         assert!(dst.len() >= 16);
@@ -298,6 +303,89 @@ impl AesBlock {
         self.0 == 0
     }
 
+    /// Shifts the block left by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shl_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shl_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) << 16);
+    /// ```
+    #[inline]
+    pub fn shl_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        // the first byte in memory is the most significant one
+        if cfg!(target_endian = "big") {
+            Self(self.0 << (8 * N))
+        } else {
+            Self(self.0 >> (8 * N))
+        }
+    }
+
+    /// Shifts the block right by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shr_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shr_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) >> 16);
+    /// ```
+    #[inline]
+    pub fn shr_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        if cfg!(target_endian = "big") {
+            Self(self.0 >> (8 * N))
+        } else {
+            Self(self.0 << (8 * N))
+        }
+    }
+
+    /// Reverses the order of the bits within each byte of the block, keeping the order of the bytes
+    /// unchanged. This is the bit-reflection between the GHASH and POLYVAL conventions, and is not the
+    /// same as reversing all 128 bits of the block
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from(*b"\x01\x02\x0f\xf0abcdefghijkl");
+    /// let expected = b"\x01\x02\x0f\xf0abcdefghijkl".map(u8::reverse_bits);
+    /// assert_eq!(block.reverse_bits_per_byte(), AesBlock::from(expected));
+    /// ```
+    #[inline]
+    pub fn reverse_bits_per_byte(self) -> Self {
+        Self::new(self.to_bytes().map(u8::reverse_bits))
+    }
+
+    /// Rotates column `c` of the state down by `c` positions, moving the byte in row `r` and column `c` to row
+    /// `(r + c) % 4`. This is the column-wise analogue of `ShiftRows`, as used in some AES-based designs.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 7, 4, 5, 6, 10, 11, 8, 9, 13, 14, 15, 12][i]` of `self`
+    #[inline]
+    pub fn rotate_diagonal(self) -> Self {
+        let bytes = self.to_bytes();
+        Self::new(crate::ROTATE_DIAGONAL.map(|i| bytes[i as usize]))
+    }
+
+    /// The inverse of [`AesBlock::rotate_diagonal`], rotating column `c` of the state up by `c` positions.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 5, 6, 7, 4, 10, 11, 8, 9, 15, 12, 13, 14][i]` of `self`
+    #[inline]
+    pub fn inv_rotate_diagonal(self) -> Self {
+        let bytes = self.to_bytes();
+        Self::new(crate::INV_ROTATE_DIAGONAL.map(|i| bytes[i as usize]))
+    }
+
     /// Performs one round of AES encryption function (`ShiftRows`->`SubBytes`->`MixColumns`->`AddRoundKey`)
     #[inline]
     pub fn enc(self, round_key: Self) -> Self {
@@ -333,63 +421,125 @@ impl AesBlock {
     pub fn imc(self) -> Self {
         Self(invmixcolumns(self.0))
     }
+
+    /// Computes the carryless (GF(2) polynomial) product of the two blocks, treated as big-endian 128-bit
+    /// integers, returning the high and low halves of the 256-bit result
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let (hi, lo) = AesBlock::from(0b11).clmul(AesBlock::from(0b11));
+    /// assert_eq!((u128::from(hi), u128::from(lo)), (0, 0b101));
+    /// ```
+    #[inline]
+    pub fn clmul(self, other: Self) -> (Self, Self) {
+        let (hi, lo) = crate::clmul_soft(
+            u128::from_be_bytes(self.to_bytes()),
+            u128::from_be_bytes(other.to_bytes()),
+        );
+        (Self::new(hi.to_be_bytes()), Self::new(lo.to_be_bytes()))
+    }
+}
+
+// The key columns are loaded with `u32::from_ne_bytes`, so the round constant must land on the first byte
+// in memory, and `RotWord` must rotate towards it, whatever the endianness
+const fn rcon(x: u8) -> u32 {
+    u32::from_ne_bytes([x, 0, 0, 0])
 }
 
-const RCON: [u32; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+const RCON: [u32; 10] = [
+    rcon(0x01),
+    rcon(0x02),
+    rcon(0x04),
+    rcon(0x08),
+    rcon(0x10),
+    rcon(0x20),
+    rcon(0x40),
+    rcon(0x80),
+    rcon(0x1b),
+    rcon(0x36),
+];
 
-pub(super) fn keygen_128(key: [u8; 16]) -> [AesBlock; 11] {
-    let mut expanded_keys: [AesBlock; 11] = unsafe { mem::zeroed() };
-    let columns = unsafe { slice::from_raw_parts_mut(expanded_keys.as_mut_ptr().cast(), 44) };
+#[inline(always)]
+const fn rot_word(x: u32) -> u32 {
+    if cfg!(target_endian = "big") {
+        x.rotate_left(8)
+    } else {
+        x.rotate_right(8)
+    }
+}
+
+/// Loads the key into the first columns of the schedule
+const fn load_columns<const K: usize, const C: usize>(key: &[u8; K]) -> [u32; C] {
+    let mut columns = [0; C];
+    let mut i = 0;
+    while i < K / 4 {
+        columns[i] =
+            u32::from_ne_bytes([key[4 * i], key[4 * i + 1], key[4 * i + 2], key[4 * i + 3]]);
+        i += 1;
+    }
+    columns
+}
 
-    for (i, chunk) in key.chunks_exact(4).enumerate() {
-        columns[i] = u32::from_ne_bytes(chunk.try_into().unwrap());
+/// Packs every four consecutive columns into a round key
+const fn to_round_keys<const C: usize, const N: usize>(columns: &[u32; C]) -> [AesBlock; N] {
+    let mut round_keys = [AesBlock(0); N];
+    let mut i = 0;
+    while i < N {
+        let mut bytes = [0; 16];
+        let mut j = 0;
+        while j < 16 {
+            bytes[j] = columns[4 * i + j / 4].to_ne_bytes()[j % 4];
+            j += 1;
+        }
+        round_keys[i] = AesBlock::new(bytes);
+        i += 1;
     }
+    round_keys
+}
 
-    for i in (0..40).step_by(4) {
-        columns[i + 4] = columns[i + 0] ^ sub_word(columns[i + 3]).rotate_right(8) ^ RCON[i / 4];
+pub(super) const fn keygen_128(key: [u8; 16]) -> [AesBlock; 11] {
+    let mut columns: [u32; 44] = load_columns(&key);
+
+    let mut i = 0;
+    while i < 40 {
+        columns[i + 4] = columns[i + 0] ^ rot_word(sub_word(columns[i + 3])) ^ RCON[i / 4];
         columns[i + 5] = columns[i + 1] ^ columns[i + 4];
         columns[i + 6] = columns[i + 2] ^ columns[i + 5];
         columns[i + 7] = columns[i + 3] ^ columns[i + 6];
+        i += 4;
     }
 
-    expanded_keys
+    to_round_keys(&columns)
 }
 
-pub(super) fn keygen_192(key: [u8; 24]) -> [AesBlock; 13] {
-    let mut expanded_keys: [AesBlock; 13] = unsafe { mem::zeroed() };
-    let columns = unsafe { slice::from_raw_parts_mut(expanded_keys.as_mut_ptr().cast(), 52) };
-
-    for (i, chunk) in key.chunks_exact(4).enumerate() {
-        columns[i] = u32::from_ne_bytes(chunk.try_into().unwrap());
-    }
+pub(super) const fn keygen_192(key: [u8; 24]) -> [AesBlock; 13] {
+    let mut columns: [u32; 52] = load_columns(&key);
 
-    for i in (0..42).step_by(6) {
-        columns[i + 6] = columns[i + 0] ^ sub_word(columns[i + 5]).rotate_right(8) ^ RCON[i / 6];
+    let mut i = 0;
+    while i < 42 {
+        columns[i + 6] = columns[i + 0] ^ rot_word(sub_word(columns[i + 5])) ^ RCON[i / 6];
         columns[i + 7] = columns[i + 1] ^ columns[i + 6];
         columns[i + 8] = columns[i + 2] ^ columns[i + 7];
         columns[i + 9] = columns[i + 3] ^ columns[i + 8];
         columns[i + 10] = columns[i + 4] ^ columns[i + 9];
         columns[i + 11] = columns[i + 5] ^ columns[i + 10];
+        i += 6;
     }
 
-    columns[48] = columns[42] ^ sub_word(columns[47]).rotate_right(8) ^ RCON[7];
+    columns[48] = columns[42] ^ rot_word(sub_word(columns[47])) ^ RCON[7];
     columns[49] = columns[43] ^ columns[48];
     columns[50] = columns[44] ^ columns[49];
     columns[51] = columns[45] ^ columns[50];
 
-    expanded_keys
+    to_round_keys(&columns)
 }
 
-pub(super) fn keygen_256(key: [u8; 32]) -> [AesBlock; 15] {
-    let mut expanded_keys: [AesBlock; 15] = unsafe { mem::zeroed() };
-    let columns = unsafe { slice::from_raw_parts_mut(expanded_keys.as_mut_ptr().cast(), 60) };
+pub(super) const fn keygen_256(key: [u8; 32]) -> [AesBlock; 15] {
+    let mut columns: [u32; 60] = load_columns(&key);
 
-    for (i, chunk) in key.chunks_exact(4).enumerate() {
-        columns[i] = u32::from_ne_bytes(chunk.try_into().unwrap());
-    }
-
-    for i in (0..48).step_by(8) {
-        columns[i + 8] = columns[i + 0] ^ sub_word(columns[i + 7]).rotate_right(8) ^ RCON[i / 8];
+    let mut i = 0;
+    while i < 48 {
+        columns[i + 8] = columns[i + 0] ^ rot_word(sub_word(columns[i + 7])) ^ RCON[i / 8];
         columns[i + 9] = columns[i + 1] ^ columns[i + 8];
         columns[i + 10] = columns[i + 2] ^ columns[i + 9];
         columns[i + 11] = columns[i + 3] ^ columns[i + 10];
@@ -397,20 +547,95 @@ pub(super) fn keygen_256(key: [u8; 32]) -> [AesBlock; 15] {
         columns[i + 13] = columns[i + 5] ^ columns[i + 12];
         columns[i + 14] = columns[i + 6] ^ columns[i + 13];
         columns[i + 15] = columns[i + 7] ^ columns[i + 14];
+        i += 8;
     }
 
-    columns[56] = columns[48] ^ sub_word(columns[55]).rotate_right(8) ^ RCON[6];
+    columns[56] = columns[48] ^ rot_word(sub_word(columns[55])) ^ RCON[6];
     columns[57] = columns[49] ^ columns[56];
     columns[58] = columns[50] ^ columns[57];
     columns[59] = columns[51] ^ columns[58];
 
-    expanded_keys
+    to_round_keys(&columns)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_const_keygen() {
+        // FIPS-197, Appendix A
+        const EXPANDED_128: [AesBlock; 11] =
+            keygen_128(0x2b7e151628aed2a6abf7158809cf4f3c_u128.to_be_bytes());
+        const EXPANDED_192: [AesBlock; 13] = keygen_192([
+            0x8e, 0x73, 0xb0, 0xf7, 0xda, 0x0e, 0x64, 0x52, 0xc8, 0x10, 0xf3, 0x2b, 0x80, 0x90,
+            0x79, 0xe5, 0x62, 0xf8, 0xea, 0xd2, 0x52, 0x2c, 0x6b, 0x7b,
+        ]);
+        const EXPANDED_256: [AesBlock; 15] = keygen_256([
+            0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe, 0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d,
+            0x77, 0x81, 0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7, 0x2d, 0x98, 0x10, 0xa3,
+            0x09, 0x14, 0xdf, 0xf4,
+        ]);
+
+        // every round key, compared as bytes, so that a byte-order error anywhere in the schedule fails on
+        // both little- and big-endian hosts
+        const ROUND_KEYS_128: [u128; 11] = [
+            0x2b7e151628aed2a6abf7158809cf4f3c,
+            0xa0fafe1788542cb123a339392a6c7605,
+            0xf2c295f27a96b9435935807a7359f67f,
+            0x3d80477d4716fe3e1e237e446d7a883b,
+            0xef44a541a8525b7fb671253bdb0bad00,
+            0xd4d1c6f87c839d87caf2b8bc11f915bc,
+            0x6d88a37a110b3efddbf98641ca0093fd,
+            0x4e54f70e5f5fc9f384a64fb24ea6dc4f,
+            0xead27321b58dbad2312bf5607f8d292f,
+            0xac7766f319fadc2128d12941575c006e,
+            0xd014f9a8c9ee2589e13f0cc8b6630ca6,
+        ];
+        const ROUND_KEYS_192: [u128; 13] = [
+            0x8e73b0f7da0e6452c810f32b809079e5,
+            0x62f8ead2522c6b7bfe0c91f72402f5a5,
+            0xec12068e6c827f6b0e7a95b95c56fec2,
+            0x4db7b4bd69b5411885a74796e92538fd,
+            0xe75fad44bb095386485af05721efb14f,
+            0xa448f6d94d6dce24aa326360113b30e6,
+            0xa25e7ed583b1cf9a27f939436a94f767,
+            0xc0a69407d19da4e1ec1786eb6fa64971,
+            0x485f703222cb8755e26d135233f0b7b3,
+            0x40beeb282f18a2596747d26b458c553e,
+            0xa7e1466c9411f1df821f750aad07d753,
+            0xca4005388fcc5006282d166abc3ce7b5,
+            0xe98ba06f448c773c8ecc720401002202,
+        ];
+        const ROUND_KEYS_256: [u128; 15] = [
+            0x603deb1015ca71be2b73aef0857d7781,
+            0x1f352c073b6108d72d9810a30914dff4,
+            0x9ba354118e6925afa51a8b5f2067fcde,
+            0xa8b09c1a93d194cdbe49846eb75d5b9a,
+            0xd59aecb85bf3c917fee94248de8ebe96,
+            0xb5a9328a2678a647983122292f6c79b3,
+            0x812c81addadf48ba24360af2fab8b464,
+            0x98c5bfc9bebd198e268c3ba709e04214,
+            0x68007bacb2df331696e939e46c518d80,
+            0xc814e20476a9fb8a5025c02d59c58239,
+            0xde1369676ccc5a71fa2563959674ee15,
+            0x5886ca5d2e2f31d77e0af1fa27cf73c3,
+            0x749c47ab18501ddae2757e4f7401905a,
+            0xcafaaae3e4d59b349adf6acebd10190d,
+            0xfe4890d1e6188d0b046df344706c631e,
+        ];
+
+        for (round_key, expected) in EXPANDED_128.iter().zip(ROUND_KEYS_128) {
+            assert_eq!(round_key.to_bytes(), expected.to_be_bytes());
+        }
+        for (round_key, expected) in EXPANDED_192.iter().zip(ROUND_KEYS_192) {
+            assert_eq!(round_key.to_bytes(), expected.to_be_bytes());
+        }
+        for (round_key, expected) in EXPANDED_256.iter().zip(ROUND_KEYS_256) {
+            assert_eq!(round_key.to_bytes(), expected.to_be_bytes());
+        }
+    }
+
     #[test]
     fn test_subbytes() {
         let x = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
@@ -432,4 +657,12 @@ mod tests {
         ];
         assert_eq!(r, e);
     }
+
+    #[test]
+    fn test_key_schedule_byte_order() {
+        // these hold on both little- and big-endian hosts
+        let rotated = rot_word(u32::from_ne_bytes([1, 2, 3, 4]));
+        assert_eq!(rotated.to_ne_bytes(), [2, 3, 4, 1]);
+        assert_eq!(RCON[9].to_ne_bytes(), [0x36, 0, 0, 0]);
+    }
 }
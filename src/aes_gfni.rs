@@ -0,0 +1,426 @@
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+use core::ops::{BitAnd, BitOr, BitXor, Not};
+
+// A constant-time software AES for x86 CPUs with GFNI but without AES-NI (or where it is not enabled). The
+// block is kept in an SSE register like with AES-NI, `SubBytes` and its inverse are single `gf2p8affineinv`
+// instructions, `ShiftRows` is a byte shuffle, and `MixColumns` uses the GF(2^8) multiplier of `gf2p8mul`.
+// None of these depend on the data for their timing, unlike the table lookups of the software implementation
+
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+#[must_use]
+pub struct AesBlock(pub(super) __m128i);
+
+impl PartialEq for AesBlock {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        (*self ^ *other).is_zero()
+    }
+}
+
+impl Eq for AesBlock {}
+
+impl From<[u8; 16]> for AesBlock {
+    #[inline]
+    fn from(value: [u8; 16]) -> Self {
+        Self(unsafe { _mm_loadu_si128(value.as_ptr().cast()) })
+    }
+}
+
+impl BitAnd for AesBlock {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(unsafe { _mm_and_si128(self.0, rhs.0) })
+    }
+}
+
+impl BitOr for AesBlock {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(unsafe { _mm_or_si128(self.0, rhs.0) })
+    }
+}
+
+impl BitXor for AesBlock {
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self(unsafe { _mm_xor_si128(self.0, rhs.0) })
+    }
+}
+
+impl Not for AesBlock {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        Self(unsafe { _mm_xor_si128(self.0, _mm_set1_epi64x(-1)) })
+    }
+}
+
+impl AesBlock {
+    #[inline]
+    pub const fn new(value: [u8; 16]) -> Self {
+        // using transmute in simd is safe
+        unsafe { core::mem::transmute(value) }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn to_bytes(self) -> [u8; 16] {
+        // using transmute in simd is safe
+        unsafe { core::mem::transmute(self) }
+    }
+
+    #[inline]
+    pub fn store_to(self, dst: &mut [u8]) {
+        assert!(dst.len() >= 16);
+        unsafe { _mm_storeu_si128(dst.as_mut_ptr().cast(), self.0) };
+    }
+
+    #[inline]
+    pub fn zero() -> Self {
+        Self(unsafe { _mm_setzero_si128() })
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_zero(self) -> bool {
+        unsafe { _mm_testz_si128(self.0, self.0) == 1 }
+    }
+
+    /// Shifts the block left by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shl_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shl_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) << 16);
+    /// ```
+    #[inline]
+    pub fn shl_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        // the first byte in memory is the most significant one
+        Self(unsafe { _mm_bsrli_si128::<N>(self.0) })
+    }
+
+    /// Shifts the block right by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shr_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shr_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) >> 16);
+    /// ```
+    #[inline]
+    pub fn shr_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        Self(unsafe { _mm_bslli_si128::<N>(self.0) })
+    }
+
+    /// Reverses the order of the bits within each byte of the block, keeping the order of the bytes
+    /// unchanged. This is the bit-reflection between the GHASH and POLYVAL conventions, and is not the
+    /// same as reversing all 128 bits of the block
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from(*b"\x01\x02\x0f\xf0abcdefghijkl");
+    /// let expected = b"\x01\x02\x0f\xf0abcdefghijkl".map(u8::reverse_bits);
+    /// assert_eq!(block.reverse_bits_per_byte(), AesBlock::from(expected));
+    /// ```
+    #[inline]
+    pub fn reverse_bits_per_byte(self) -> Self {
+        // swap adjacent bits, then bit pairs, then nibbles; the masks keep every swap inside its byte
+        unsafe {
+            let x = swap_bits::<1>(self.0, 0x55);
+            let x = swap_bits::<2>(x, 0x33);
+            Self(swap_bits::<4>(x, 0x0f))
+        }
+    }
+
+    /// Rotates column `c` of the state down by `c` positions, moving the byte in row `r` and column `c` to row
+    /// `(r + c) % 4`. This is the column-wise analogue of `ShiftRows`, as used in some AES-based designs.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 7, 4, 5, 6, 10, 11, 8, 9, 13, 14, 15, 12][i]` of `self`
+    #[inline]
+    pub fn rotate_diagonal(self) -> Self {
+        Self(unsafe {
+            _mm_shuffle_epi8(
+                self.0,
+                _mm_loadu_si128(crate::ROTATE_DIAGONAL.as_ptr().cast()),
+            )
+        })
+    }
+
+    /// The inverse of [`AesBlock::rotate_diagonal`], rotating column `c` of the state up by `c` positions.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 5, 6, 7, 4, 10, 11, 8, 9, 15, 12, 13, 14][i]` of `self`
+    #[inline]
+    pub fn inv_rotate_diagonal(self) -> Self {
+        Self(unsafe {
+            _mm_shuffle_epi8(
+                self.0,
+                _mm_loadu_si128(crate::INV_ROTATE_DIAGONAL.as_ptr().cast()),
+            )
+        })
+    }
+
+    /// Performs one round of AES encryption function (`ShiftRows`->`SubBytes`->`MixColumns`->`AddRoundKey`)
+    #[inline]
+    pub fn enc(self, round_key: Self) -> Self {
+        Self(unsafe {
+            _mm_xor_si128(
+                mix_columns(sub_bytes(shuffle(self.0, crate::SHIFT_ROWS))),
+                round_key.0,
+            )
+        })
+    }
+
+    /// Performs one round of AES decryption function (`InvShiftRows`->`InvSubBytes`->`InvMixColumn`s->`AddRoundKey`)
+    #[inline]
+    pub fn dec(self, round_key: Self) -> Self {
+        Self(unsafe {
+            _mm_xor_si128(
+                inv_mix_columns(inv_sub_bytes(shuffle(self.0, crate::INV_SHIFT_ROWS))),
+                round_key.0,
+            )
+        })
+    }
+
+    /// Performs one round of AES encryption function without `MixColumns` (`ShiftRows`->`SubBytes`->`AddRoundKey`)
+    #[inline]
+    pub fn enc_last(self, round_key: Self) -> Self {
+        Self(unsafe { _mm_xor_si128(sub_bytes(shuffle(self.0, crate::SHIFT_ROWS)), round_key.0) })
+    }
+
+    /// Performs one round of AES decryption function without `InvMixColumn`s (`InvShiftRows`->`InvSubBytes`->`AddRoundKey`)
+    #[inline]
+    pub fn dec_last(self, round_key: Self) -> Self {
+        Self(unsafe {
+            _mm_xor_si128(
+                inv_sub_bytes(shuffle(self.0, crate::INV_SHIFT_ROWS)),
+                round_key.0,
+            )
+        })
+    }
+
+    /// Performs the `MixColumns` operation
+    #[inline]
+    pub fn mc(self) -> Self {
+        Self(unsafe { mix_columns(self.0) })
+    }
+
+    /// Performs the `InvMixColumn`s operation
+    #[inline]
+    pub fn imc(self) -> Self {
+        Self(unsafe { inv_mix_columns(self.0) })
+    }
+
+    /// Applies the AES S-box to every byte of the block (the `SubBytes` step on its own), with a single
+    /// `gf2p8affineinv`
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from([0x53; 16]);
+    /// assert_eq!(block.sub_bytes(), AesBlock::from([0xed; 16]));
+    /// ```
+    #[inline]
+    pub fn sub_bytes(self) -> Self {
+        Self(unsafe { sub_bytes(self.0) })
+    }
+
+    /// Applies the inverse AES S-box to every byte of the block (the `InvSubBytes` step on its own), the
+    /// inverse of [`sub_bytes`](Self::sub_bytes)
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from([0xed; 16]);
+    /// assert_eq!(block.inv_sub_bytes(), AesBlock::from([0x53; 16]));
+    /// ```
+    #[inline]
+    pub fn inv_sub_bytes(self) -> Self {
+        Self(unsafe { inv_sub_bytes(self.0) })
+    }
+
+    /// Computes the carryless (GF(2) polynomial) product of the two blocks, treated as big-endian 128-bit
+    /// integers, returning the high and low halves of the 256-bit result
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let (hi, lo) = AesBlock::from(0b11).clmul(AesBlock::from(0b11));
+    /// assert_eq!((u128::from(hi), u128::from(lo)), (0, 0b101));
+    /// ```
+    #[inline]
+    pub fn clmul(self, other: Self) -> (Self, Self) {
+        #[cfg(target_feature = "pclmulqdq")]
+        unsafe {
+            // the bytes are stored big-endian, while the 64-bit lanes are little-endian
+            let bswap = _mm_set_epi64x(0x0001_0203_0405_0607, 0x0809_0a0b_0c0d_0e0f);
+            let a = _mm_shuffle_epi8(self.0, bswap);
+            let b = _mm_shuffle_epi8(other.0, bswap);
+
+            let lo = _mm_clmulepi64_si128::<0x00>(a, b);
+            let hi = _mm_clmulepi64_si128::<0x11>(a, b);
+            let mid = _mm_xor_si128(
+                _mm_clmulepi64_si128::<0x01>(a, b),
+                _mm_clmulepi64_si128::<0x10>(a, b),
+            );
+            let lo = _mm_xor_si128(lo, _mm_bslli_si128::<8>(mid));
+            let hi = _mm_xor_si128(hi, _mm_bsrli_si128::<8>(mid));
+
+            (
+                Self(_mm_shuffle_epi8(hi, bswap)),
+                Self(_mm_shuffle_epi8(lo, bswap)),
+            )
+        }
+        #[cfg(not(target_feature = "pclmulqdq"))]
+        {
+            let (hi, lo) = crate::clmul_soft(self.into(), other.into());
+            (hi.into(), lo.into())
+        }
+    }
+}
+
+#[inline(always)]
+unsafe fn swap_bits<const SHIFT: i32>(vector: __m128i, mask: i8) -> __m128i {
+    let mask = _mm_set1_epi8(mask);
+    let hi = _mm_and_si128(_mm_srli_epi64::<SHIFT>(vector), mask);
+    let lo = _mm_slli_epi64::<SHIFT>(_mm_and_si128(vector, mask));
+    _mm_or_si128(hi, lo)
+}
+
+/// Rotates every column of the state up by one byte, so row `r` gets the byte of row `r + 1`
+const ROTATE_COLUMNS_1: [u8; 16] = [1, 2, 3, 0, 5, 6, 7, 4, 9, 10, 11, 8, 13, 14, 15, 12];
+/// Rotates every column of the state up by two bytes
+const ROTATE_COLUMNS_2: [u8; 16] = [2, 3, 0, 1, 6, 7, 4, 5, 10, 11, 8, 9, 14, 15, 12, 13];
+/// Rotates every column of the state up by three bytes
+const ROTATE_COLUMNS_3: [u8; 16] = [3, 0, 1, 2, 7, 4, 5, 6, 11, 8, 9, 10, 15, 12, 13, 14];
+
+// The bit matrices of the affine transforms, in the row order of `gf2p8affine`: byte `7 - i` of the qword
+// selects the input bits of output bit `i`
+/// The affine transform of the S-box, applied after the inversion, followed by a XOR with `0x63`
+#[allow(clippy::cast_possible_wrap)] // the intrinsics take the bits as an `i64`
+const SBOX_AFFINE: i64 = 0xf1e3_c78f_1f3e_7cf8_u64 as i64;
+/// The inverse of the S-box affine transform, applied before the inversion, followed by a XOR with `0x05`
+#[allow(clippy::cast_possible_wrap)]
+const INV_SBOX_AFFINE: i64 = 0xa449_9225_4a94_2952_u64 as i64;
+/// The identity matrix, to get a bare inversion out of `gf2p8affineinv`
+const IDENTITY: i64 = 0x0102_0408_1020_4080;
+
+#[inline(always)]
+unsafe fn shuffle(vector: __m128i, permutation: [u8; 16]) -> __m128i {
+    _mm_shuffle_epi8(vector, _mm_loadu_si128(permutation.as_ptr().cast()))
+}
+
+/// `SubBytes` in one instruction: `gf2p8affineinv` inverts every byte in GF(2^8) (with the AES polynomial)
+/// and then applies the affine transform
+#[inline(always)]
+unsafe fn sub_bytes(vector: __m128i) -> __m128i {
+    _mm_gf2p8affineinv_epi64_epi8::<0x63>(vector, _mm_set1_epi64x(SBOX_AFFINE))
+}
+
+/// `InvSubBytes`: the inverse affine transform, then the inversion with the identity transform
+#[inline(always)]
+unsafe fn inv_sub_bytes(vector: __m128i) -> __m128i {
+    let vector = _mm_gf2p8affine_epi64_epi8::<0x05>(vector, _mm_set1_epi64x(INV_SBOX_AFFINE));
+    _mm_gf2p8affineinv_epi64_epi8::<0>(vector, _mm_set1_epi64x(IDENTITY))
+}
+
+#[inline(always)]
+unsafe fn gf_mul(vector: __m128i, factor: i8) -> __m128i {
+    _mm_gf2p8mul_epi8(vector, _mm_set1_epi8(factor))
+}
+
+/// `MixColumns`, as `2 * (a[r] ^ a[r + 1]) ^ a[r + 1] ^ a[r + 2] ^ a[r + 3]` for row `r` of every column
+#[inline(always)]
+unsafe fn mix_columns(vector: __m128i) -> __m128i {
+    let rot1 = shuffle(vector, ROTATE_COLUMNS_1);
+    let rot2 = shuffle(vector, ROTATE_COLUMNS_2);
+    let rot3 = shuffle(vector, ROTATE_COLUMNS_3);
+    let doubled = gf_mul(_mm_xor_si128(vector, rot1), 2);
+    _mm_xor_si128(_mm_xor_si128(doubled, rot1), _mm_xor_si128(rot2, rot3))
+}
+
+/// `InvMixColumns`, as `14 * a[r] ^ 11 * a[r + 1] ^ 13 * a[r + 2] ^ 9 * a[r + 3]`
+#[inline(always)]
+unsafe fn inv_mix_columns(vector: __m128i) -> __m128i {
+    let a = gf_mul(vector, 14);
+    let b = shuffle(gf_mul(vector, 11), ROTATE_COLUMNS_1);
+    let c = shuffle(gf_mul(vector, 13), ROTATE_COLUMNS_2);
+    let d = shuffle(gf_mul(vector, 9), ROTATE_COLUMNS_3);
+    _mm_xor_si128(_mm_xor_si128(a, b), _mm_xor_si128(c, d))
+}
+
+// The key expansion follows FIPS-197 word by word, with `SubWord` done by GFNI too, so it is constant-time
+// like the rounds
+
+/// Applies the S-box to every byte of a word
+fn sub_word(word: [u8; 4]) -> [u8; 4] {
+    unsafe {
+        let vector = _mm_cvtsi32_si128(i32::from_ne_bytes(word));
+        _mm_cvtsi128_si32(sub_bytes(vector)).to_ne_bytes()
+    }
+}
+
+/// Expands a key of `NK` words into `N` round keys
+fn keygen<const NK: usize, const N: usize>(key: &[u8]) -> [AesBlock; N] {
+    const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+    let mut words = [[0; 4]; 60];
+    for (word, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+        *word = chunk.try_into().unwrap();
+    }
+    for i in NK..4 * N {
+        let mut temp = words[i - 1];
+        if i % NK == 0 {
+            temp.rotate_left(1);
+            temp = sub_word(temp);
+            temp[0] ^= RCON[i / NK - 1];
+        } else if NK > 6 && i % NK == 4 {
+            temp = sub_word(temp);
+        }
+        for j in 0..4 {
+            words[i][j] = words[i - NK][j] ^ temp[j];
+        }
+    }
+
+    core::array::from_fn(|i| {
+        let mut block = [0; 16];
+        for (chunk, word) in block.chunks_exact_mut(4).zip(&words[4 * i..]) {
+            chunk.copy_from_slice(word);
+        }
+        block.into()
+    })
+}
+
+pub(super) fn keygen_128(key: [u8; 16]) -> [AesBlock; 11] {
+    keygen::<4, 11>(&key)
+}
+
+pub(super) fn keygen_192(key: [u8; 24]) -> [AesBlock; 13] {
+    keygen::<6, 13>(&key)
+}
+
+pub(super) fn keygen_256(key: [u8; 32]) -> [AesBlock; 15] {
+    keygen::<8, 15>(&key)
+}
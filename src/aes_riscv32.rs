@@ -103,6 +103,12 @@ impl AesBlock {
         unsafe { mem::transmute(value) }
     }
 
+    #[inline]
+    #[must_use]
+    pub const fn to_bytes(self) -> [u8; 16] {
+        unsafe { mem::transmute(self) }
+    }
+
     #[inline]
     pub fn store_to(self, dst: &mut [u8]) {
         assert!(dst.len() >= 16);
@@ -123,14 +129,110 @@ impl AesBlock {
     }
 
     #[inline(always)]
-    pub(crate) fn pre_enc(self, round_key: Self) -> Self {
-        outer!(aes32esmi, self, round_key)
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_le_u128(value: u128) -> Self {
+        Self(
+            value as u32,
+            (value >> 32) as u32,
+            (value >> 64) as u32,
+            (value >> 96) as u32,
+        )
+    }
+
+    /// Shifts the block left by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shl_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shl_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) << 16);
+    /// ```
+    #[inline]
+    pub fn shl_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        // the first byte in memory is the most significant one
+        let value = u128::from(self.0)
+            | (u128::from(self.1) << 32)
+            | (u128::from(self.2) << 64)
+            | (u128::from(self.3) << 96);
+        Self::from_le_u128(value >> (8 * N))
+    }
+
+    /// Shifts the block right by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shr_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shr_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) >> 16);
+    /// ```
+    #[inline]
+    pub fn shr_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        let value = u128::from(self.0)
+            | (u128::from(self.1) << 32)
+            | (u128::from(self.2) << 64)
+            | (u128::from(self.3) << 96);
+        Self::from_le_u128(value << (8 * N))
+    }
+
+    /// Reverses the order of the bits within each byte of the block, keeping the order of the bytes
+    /// unchanged. This is the bit-reflection between the GHASH and POLYVAL conventions, and is not the
+    /// same as reversing all 128 bits of the block
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from(*b"\x01\x02\x0f\xf0abcdefghijkl");
+    /// let expected = b"\x01\x02\x0f\xf0abcdefghijkl".map(u8::reverse_bits);
+    /// assert_eq!(block.reverse_bits_per_byte(), AesBlock::from(expected));
+    /// ```
+    #[inline]
+    pub fn reverse_bits_per_byte(self) -> Self {
+        Self::new(self.to_bytes().map(u8::reverse_bits))
+    }
+
+    /// Rotates column `c` of the state down by `c` positions, moving the byte in row `r` and column `c` to row
+    /// `(r + c) % 4`. This is the column-wise analogue of `ShiftRows`, as used in some AES-based designs.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 7, 4, 5, 6, 10, 11, 8, 9, 13, 14, 15, 12][i]` of `self`
+    #[inline]
+    pub fn rotate_diagonal(self) -> Self {
+        let bytes = self.to_bytes();
+        Self::new(crate::ROTATE_DIAGONAL.map(|i| bytes[i as usize]))
+    }
+
+    /// The inverse of [`AesBlock::rotate_diagonal`], rotating column `c` of the state up by `c` positions.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 5, 6, 7, 4, 10, 11, 8, 9, 15, 12, 13, 14][i]` of `self`
+    #[inline]
+    pub fn inv_rotate_diagonal(self) -> Self {
+        let bytes = self.to_bytes();
+        Self::new(crate::INV_ROTATE_DIAGONAL.map(|i| bytes[i as usize]))
     }
 
     /// Performs one round of AES encryption function (`ShiftRows`->`SubBytes`->`MixColumns`->`AddRoundKey`)
     #[inline]
     pub fn enc(self, round_key: Self) -> Self {
-        self.pre_enc(Self::zero()) ^ round_key
+        outer!(aes32esmi, self, round_key)
+    }
+
+    /// Performs one round of AES encryption function with the round key added first, and not at the end
+    /// (`AddRoundKey`->`ShiftRows`->`SubBytes`->`MixColumns`)
+    #[inline]
+    pub fn pre_enc(self, round_key: Self) -> Self {
+        (self ^ round_key).enc(Self::zero())
     }
 
     #[inline(always)]
@@ -144,15 +246,17 @@ impl AesBlock {
         self.pre_enc_last(Self::zero()) ^ round_key
     }
 
-    #[inline(always)]
-    pub(crate) fn pre_dec(self, round_key: Self) -> Self {
+    /// Performs one round of AES decryption function (`InvShiftRows`->`InvSubBytes`->`InvMixColumn`s->`AddRoundKey`)
+    #[inline]
+    pub fn dec(self, round_key: Self) -> Self {
         outer!(aes32dsmi, self, round_key)
     }
 
-    /// Performs one round of AES decryption function (`InvShiftRows`->`InvSubBytes`->`InvMixColumn`s->`AddRoundKey`)
+    /// Performs one round of AES decryption function with the round key added first, and not at the end
+    /// (`AddRoundKey`->`InvShiftRows`->`InvSubBytes`->`InvMixColumn`s)
     #[inline]
-    pub fn dec(self, round_key: Self) -> Self {
-        self.pre_dec(Self::zero()) ^ round_key
+    pub fn pre_dec(self, round_key: Self) -> Self {
+        (self ^ round_key).dec(Self::zero())
     }
 
     #[inline(always)]
@@ -177,6 +281,20 @@ impl AesBlock {
     pub fn imc(self) -> Self {
         self.pre_enc_last(Self::zero()).dec(Self::zero())
     }
+
+    /// Computes the carryless (GF(2) polynomial) product of the two blocks, treated as big-endian 128-bit
+    /// integers, returning the high and low halves of the 256-bit result
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let (hi, lo) = AesBlock::from(0b11).clmul(AesBlock::from(0b11));
+    /// assert_eq!((u128::from(hi), u128::from(lo)), (0, 0b101));
+    /// ```
+    #[inline]
+    pub fn clmul(self, other: Self) -> (Self, Self) {
+        let (hi, lo) = crate::clmul_soft(self.into(), other.into());
+        (hi.into(), lo.into())
+    }
 }
 
 const RCON: [u32; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
@@ -91,6 +91,21 @@ impl AesBlock {
         )
     }
 
+    #[inline]
+    #[must_use]
+    pub const fn to_bytes(self) -> [u8; 16] {
+        let (a, b, c, d) = (
+            self.0.to_be_bytes(),
+            self.1.to_be_bytes(),
+            self.2.to_be_bytes(),
+            self.3.to_be_bytes(),
+        );
+        [
+            a[0], a[1], a[2], a[3], b[0], b[1], b[2], b[3], c[0], c[1], c[2], c[3], d[0], d[1],
+            d[2], d[3],
+        ]
+    }
+
     #[inline]
     pub fn store_to(self, dst: &mut [u8]) {
         assert!(dst.len() >= 16);
@@ -111,9 +126,104 @@ impl AesBlock {
         (self.0 | self.1 | self.2 | self.3) == 0
     }
 
+    #[inline(always)]
+    fn to_be_u128(self) -> u128 {
+        (u128::from(self.0) << 96)
+            | (u128::from(self.1) << 64)
+            | (u128::from(self.2) << 32)
+            | u128::from(self.3)
+    }
+
+    #[inline(always)]
+    fn from_be_u128(value: u128) -> Self {
+        Self(
+            (value >> 96) as u32,
+            (value >> 64) as u32,
+            (value >> 32) as u32,
+            value as u32,
+        )
+    }
+
+    /// Shifts the block left by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shl_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shl_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) << 16);
+    /// ```
+    #[inline]
+    pub fn shl_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        let value = self.to_be_u128();
+        Self::from_be_u128(value << (8 * N))
+    }
+
+    /// Shifts the block right by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shr_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shr_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) >> 16);
+    /// ```
+    #[inline]
+    pub fn shr_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        let value = self.to_be_u128();
+        Self::from_be_u128(value >> (8 * N))
+    }
+
+    /// Reverses the order of the bits within each byte of the block, keeping the order of the bytes
+    /// unchanged. This is the bit-reflection between the GHASH and POLYVAL conventions, and is not the
+    /// same as reversing all 128 bits of the block
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from(*b"\x01\x02\x0f\xf0abcdefghijkl");
+    /// let expected = b"\x01\x02\x0f\xf0abcdefghijkl".map(u8::reverse_bits);
+    /// assert_eq!(block.reverse_bits_per_byte(), AesBlock::from(expected));
+    /// ```
+    #[inline]
+    pub fn reverse_bits_per_byte(self) -> Self {
+        Self::new(self.to_bytes().map(u8::reverse_bits))
+    }
+
+    /// Rotates column `c` of the state down by `c` positions, moving the byte in row `r` and column `c` to row
+    /// `(r + c) % 4`. This is the column-wise analogue of `ShiftRows`, as used in some AES-based designs.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 7, 4, 5, 6, 10, 11, 8, 9, 13, 14, 15, 12][i]` of `self`
+    #[inline]
+    pub fn rotate_diagonal(self) -> Self {
+        let bytes = self.to_bytes();
+        Self::new(crate::ROTATE_DIAGONAL.map(|i| bytes[i as usize]))
+    }
+
+    /// The inverse of [`AesBlock::rotate_diagonal`], rotating column `c` of the state up by `c` positions.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 5, 6, 7, 4, 10, 11, 8, 9, 15, 12, 13, 14][i]` of `self`
+    #[inline]
+    pub fn inv_rotate_diagonal(self) -> Self {
+        let bytes = self.to_bytes();
+        Self::new(crate::INV_ROTATE_DIAGONAL.map(|i| bytes[i as usize]))
+    }
+
     /// Performs one round of AES encryption function (`ShiftRows`->`SubBytes`->`MixColumns`->`AddRoundKey`)
     #[inline]
     pub fn enc(self, round_key: Self) -> Self {
+        touch(&TE0);
         Self(
             te0(self.0 >> 24) ^ te1(self.1 >> 16) ^ te2(self.2 >> 8) ^ te3(self.3) ^ round_key.0,
             te0(self.1 >> 24) ^ te1(self.2 >> 16) ^ te2(self.3 >> 8) ^ te3(self.0) ^ round_key.1,
@@ -125,6 +235,7 @@ impl AesBlock {
     /// Performs one round of AES encryption function without `MixColumns` (`ShiftRows`->`SubBytes`->`AddRoundKey`)
     #[inline]
     pub fn enc_last(self, round_key: Self) -> Self {
+        touch(&TE4);
         Self(
             te4_0(self.0 >> 24)
                 ^ te4_1(self.1 >> 16)
@@ -152,6 +263,7 @@ impl AesBlock {
     /// Performs one round of AES decryption function (`InvShiftRows`->`InvSubBytes`->`InvMixColumn`s->`AddRoundKey`)
     #[inline]
     pub fn dec(self, round_key: Self) -> Self {
+        touch(&TD0);
         Self(
             td0(self.0 >> 24) ^ td1(self.3 >> 16) ^ td2(self.2 >> 8) ^ td3(self.1) ^ round_key.0,
             td0(self.1 >> 24) ^ td1(self.0 >> 16) ^ td2(self.3 >> 8) ^ td3(self.2) ^ round_key.1,
@@ -163,6 +275,7 @@ impl AesBlock {
     /// Performs one round of AES decryption function without `InvMixColumn`s (`InvShiftRows`->`InvSubBytes`->`AddRoundKey`)
     #[inline]
     pub fn dec_last(self, round_key: Self) -> Self {
+        touch(&TD4);
         Self(
             td4_0(self.0 >> 24)
                 ^ td4_1(self.3 >> 16)
@@ -190,6 +303,8 @@ impl AesBlock {
     /// Performs the `MixColumns` operation
     #[inline]
     pub fn mc(self) -> Self {
+        touch(&TD4);
+        touch(&TE0);
         Self(
             te0(td4_3(self.0 >> 24))
                 ^ te1(td4_3(self.0 >> 16))
@@ -213,6 +328,8 @@ impl AesBlock {
     /// Performs the `InvMixColumn`s operation
     #[inline]
     pub fn imc(self) -> Self {
+        touch(&TE4);
+        touch(&TD0);
         Self(
             td0(te4_3(self.0 >> 24))
                 ^ td1(te4_3(self.0 >> 16))
@@ -232,14 +349,31 @@ impl AesBlock {
                 ^ td3(te4_3(self.3)),
         )
     }
+
+    /// Computes the carryless (GF(2) polynomial) product of the two blocks, treated as big-endian 128-bit
+    /// integers, returning the high and low halves of the 256-bit result
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let (hi, lo) = AesBlock::from(0b11).clmul(AesBlock::from(0b11));
+    /// assert_eq!((u128::from(hi), u128::from(lo)), (0, 0b101));
+    /// ```
+    #[inline]
+    pub fn clmul(self, other: Self) -> (Self, Self) {
+        let (hi, lo) = crate::clmul_soft(
+            u128::from_be_bytes(self.to_bytes()),
+            u128::from_be_bytes(other.to_bytes()),
+        );
+        (Self::new(hi.to_be_bytes()), Self::new(lo.to_be_bytes()))
+    }
 }
 
 #[inline(always)]
-fn sub_word(x: u32) -> u32 {
+const fn sub_word(x: u32) -> u32 {
     te4_0(x >> 16) | te4_1(x >> 8) | te4_2(x) | te4_3(x >> 24)
 }
 
-fn keyexp_128<const RCON: u32>(prev_rkey: AesBlock) -> AesBlock {
+const fn keyexp_128<const RCON: u32>(prev_rkey: AesBlock) -> AesBlock {
     let k0 = prev_rkey.0 ^ sub_word(prev_rkey.3) ^ RCON;
     let k1 = prev_rkey.1 ^ k0;
     let k2 = prev_rkey.2 ^ k1;
@@ -247,7 +381,7 @@ fn keyexp_128<const RCON: u32>(prev_rkey: AesBlock) -> AesBlock {
     AesBlock(k0, k1, k2, k3)
 }
 
-fn keyexp_192<const RCON: u32>(prev: [u32; 6]) -> [u32; 6] {
+const fn keyexp_192<const RCON: u32>(prev: [u32; 6]) -> [u32; 6] {
     let k0 = prev[0] ^ sub_word(prev[5]) ^ RCON;
     let k1 = prev[1] ^ k0;
     let k2 = prev[2] ^ k1;
@@ -258,7 +392,7 @@ fn keyexp_192<const RCON: u32>(prev: [u32; 6]) -> [u32; 6] {
     [k0, k1, k2, k3, k4, k5]
 }
 
-fn keyexp_256_1<const RCON: u32>(prev0: AesBlock, prev1: AesBlock) -> AesBlock {
+const fn keyexp_256_1<const RCON: u32>(prev0: AesBlock, prev1: AesBlock) -> AesBlock {
     let k0 = prev0.0 ^ sub_word(prev1.3) ^ RCON;
     let k1 = prev0.1 ^ k0;
     let k2 = prev0.2 ^ k1;
@@ -266,7 +400,7 @@ fn keyexp_256_1<const RCON: u32>(prev0: AesBlock, prev1: AesBlock) -> AesBlock {
     AesBlock(k0, k1, k2, k3)
 }
 
-fn keyexp_256_2(prev0: AesBlock, prev1: AesBlock) -> AesBlock {
+const fn keyexp_256_2(prev0: AesBlock, prev1: AesBlock) -> AesBlock {
     let k0 = prev0.0 ^ sub_word(prev1.3.rotate_right(8));
     let k1 = prev0.1 ^ k0;
     let k2 = prev0.2 ^ k1;
@@ -275,7 +409,23 @@ fn keyexp_256_2(prev0: AesBlock, prev1: AesBlock) -> AesBlock {
 }
 
 pub(super) fn keygen_128(key: [u8; 16]) -> [AesBlock; 11] {
-    let key0 = key.into();
+    touch(&TE4);
+    keygen_128_const(key)
+}
+
+pub(super) fn keygen_192(key: [u8; 24]) -> [AesBlock; 13] {
+    touch(&TE4);
+    keygen_192_const(key)
+}
+
+pub(super) fn keygen_256(key: [u8; 32]) -> [AesBlock; 15] {
+    touch(&TE4);
+    keygen_256_const(key)
+}
+
+/// The key expansion, without touching the table first with `ct-tables`, so that it can run in `const`
+pub(super) const fn keygen_128_const(key: [u8; 16]) -> [AesBlock; 11] {
+    let key0 = AesBlock::new(key);
     let key1 = keyexp_128::<0x01000000>(key0);
     let key2 = keyexp_128::<0x02000000>(key1);
     let key3 = keyexp_128::<0x04000000>(key2);
@@ -292,7 +442,7 @@ pub(super) fn keygen_128(key: [u8; 16]) -> [AesBlock; 11] {
     ]
 }
 
-pub(super) fn keygen_192(key: [u8; 24]) -> [AesBlock; 13] {
+pub(super) const fn keygen_192_const(key: [u8; 24]) -> [AesBlock; 13] {
     let k = [
         load_u32_be(&key, 0),
         load_u32_be(&key, 4),
@@ -328,9 +478,19 @@ pub(super) fn keygen_192(key: [u8; 24]) -> [AesBlock; 13] {
     ]
 }
 
-pub(super) fn keygen_256(key: [u8; 32]) -> [AesBlock; 15] {
-    let key0 = AesBlock::try_from(&key[..16]).unwrap();
-    let key1 = AesBlock::try_from(&key[16..]).unwrap();
+pub(super) const fn keygen_256_const(key: [u8; 32]) -> [AesBlock; 15] {
+    let key0 = AesBlock(
+        load_u32_be(&key, 0),
+        load_u32_be(&key, 4),
+        load_u32_be(&key, 8),
+        load_u32_be(&key, 12),
+    );
+    let key1 = AesBlock(
+        load_u32_be(&key, 16),
+        load_u32_be(&key, 20),
+        load_u32_be(&key, 24),
+        load_u32_be(&key, 28),
+    );
 
     let key2 = keyexp_256_1::<0x01000000>(key0, key1);
     let key3 = keyexp_256_2(key1, key2);
@@ -355,14 +515,20 @@ pub(super) fn keygen_256(key: [u8; 32]) -> [AesBlock; 15] {
 macro_rules! declare {
     (4: $($ename:ident $dname:ident=$mask:literal),*)=>{$(
         #[inline(always)]
-        fn $ename(x: u32) -> u32 {
+        const fn $ename(x: u32) -> u32 {
             TE4[x as u8 as usize] & $mask
         }
         #[inline(always)]
-        fn $dname(x: u32) -> u32 {
+        const fn $dname(x: u32) -> u32 {
             TD4[x as u8 as usize] & $mask
         }
     )*};
+    (rotate: $($name:ident=$base:ident>>$bits:literal),*) => {$(
+        #[inline(always)]
+        fn $name(x: u32) -> u32 {
+            $base(x).rotate_right($bits)
+        }
+    )*};
     ($($name:ident=$table:ident),*) => {$(
         #[inline(always)]
         fn $name(x: u32) -> u32 {
@@ -371,18 +537,33 @@ macro_rules! declare {
     )*};
 }
 
-declare!(
-    te0 = TE0,
-    te1 = TE1,
-    te2 = TE2,
-    te3 = TE3,
-    td0 = TD0,
-    td1 = TD1,
-    td2 = TD2,
-    td3 = TD3
-);
+declare!(te0 = TE0, td0 = TD0);
 declare!(4: te4_0 td4_0=0xff000000, te4_1 td4_1=0xff0000, te4_2 td4_2=0xff00, te4_3 td4_3=0xff);
 
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ct-tables")] {
+        // the other tables are rotations of `TE0` and `TD0`, so only 4 tables (4 KiB) have to be touched
+        declare!(rotate: te1 = te0 >> 8, te2 = te0 >> 16, te3 = te0 >> 24);
+        declare!(rotate: td1 = td0 >> 8, td2 = td0 >> 16, td3 = td0 >> 24);
+
+        /// Reads every cache line of `table` (assuming lines of at least 32 bytes), so that the lookups that
+        /// follow hit the cache whatever their indices are, and the lines that are accessed do not depend on
+        /// the data
+        #[inline(always)]
+        fn touch(table: &[u32; 256]) {
+            // volatile, as the tables never change, so plain loads could be hoisted out of loops or removed
+            for i in (0..256).step_by(8).chain([255]) {
+                unsafe { core::ptr::from_ref(&table[i]).read_volatile() };
+            }
+        }
+    } else {
+        declare!(te1 = TE1, te2 = TE2, te3 = TE3, td1 = TD1, td2 = TD2, td3 = TD3);
+
+        #[inline(always)]
+        fn touch(_: &[u32; 256]) {}
+    }
+}
+
 static TE0: [u32; 256] = [
     0xc66363a5, 0xf87c7c84, 0xee777799, 0xf67b7b8d, 0xfff2f20d, 0xd66b6bbd, 0xde6f6fb1, 0x91c5c554,
     0x60303050, 0x02010103, 0xce6767a9, 0x562b2b7d, 0xe7fefe19, 0xb5d7d762, 0x4dababe6, 0xec76769a,
@@ -418,6 +599,7 @@ static TE0: [u32; 256] = [
     0x824141c3, 0x299999b0, 0x5a2d2d77, 0x1e0f0f11, 0x7bb0b0cb, 0xa85454fc, 0x6dbbbbd6, 0x2c16163a,
 ];
 
+#[cfg(not(feature = "ct-tables"))]
 static TE1: [u32; 256] = [
     0xa5c66363, 0x84f87c7c, 0x99ee7777, 0x8df67b7b, 0x0dfff2f2, 0xbdd66b6b, 0xb1de6f6f, 0x5491c5c5,
     0x50603030, 0x03020101, 0xa9ce6767, 0x7d562b2b, 0x19e7fefe, 0x62b5d7d7, 0xe64dabab, 0x9aec7676,
@@ -453,6 +635,7 @@ static TE1: [u32; 256] = [
     0xc3824141, 0xb0299999, 0x775a2d2d, 0x111e0f0f, 0xcb7bb0b0, 0xfca85454, 0xd66dbbbb, 0x3a2c1616,
 ];
 
+#[cfg(not(feature = "ct-tables"))]
 static TE2: [u32; 256] = [
     0x63a5c663, 0x7c84f87c, 0x7799ee77, 0x7b8df67b, 0xf20dfff2, 0x6bbdd66b, 0x6fb1de6f, 0xc55491c5,
     0x30506030, 0x01030201, 0x67a9ce67, 0x2b7d562b, 0xfe19e7fe, 0xd762b5d7, 0xabe64dab, 0x769aec76,
@@ -488,6 +671,7 @@ static TE2: [u32; 256] = [
     0x41c38241, 0x99b02999, 0x2d775a2d, 0x0f111e0f, 0xb0cb7bb0, 0x54fca854, 0xbbd66dbb, 0x163a2c16,
 ];
 
+#[cfg(not(feature = "ct-tables"))]
 static TE3: [u32; 256] = [
     0x6363a5c6, 0x7c7c84f8, 0x777799ee, 0x7b7b8df6, 0xf2f20dff, 0x6b6bbdd6, 0x6f6fb1de, 0xc5c55491,
     0x30305060, 0x01010302, 0x6767a9ce, 0x2b2b7d56, 0xfefe19e7, 0xd7d762b5, 0xababe64d, 0x76769aec,
@@ -593,6 +777,7 @@ static TD0: [u32; 256] = [
     0x39a80171, 0x080cb3de, 0xd8b4e49c, 0x6456c190, 0x7bcb8461, 0xd532b670, 0x486c5c74, 0xd0b85742,
 ];
 
+#[cfg(not(feature = "ct-tables"))]
 static TD1: [u32; 256] = [
     0x5051f4a7, 0x537e4165, 0xc31a17a4, 0x963a275e, 0xcb3bab6b, 0xf11f9d45, 0xabacfa58, 0x934be303,
     0x552030fa, 0xf6ad766d, 0x9188cc76, 0x25f5024c, 0xfc4fe5d7, 0xd7c52acb, 0x80263544, 0x8fb562a3,
@@ -628,6 +813,7 @@ static TD1: [u32; 256] = [
     0x7139a801, 0xde080cb3, 0x9cd8b4e4, 0x906456c1, 0x617bcb84, 0x70d532b6, 0x74486c5c, 0x42d0b857,
 ];
 
+#[cfg(not(feature = "ct-tables"))]
 static TD2: [u32; 256] = [
     0xa75051f4, 0x65537e41, 0xa4c31a17, 0x5e963a27, 0x6bcb3bab, 0x45f11f9d, 0x58abacfa, 0x03934be3,
     0xfa552030, 0x6df6ad76, 0x769188cc, 0x4c25f502, 0xd7fc4fe5, 0xcbd7c52a, 0x44802635, 0xa38fb562,
@@ -663,6 +849,7 @@ static TD2: [u32; 256] = [
     0x017139a8, 0xb3de080c, 0xe49cd8b4, 0xc1906456, 0x84617bcb, 0xb670d532, 0x5c74486c, 0x5742d0b8,
 ];
 
+#[cfg(not(feature = "ct-tables"))]
 static TD3: [u32; 256] = [
     0xf4a75051, 0x4165537e, 0x17a4c31a, 0x275e963a, 0xab6bcb3b, 0x9d45f11f, 0xfa58abac, 0xe303934b,
     0x30fa5520, 0x766df6ad, 0xcc769188, 0x024c25f5, 0xe5d7fc4f, 0x2acbd7c5, 0x35448026, 0x62a38fb5,
@@ -732,3 +919,51 @@ static TD4: [u32; 256] = [
     0x17171717, 0x2b2b2b2b, 0x04040404, 0x7e7e7e7e, 0xbabababa, 0x77777777, 0xd6d6d6d6, 0x26262626,
     0xe1e1e1e1, 0x69696969, 0x14141414, 0x63636363, 0x55555555, 0x21212121, 0x0c0c0c0c, 0x7d7d7d7d,
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_const_keygen() {
+        // FIPS-197, Appendix A
+        const EXPANDED_128: [AesBlock; 11] =
+            keygen_128_const(0x2b7e151628aed2a6abf7158809cf4f3c_u128.to_be_bytes());
+        const EXPANDED_192: [AesBlock; 13] = keygen_192_const([
+            0x8e, 0x73, 0xb0, 0xf7, 0xda, 0x0e, 0x64, 0x52, 0xc8, 0x10, 0xf3, 0x2b, 0x80, 0x90,
+            0x79, 0xe5, 0x62, 0xf8, 0xea, 0xd2, 0x52, 0x2c, 0x6b, 0x7b,
+        ]);
+        const EXPANDED_256: [AesBlock; 15] = keygen_256_const([
+            0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe, 0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d,
+            0x77, 0x81, 0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7, 0x2d, 0x98, 0x10, 0xa3,
+            0x09, 0x14, 0xdf, 0xf4,
+        ]);
+
+        assert_eq!(
+            EXPANDED_128[10].to_bytes(),
+            0xd014f9a8c9ee2589e13f0cc8b6630ca6_u128.to_be_bytes()
+        );
+        assert_eq!(
+            EXPANDED_192[12].to_bytes(),
+            0xe98ba06f448c773c8ecc720401002202_u128.to_be_bytes()
+        );
+        assert_eq!(
+            EXPANDED_256[14].to_bytes(),
+            0xfe4890d1e6188d0b046df344706c631e_u128.to_be_bytes()
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "ct-tables"))]
+    fn tables_are_rotations() {
+        // `ct-tables` replaces the lookups into these tables with rotations of `TE0` and `TD0`
+        for i in 0..256 {
+            assert_eq!(TE1[i], TE0[i].rotate_right(8));
+            assert_eq!(TE2[i], TE0[i].rotate_right(16));
+            assert_eq!(TE3[i], TE0[i].rotate_right(24));
+            assert_eq!(TD1[i], TD0[i].rotate_right(8));
+            assert_eq!(TD2[i], TD0[i].rotate_right(16));
+            assert_eq!(TD3[i], TD0[i].rotate_right(24));
+        }
+    }
+}
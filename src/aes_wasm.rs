@@ -0,0 +1,692 @@
+use core::arch::wasm32::*;
+use core::ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr};
+use core::{mem, slice};
+
+// This is the same algorithm as the constant-time software implementation, which processes all 16 bytes
+// in parallel with bitwise operations. With SIMD128, the shifts can be done per byte, so the masking that
+// keeps the bits of a `u128` from crossing into the neighbouring byte is done by the instruction itself, and
+// the byte permutations are single shuffles
+
+/// A vector of 16 bytes, where the shifts shift every byte independently
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+struct Bytes(v128);
+
+impl BitAnd for Bytes {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitand(self, rhs: Self) -> Self {
+        Self(v128_and(self.0, rhs.0))
+    }
+}
+
+impl BitOr for Bytes {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(v128_or(self.0, rhs.0))
+    }
+}
+
+impl BitXor for Bytes {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitxor(self, rhs: Self) -> Self {
+        Self(v128_xor(self.0, rhs.0))
+    }
+}
+
+impl Shl<u32> for Bytes {
+    type Output = Self;
+
+    #[inline(always)]
+    fn shl(self, rhs: u32) -> Self {
+        Self(u8x16_shl(self.0, rhs))
+    }
+}
+
+impl Shr<u32> for Bytes {
+    type Output = Self;
+
+    #[inline(always)]
+    fn shr(self, rhs: u32) -> Self {
+        Self(u8x16_shr(self.0, rhs))
+    }
+}
+
+impl Bytes {
+    #[inline(always)]
+    fn wrapping_sub(self, rhs: Self) -> Self {
+        Self(i8x16_sub(self.0, rhs.0))
+    }
+}
+
+#[inline(always)]
+fn rep(x: u8) -> Bytes {
+    Bytes(u8x16_splat(x))
+}
+
+#[inline(always)]
+fn ror1(x: Bytes) -> Bytes {
+    (x >> 1) | (x << 7)
+}
+
+#[inline(always)]
+fn swap2(x: Bytes) -> Bytes {
+    ((x & rep(0xcc)) >> 2) | ((x & rep(0x33)) << 2)
+}
+
+#[inline(always)]
+fn step_a(a: Bytes, b: Bytes, mask: Bytes) -> Bytes {
+    let x = a & b;
+    x ^ ((x & mask) >> 1) ^ ((((a << 1) & b) ^ ((b << 1) & a)) & mask)
+}
+
+#[inline(always)]
+fn step_b(a: Bytes, mask: Bytes) -> Bytes {
+    let x = a & mask;
+    (x | (x >> 1)) ^ ((a << 1) & mask)
+}
+
+fn sub_word(x: u32) -> u32 {
+    u32x4_extract_lane::<0>(subbytes(Bytes(u32x4_splat(x))).0)
+}
+
+fn subbytes(x: Bytes) -> Bytes {
+    let y = ror1(x);
+    let x = (x & rep(0xdd)) ^ (y & rep(0x57));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0x1c));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0x4a));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0x42));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0x64));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0xe0));
+
+    let a1 = x ^ ((x & rep(0xf0)) >> 4);
+    let a2 = swap2(x);
+    let a3 = step_a(x, a1, rep(0xaa));
+    let a4 = step_a(a1, a2, rep(0xaa));
+    let a5 = (a3 & rep(0xcc)) >> 2;
+    let a3 = a3 ^ (((a4 << 2) ^ a4) & rep(0xcc));
+    let a4 = step_b(a5, rep(0x22));
+    let a3 = a3 ^ a4;
+    let a5 = step_b(a3, rep(0xa0));
+    let a4 = a5 & rep(0xc0);
+    let a6 = a4 >> 2;
+    let a4 = a4 ^ ((a5 << 2) & rep(0xc0));
+    let a5 = step_b(a6, rep(0x20));
+    let a4 = a4 | a5;
+    let a3 = (a3 ^ (a4 >> 4)) & rep(0x0f);
+    let a2 = a3 ^ ((a3 & rep(0x0c)) >> 2);
+    let a4 = step_a(a2, a3, rep(0x0a));
+    let a5 = step_b(a4, rep(0x08));
+    let a4 = (a4 ^ (a5 >> 2)) & rep(0x03);
+    let a4 = a4 ^ ((a4 & rep(0x02)) >> 1);
+    let a4 = a4 | (a4 << 2);
+    let a3 = step_a(a2, a4, rep(0x0a));
+    let a3 = a3 | (a3 << 4);
+    let a2 = swap2(a1);
+    let x = step_a(a1, a3, rep(0xaa));
+    let a4 = step_a(a2, a3, rep(0xaa));
+    let a5 = (x & rep(0xcc)) >> 2;
+    let x = x ^ (((a4 << 2) ^ a4) & rep(0xcc));
+    let a4 = step_b(a5, rep(0x22));
+    let x = x ^ a4;
+
+    let y = ror1(x);
+    let x = (x & rep(0x39)) ^ (y & rep(0x3f));
+    let y = ((y & rep(0xfc)) >> 2) | ((y & rep(0x03)) << 6);
+    let x = x ^ (y & rep(0x97));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0x9b));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0x3c));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0xdd));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0x72));
+
+    x ^ rep(0x63)
+}
+
+fn invsubbytes(x: Bytes) -> Bytes {
+    let x = x ^ rep(0x63);
+    let y = ror1(x);
+    let x = (x & rep(0xfd)) ^ (y & rep(0x5e));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0xf3));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0xf5));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0x78));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0x77));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0x15));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0xa5));
+
+    let a1 = x ^ ((x & rep(0xf0)) >> 4);
+    let a2 = swap2(x);
+    let a3 = step_a(x, a1, rep(0xaa));
+    let a4 = step_a(a1, a2, rep(0xaa));
+    let a5 = (a3 & rep(0xcc)) >> 2;
+    let a3 = a3 ^ (((a4 << 2) ^ a4) & rep(0xcc));
+    let a4 = step_b(a5, rep(0x22));
+    let a3 = a3 ^ a4;
+    let a5 = step_b(a3, rep(0xa0));
+    let a4 = a5 & rep(0xc0);
+    let a6 = a4 >> 2;
+    let a4 = a4 ^ ((a5 << 2) & rep(0xc0));
+    let a5 = step_b(a6, rep(0x20));
+    let a4 = a4 | a5;
+    let a3 = (a3 ^ (a4 >> 4)) & rep(0x0f);
+    let a2 = a3 ^ ((a3 & rep(0x0c)) >> 2);
+    let a4 = step_a(a2, a3, rep(0x0a));
+    let a5 = step_b(a4, rep(0x08));
+    let a4 = (a4 ^ (a5 >> 2)) & rep(0x03);
+    let a4 = a4 ^ ((a4 & rep(0x02)) >> 1);
+    let a4 = a4 | (a4 << 2);
+    let a3 = step_a(a2, a4, rep(0x0a));
+    let a3 = a3 | (a3 << 4);
+    let a2 = swap2(a1);
+    let x = step_a(a1, a3, rep(0xaa));
+    let a4 = step_a(a2, a3, rep(0xaa));
+    let a5 = (x & rep(0xcc)) >> 2;
+    let x = x ^ (((a4 << 2) ^ a4) & rep(0xcc));
+    let a4 = step_b(a5, rep(0x22));
+    let x = x ^ a4;
+
+    let y = ror1(x);
+    let x = (x & rep(0xb5)) ^ (y & rep(0x40));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0x80));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0x16));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0xeb));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0x97));
+    let y = ror1(y);
+    let x = x ^ (y & rep(0xfb));
+    let y = ror1(y);
+
+    x ^ (y & rep(0x7d))
+}
+
+#[inline(always)]
+fn shiftrows(state: Bytes) -> Bytes {
+    Bytes(i8x16_shuffle::<
+        0,
+        5,
+        10,
+        15,
+        4,
+        9,
+        14,
+        3,
+        8,
+        13,
+        2,
+        7,
+        12,
+        1,
+        6,
+        11,
+    >(state.0, state.0))
+}
+
+#[inline(always)]
+fn invshiftrows(state: Bytes) -> Bytes {
+    Bytes(i8x16_shuffle::<
+        0,
+        13,
+        10,
+        7,
+        4,
+        1,
+        14,
+        11,
+        8,
+        5,
+        2,
+        15,
+        12,
+        9,
+        6,
+        3,
+    >(state.0, state.0))
+}
+
+#[inline(always)]
+fn xtime(a: Bytes) -> Bytes {
+    let b = a & rep(0x80);
+    let a = a ^ b;
+    let b = b.wrapping_sub(b >> 7) & rep(0x1b);
+    b ^ (a << 1)
+}
+
+#[inline(always)]
+fn swap16(x: Bytes) -> Bytes {
+    Bytes(i8x16_shuffle::<
+        2,
+        3,
+        0,
+        1,
+        6,
+        7,
+        4,
+        5,
+        10,
+        11,
+        8,
+        9,
+        14,
+        15,
+        12,
+        13,
+    >(x.0, x.0))
+}
+
+#[inline(always)]
+fn swap8(x: Bytes) -> Bytes {
+    Bytes(i8x16_shuffle::<
+        1,
+        0,
+        3,
+        2,
+        5,
+        4,
+        7,
+        6,
+        9,
+        8,
+        11,
+        10,
+        13,
+        12,
+        15,
+        14,
+    >(x.0, x.0))
+}
+
+#[inline(always)]
+fn ror8_32(x: Bytes) -> Bytes {
+    Bytes(i8x16_shuffle::<
+        1,
+        2,
+        3,
+        0,
+        5,
+        6,
+        7,
+        4,
+        9,
+        10,
+        11,
+        8,
+        13,
+        14,
+        15,
+        12,
+    >(x.0, x.0))
+}
+
+fn mixcolumns(state: Bytes) -> Bytes {
+    let s = state ^ swap16(state);
+    let s = s ^ swap8(s) ^ state;
+    let t = xtime(state);
+
+    s ^ t ^ ror8_32(t)
+}
+
+fn invmixcolumns(state: Bytes) -> Bytes {
+    let s = state ^ swap16(state);
+    let s = s ^ swap8(s) ^ state;
+
+    let t = xtime(state);
+    let s = s ^ t ^ ror8_32(t);
+    let t = xtime(t);
+    let t = t ^ swap16(t);
+    let s = s ^ t;
+    let t = xtime(t);
+
+    s ^ t ^ swap8(t)
+}
+
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+#[must_use]
+pub struct AesBlock(v128);
+
+impl PartialEq for AesBlock {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        (*self ^ *other).is_zero()
+    }
+}
+
+impl Eq for AesBlock {}
+
+impl From<[u8; 16]> for AesBlock {
+    #[inline]
+    fn from(value: [u8; 16]) -> Self {
+        Self::new(value)
+    }
+}
+
+impl BitAnd for AesBlock {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(v128_and(self.0, rhs.0))
+    }
+}
+
+impl BitOr for AesBlock {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(v128_or(self.0, rhs.0))
+    }
+}
+
+impl BitXor for AesBlock {
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self(v128_xor(self.0, rhs.0))
+    }
+}
+
+impl Not for AesBlock {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        Self(v128_not(self.0))
+    }
+}
+
+impl AesBlock {
+    #[inline]
+    pub const fn new(value: [u8; 16]) -> Self {
+        // using transmute in simd is safe
+        unsafe { mem::transmute(value) }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn to_bytes(self) -> [u8; 16] {
+        // using transmute in simd is safe
+        unsafe { mem::transmute(self) }
+    }
+
+    #[inline]
+    pub fn store_to(self, dst: &mut [u8]) {
+        assert!(dst.len() >= 16);
+        unsafe { v128_store(dst.as_mut_ptr().cast(), self.0) };
+    }
+
+    #[inline]
+    pub fn zero() -> Self {
+        Self(u64x2_splat(0))
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_zero(self) -> bool {
+        !v128_any_true(self.0)
+    }
+
+    /// Shifts the block left by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shl_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shl_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) << 16);
+    /// ```
+    #[inline]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn shl_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        // the first byte in memory is the most significant one, and `swizzle` gives zero for the indices
+        // past the end
+        let indices: [u8; 16] = core::array::from_fn(|i| i as u8 + N as u8);
+        Self(u8x16_swizzle(self.0, Self::new(indices).0))
+    }
+
+    /// Shifts the block right by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shr_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shr_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) >> 16);
+    /// ```
+    #[inline]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn shr_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        let indices: [u8; 16] = core::array::from_fn(|i| (i as u8).wrapping_sub(N as u8));
+        Self(u8x16_swizzle(self.0, Self::new(indices).0))
+    }
+
+    /// Reverses the order of the bits within each byte of the block, keeping the order of the bytes
+    /// unchanged. This is the bit-reflection between the GHASH and POLYVAL conventions, and is not the
+    /// same as reversing all 128 bits of the block
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from(*b"\x01\x02\x0f\xf0abcdefghijkl");
+    /// let expected = b"\x01\x02\x0f\xf0abcdefghijkl".map(u8::reverse_bits);
+    /// assert_eq!(block.reverse_bits_per_byte(), AesBlock::from(expected));
+    /// ```
+    #[inline]
+    pub fn reverse_bits_per_byte(self) -> Self {
+        // swap adjacent bits, then bit pairs, then nibbles, with shifts that stay inside every byte
+        let x = Bytes(self.0);
+        let x = ((x >> 1) & rep(0x55)) | ((x & rep(0x55)) << 1);
+        let x = ((x >> 2) & rep(0x33)) | ((x & rep(0x33)) << 2);
+        Self(((x >> 4) | (x << 4)).0)
+    }
+
+    /// Rotates column `c` of the state down by `c` positions, moving the byte in row `r` and column `c` to row
+    /// `(r + c) % 4`. This is the column-wise analogue of `ShiftRows`, as used in some AES-based designs.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 7, 4, 5, 6, 10, 11, 8, 9, 13, 14, 15, 12][i]` of `self`
+    #[inline]
+    pub fn rotate_diagonal(self) -> Self {
+        Self(u8x16_swizzle(self.0, Self::new(crate::ROTATE_DIAGONAL).0))
+    }
+
+    /// The inverse of [`AesBlock::rotate_diagonal`], rotating column `c` of the state up by `c` positions.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 5, 6, 7, 4, 10, 11, 8, 9, 15, 12, 13, 14][i]` of `self`
+    #[inline]
+    pub fn inv_rotate_diagonal(self) -> Self {
+        Self(u8x16_swizzle(
+            self.0,
+            Self::new(crate::INV_ROTATE_DIAGONAL).0,
+        ))
+    }
+
+    /// Performs one round of AES encryption function (`ShiftRows`->`SubBytes`->`MixColumns`->`AddRoundKey`)
+    #[inline]
+    pub fn enc(self, round_key: Self) -> Self {
+        Self(mixcolumns(subbytes(shiftrows(Bytes(self.0)))).0) ^ round_key
+    }
+
+    /// Performs one round of AES decryption function (`InvShiftRows`->`InvSubBytes`->`InvMixColumn`s->`AddRoundKey`)
+    #[inline]
+    pub fn dec(self, round_key: Self) -> Self {
+        Self(invmixcolumns(invsubbytes(invshiftrows(Bytes(self.0)))).0) ^ round_key
+    }
+
+    /// Performs one round of AES encryption function without `MixColumns` (`ShiftRows`->`SubBytes`->`AddRoundKey`)
+    #[inline]
+    pub fn enc_last(self, round_key: Self) -> Self {
+        Self(subbytes(shiftrows(Bytes(self.0))).0) ^ round_key
+    }
+
+    /// Performs one round of AES decryption function without `InvMixColumn`s (`InvShiftRows`->`InvSubBytes`->`AddRoundKey`)
+    #[inline]
+    pub fn dec_last(self, round_key: Self) -> Self {
+        Self(invsubbytes(invshiftrows(Bytes(self.0))).0) ^ round_key
+    }
+
+    /// Performs the `MixColumns` operation
+    #[inline]
+    pub fn mc(self) -> Self {
+        Self(mixcolumns(Bytes(self.0)).0)
+    }
+
+    /// Performs the `InvMixColumn`s operation
+    #[inline]
+    pub fn imc(self) -> Self {
+        Self(invmixcolumns(Bytes(self.0)).0)
+    }
+
+    /// Computes the carryless (GF(2) polynomial) product of the two blocks, treated as big-endian 128-bit
+    /// integers, returning the high and low halves of the 256-bit result
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let (hi, lo) = AesBlock::from(0b11).clmul(AesBlock::from(0b11));
+    /// assert_eq!((u128::from(hi), u128::from(lo)), (0, 0b101));
+    /// ```
+    #[inline]
+    pub fn clmul(self, other: Self) -> (Self, Self) {
+        let (hi, lo) = crate::clmul_soft(self.into(), other.into());
+        (hi.into(), lo.into())
+    }
+}
+
+// wasm is little-endian, so the round constant is the low byte of the column, and `RotWord` is a right
+// rotation
+const RCON: [u32; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+#[inline(always)]
+const fn rot_word(x: u32) -> u32 {
+    x.rotate_right(8)
+}
+
+pub(super) fn keygen_128(key: [u8; 16]) -> [AesBlock; 11] {
+    let mut expanded_keys: [AesBlock; 11] = unsafe { mem::zeroed() };
+    let columns = unsafe { slice::from_raw_parts_mut(expanded_keys.as_mut_ptr().cast(), 44) };
+
+    for (i, chunk) in key.chunks_exact(4).enumerate() {
+        columns[i] = u32::from_ne_bytes(chunk.try_into().unwrap());
+    }
+
+    for i in (0..40).step_by(4) {
+        columns[i + 4] = columns[i + 0] ^ rot_word(sub_word(columns[i + 3])) ^ RCON[i / 4];
+        columns[i + 5] = columns[i + 1] ^ columns[i + 4];
+        columns[i + 6] = columns[i + 2] ^ columns[i + 5];
+        columns[i + 7] = columns[i + 3] ^ columns[i + 6];
+    }
+
+    expanded_keys
+}
+
+pub(super) fn keygen_192(key: [u8; 24]) -> [AesBlock; 13] {
+    let mut expanded_keys: [AesBlock; 13] = unsafe { mem::zeroed() };
+    let columns = unsafe { slice::from_raw_parts_mut(expanded_keys.as_mut_ptr().cast(), 52) };
+
+    for (i, chunk) in key.chunks_exact(4).enumerate() {
+        columns[i] = u32::from_ne_bytes(chunk.try_into().unwrap());
+    }
+
+    for i in (0..42).step_by(6) {
+        columns[i + 6] = columns[i + 0] ^ rot_word(sub_word(columns[i + 5])) ^ RCON[i / 6];
+        columns[i + 7] = columns[i + 1] ^ columns[i + 6];
+        columns[i + 8] = columns[i + 2] ^ columns[i + 7];
+        columns[i + 9] = columns[i + 3] ^ columns[i + 8];
+        columns[i + 10] = columns[i + 4] ^ columns[i + 9];
+        columns[i + 11] = columns[i + 5] ^ columns[i + 10];
+    }
+
+    columns[48] = columns[42] ^ rot_word(sub_word(columns[47])) ^ RCON[7];
+    columns[49] = columns[43] ^ columns[48];
+    columns[50] = columns[44] ^ columns[49];
+    columns[51] = columns[45] ^ columns[50];
+
+    expanded_keys
+}
+
+pub(super) fn keygen_256(key: [u8; 32]) -> [AesBlock; 15] {
+    let mut expanded_keys: [AesBlock; 15] = unsafe { mem::zeroed() };
+    let columns = unsafe { slice::from_raw_parts_mut(expanded_keys.as_mut_ptr().cast(), 60) };
+
+    for (i, chunk) in key.chunks_exact(4).enumerate() {
+        columns[i] = u32::from_ne_bytes(chunk.try_into().unwrap());
+    }
+
+    for i in (0..48).step_by(8) {
+        columns[i + 8] = columns[i + 0] ^ rot_word(sub_word(columns[i + 7])) ^ RCON[i / 8];
+        columns[i + 9] = columns[i + 1] ^ columns[i + 8];
+        columns[i + 10] = columns[i + 2] ^ columns[i + 9];
+        columns[i + 11] = columns[i + 3] ^ columns[i + 10];
+        columns[i + 12] = columns[i + 4] ^ sub_word(columns[i + 11]);
+        columns[i + 13] = columns[i + 5] ^ columns[i + 12];
+        columns[i + 14] = columns[i + 6] ^ columns[i + 13];
+        columns[i + 15] = columns[i + 7] ^ columns[i + 14];
+    }
+
+    columns[56] = columns[48] ^ rot_word(sub_word(columns[55])) ^ RCON[6];
+    columns[57] = columns[49] ^ columns[56];
+    columns[58] = columns[50] ^ columns[57];
+    columns[59] = columns[51] ^ columns[58];
+
+    expanded_keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subbytes() {
+        let x = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+        let r = AesBlock(subbytes(Bytes(AesBlock::new(x).0)).0).to_bytes();
+        let e = [
+            0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7,
+            0xab, 0x76,
+        ];
+        assert_eq!(r, e);
+    }
+
+    #[test]
+    fn test_invsubbytes() {
+        let x = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+        let r = AesBlock(invsubbytes(Bytes(AesBlock::new(x).0)).0).to_bytes();
+        let e = [
+            0x52, 0x09, 0x6a, 0xd5, 0x30, 0x36, 0xa5, 0x38, 0xbf, 0x40, 0xa3, 0x9e, 0x81, 0xf3,
+            0xd7, 0xfb,
+        ];
+        assert_eq!(r, e);
+    }
+}
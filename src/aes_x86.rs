@@ -68,6 +68,13 @@ impl AesBlock {
         unsafe { core::mem::transmute(value) }
     }
 
+    #[inline]
+    #[must_use]
+    pub const fn to_bytes(self) -> [u8; 16] {
+        // using transmute in simd is safe
+        unsafe { core::mem::transmute(self) }
+    }
+
     #[inline]
     pub fn store_to(self, dst: &mut [u8]) {
         assert!(dst.len() >= 16);
@@ -85,6 +92,94 @@ impl AesBlock {
         unsafe { _mm_testz_si128(self.0, self.0) == 1 }
     }
 
+    /// Shifts the block left by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shl_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shl_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) << 16);
+    /// ```
+    #[inline]
+    pub fn shl_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        // the first byte in memory is the most significant one
+        Self(unsafe { _mm_bsrli_si128::<N>(self.0) })
+    }
+
+    /// Shifts the block right by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shr_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shr_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) >> 16);
+    /// ```
+    #[inline]
+    pub fn shr_bytes<const N: i32>(self) -> Self {
+        assert!(N >= 0);
+        if N >= 16 {
+            return Self::zero();
+        }
+        Self(unsafe { _mm_bslli_si128::<N>(self.0) })
+    }
+
+    /// Reverses the order of the bits within each byte of the block, keeping the order of the bytes
+    /// unchanged. This is the bit-reflection between the GHASH and POLYVAL conventions, and is not the
+    /// same as reversing all 128 bits of the block
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from(*b"\x01\x02\x0f\xf0abcdefghijkl");
+    /// let expected = b"\x01\x02\x0f\xf0abcdefghijkl".map(u8::reverse_bits);
+    /// assert_eq!(block.reverse_bits_per_byte(), AesBlock::from(expected));
+    /// ```
+    #[inline]
+    pub fn reverse_bits_per_byte(self) -> Self {
+        // swap adjacent bits, then bit pairs, then nibbles; the masks keep every swap inside its byte
+        unsafe {
+            let x = swap_bits::<1>(self.0, 0x55);
+            let x = swap_bits::<2>(x, 0x33);
+            Self(swap_bits::<4>(x, 0x0f))
+        }
+    }
+
+    /// Rotates column `c` of the state down by `c` positions, moving the byte in row `r` and column `c` to row
+    /// `(r + c) % 4`. This is the column-wise analogue of `ShiftRows`, as used in some AES-based designs.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 7, 4, 5, 6, 10, 11, 8, 9, 13, 14, 15, 12][i]` of `self`
+    #[inline]
+    pub fn rotate_diagonal(self) -> Self {
+        Self(unsafe {
+            _mm_shuffle_epi8(
+                self.0,
+                _mm_loadu_si128(crate::ROTATE_DIAGONAL.as_ptr().cast()),
+            )
+        })
+    }
+
+    /// The inverse of [`AesBlock::rotate_diagonal`], rotating column `c` of the state up by `c` positions.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 5, 6, 7, 4, 10, 11, 8, 9, 15, 12, 13, 14][i]` of `self`
+    #[inline]
+    pub fn inv_rotate_diagonal(self) -> Self {
+        Self(unsafe {
+            _mm_shuffle_epi8(
+                self.0,
+                _mm_loadu_si128(crate::INV_ROTATE_DIAGONAL.as_ptr().cast()),
+            )
+        })
+    }
+
     /// Performs one round of AES encryption function (`ShiftRows`->`SubBytes`->`MixColumns`->`AddRoundKey`)
     #[inline]
     pub fn enc(self, round_key: Self) -> Self {
@@ -125,6 +220,52 @@ impl AesBlock {
     pub fn imc(self) -> Self {
         Self(unsafe { _mm_aesimc_si128(self.0) })
     }
+
+    /// Computes the carryless (GF(2) polynomial) product of the two blocks, treated as big-endian 128-bit
+    /// integers, returning the high and low halves of the 256-bit result
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let (hi, lo) = AesBlock::from(0b11).clmul(AesBlock::from(0b11));
+    /// assert_eq!((u128::from(hi), u128::from(lo)), (0, 0b101));
+    /// ```
+    #[inline]
+    pub fn clmul(self, other: Self) -> (Self, Self) {
+        #[cfg(target_feature = "pclmulqdq")]
+        unsafe {
+            // the bytes are stored big-endian, while the 64-bit lanes are little-endian
+            let bswap = _mm_set_epi64x(0x0001_0203_0405_0607, 0x0809_0a0b_0c0d_0e0f);
+            let a = _mm_shuffle_epi8(self.0, bswap);
+            let b = _mm_shuffle_epi8(other.0, bswap);
+
+            let lo = _mm_clmulepi64_si128::<0x00>(a, b);
+            let hi = _mm_clmulepi64_si128::<0x11>(a, b);
+            let mid = _mm_xor_si128(
+                _mm_clmulepi64_si128::<0x01>(a, b),
+                _mm_clmulepi64_si128::<0x10>(a, b),
+            );
+            let lo = _mm_xor_si128(lo, _mm_bslli_si128::<8>(mid));
+            let hi = _mm_xor_si128(hi, _mm_bsrli_si128::<8>(mid));
+
+            (
+                Self(_mm_shuffle_epi8(hi, bswap)),
+                Self(_mm_shuffle_epi8(lo, bswap)),
+            )
+        }
+        #[cfg(not(target_feature = "pclmulqdq"))]
+        {
+            let (hi, lo) = crate::clmul_soft(self.into(), other.into());
+            (hi.into(), lo.into())
+        }
+    }
+}
+
+#[inline(always)]
+unsafe fn swap_bits<const SHIFT: i32>(vector: __m128i, mask: i8) -> __m128i {
+    let mask = _mm_set1_epi8(mask);
+    let hi = _mm_and_si128(_mm_srli_epi64::<SHIFT>(vector), mask);
+    let lo = _mm_slli_epi64::<SHIFT>(_mm_and_si128(vector, mask));
+    _mm_or_si128(hi, lo)
 }
 
 #[inline(always)]
@@ -0,0 +1,625 @@
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+use core::ops::{BitAnd, BitOr, BitXor, Not};
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicU8, Ordering};
+
+#[cfg(feature = "constant-time")]
+use crate::aes_bitslice as soft;
+#[cfg(not(feature = "constant-time"))]
+use crate::aes_table_based as soft;
+
+// The block is kept in an SSE2 register, which is part of the baseline of every x86 target this is compiled
+// for. Whether the CPU has AES-NI is checked once, then read from the cache. The ciphers check it once per
+// call, and then run all the rounds either in a `#[target_feature]` function or with the software
+// implementation, and the public round functions check it on every call. The key expansion always uses the
+// software implementation, as it is not performance-critical
+
+const UNKNOWN: u8 = 0;
+const ABSENT: u8 = 1;
+const PRESENT: u8 = 2;
+
+static AESNI: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+/// The hook registered with [`set_backend_hook`], as a type-erased function pointer (null if none)
+static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+/// Whether the hook has been called
+static HOOK_CALLED: AtomicBool = AtomicBool::new(false);
+
+const SOFT_IMPL: &str = if cfg!(feature = "constant-time") {
+    "Constant-time Software AES"
+} else {
+    "Software AES"
+};
+
+/// Whether the CPU supports AES-NI, detected with `cpuid` on the first call
+#[inline]
+pub(crate) fn has_aesni() -> bool {
+    match AESNI.load(Ordering::Relaxed) {
+        UNKNOWN => detect(),
+        state => state == PRESENT,
+    }
+}
+
+fn cpu_has_aesni() -> bool {
+    // bit 25 of ecx in leaf 1 is the AES-NI flag. Every CPU with SSE2 has `cpuid` and leaf 1
+    #[allow(unused_unsafe)] // `__cpuid` is safe since Rust 1.87
+    let present = unsafe { __cpuid(1) }.ecx & (1 << 25) != 0;
+    present
+}
+
+#[cold]
+fn detect() -> bool {
+    let present = cpu_has_aesni();
+    AESNI.store(if present { PRESENT } else { ABSENT }, Ordering::SeqCst);
+    call_hook();
+    present
+}
+
+/// Calls the hook if one is registered and it has not been called yet. Both the detection and the
+/// registration call this after publishing their side, so whichever comes second sees the other, and the
+/// hook is called exactly once
+fn call_hook() {
+    let hook = HOOK.load(Ordering::SeqCst);
+    let state = AESNI.load(Ordering::SeqCst);
+    if hook.is_null() || state == UNKNOWN || HOOK_CALLED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    // only `set_backend_hook` stores to `HOOK`, and it only stores a `fn(&'static str)`
+    let hook: fn(&'static str) = unsafe { core::mem::transmute(hook) };
+    hook(if state == PRESENT {
+        "AES-NI"
+    } else {
+        SOFT_IMPL
+    });
+}
+
+/// Registers a function that is called once with the name of the implementation that runtime detection
+/// selects for [`AesBlock`] (`"AES-NI"`, or the software implementation with the same name as in
+/// [`backend_info`](crate::backend_info)), e.g. to log it or to assert that AES-NI is used in production.
+///
+/// Detection happens on the first AES operation. If it already happened, the hook is called right away,
+/// from this function; otherwise it is called from whichever thread triggers detection. Only the first
+/// registered hook is kept, later ones are ignored and `false` is returned. This only exists with the
+/// `runtime-detect` feature, and costs nothing when no hook is registered
+///
+/// ```
+/// # use aes_crypto::{set_backend_hook, Aes128Enc, AesBlock, AesEncrypt};
+/// fn log_backend(name: &'static str) {
+///     std::println!("AES backend: {name}");
+/// }
+///
+/// set_backend_hook(log_backend);
+/// Aes128Enc::from([0; 16]).encrypt_block(AesBlock::zero());
+/// ```
+pub fn set_backend_hook(hook: fn(&'static str)) -> bool {
+    let registered = HOOK
+        .compare_exchange(
+            ptr::null_mut(),
+            hook as *mut (),
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        )
+        .is_ok();
+    if registered {
+        call_hook();
+    }
+    registered
+}
+
+/// Overrides the detection result, to test both paths on the same machine
+#[cfg(test)]
+fn force_aesni(present: bool) {
+    AESNI.store(if present { PRESENT } else { ABSENT }, Ordering::Relaxed);
+}
+
+/// Runs the whole cipher on `blocks`: the first round key is added, the middle ones are used for full rounds,
+/// and the last one for the final round. `lane` gets the round key out of an element of `round_keys`. This
+/// checks for AES-NI once for all the rounds
+#[inline]
+fn encrypt<const N: usize, K: Copy>(
+    blocks: [AesBlock; N],
+    round_keys: &[K],
+    lane: impl Fn(K) -> AesBlock,
+) -> [AesBlock; N] {
+    if has_aesni() {
+        unsafe { encrypt_aesni(blocks, round_keys, lane) }
+    } else {
+        let (first, rest) = round_keys.split_first().unwrap();
+        let (last, middle) = rest.split_last().unwrap();
+        blocks.map(|block| {
+            let mut acc = block.soft() ^ lane(*first).soft();
+            for &key in middle {
+                acc = acc.enc(lane(key).soft());
+            }
+            AesBlock::from_soft(acc.enc_last(lane(*last).soft()))
+        })
+    }
+}
+
+/// Like [`encrypt`], with the decryption rounds
+#[inline]
+fn decrypt<const N: usize, K: Copy>(
+    blocks: [AesBlock; N],
+    round_keys: &[K],
+    lane: impl Fn(K) -> AesBlock,
+) -> [AesBlock; N] {
+    if has_aesni() {
+        unsafe { decrypt_aesni(blocks, round_keys, lane) }
+    } else {
+        let (first, rest) = round_keys.split_first().unwrap();
+        let (last, middle) = rest.split_last().unwrap();
+        blocks.map(|block| {
+            let mut acc = block.soft() ^ lane(*first).soft();
+            for &key in middle {
+                acc = acc.dec(lane(key).soft());
+            }
+            AesBlock::from_soft(acc.dec_last(lane(*last).soft()))
+        })
+    }
+}
+
+// the rounds of the blocks are interleaved, so that the blocks are independent in flight
+
+#[target_feature(enable = "aes")]
+unsafe fn encrypt_aesni<const N: usize, K: Copy>(
+    mut blocks: [AesBlock; N],
+    round_keys: &[K],
+    lane: impl Fn(K) -> AesBlock,
+) -> [AesBlock; N] {
+    let (first, rest) = round_keys.split_first().unwrap();
+    let (last, middle) = rest.split_last().unwrap();
+    for block in &mut blocks {
+        block.0 = _mm_xor_si128(block.0, lane(*first).0);
+    }
+    for &key in middle {
+        let key = lane(key).0;
+        for block in &mut blocks {
+            block.0 = _mm_aesenc_si128(block.0, key);
+        }
+    }
+    for block in &mut blocks {
+        block.0 = _mm_aesenclast_si128(block.0, lane(*last).0);
+    }
+    blocks
+}
+
+#[target_feature(enable = "aes")]
+unsafe fn decrypt_aesni<const N: usize, K: Copy>(
+    mut blocks: [AesBlock; N],
+    round_keys: &[K],
+    lane: impl Fn(K) -> AesBlock,
+) -> [AesBlock; N] {
+    let (first, rest) = round_keys.split_first().unwrap();
+    let (last, middle) = rest.split_last().unwrap();
+    for block in &mut blocks {
+        block.0 = _mm_xor_si128(block.0, lane(*first).0);
+    }
+    for &key in middle {
+        let key = lane(key).0;
+        for block in &mut blocks {
+            block.0 = _mm_aesdec_si128(block.0, key);
+        }
+    }
+    for block in &mut blocks {
+        block.0 = _mm_aesdeclast_si128(block.0, lane(*last).0);
+    }
+    blocks
+}
+
+impl AesBlock {
+    /// Computes `self.chain_enc(&keys[..keys.len() - 1]).enc_last(keys[keys.len() - 1])`, checking for AES-NI
+    /// once rather than in every round
+    #[inline]
+    pub(crate) fn cipher_enc(self, keys: &[Self]) -> Self {
+        encrypt([self], keys, |key| key)[0]
+    }
+
+    /// Computes `self.chain_dec(&keys[..keys.len() - 1]).dec_last(keys[keys.len() - 1])`, checking for AES-NI
+    /// once rather than in every round
+    #[inline]
+    pub(crate) fn cipher_dec(self, keys: &[Self]) -> Self {
+        decrypt([self], keys, |key| key)[0]
+    }
+}
+
+macro_rules! impl_cipher {
+    ($($name:ty, ($($lane:ident),*)),*) => {$(
+        impl $name {
+            /// Like [`AesBlock::cipher_enc`] on every lane. Every lane of a round key must be the same
+            #[inline]
+            pub(crate) fn cipher_enc(self, keys: &[Self]) -> Self {
+                let ($($lane),*) = self.into();
+                let [$($lane),*] = encrypt([$($lane),*], keys, |key| {
+                    let ($($lane),*) = key.into();
+                    [$($lane),*][0]
+                });
+                ($($lane),*).into()
+            }
+
+            /// Like [`AesBlock::cipher_dec`] on every lane. Every lane of a round key must be the same
+            #[inline]
+            pub(crate) fn cipher_dec(self, keys: &[Self]) -> Self {
+                let ($($lane),*) = self.into();
+                let [$($lane),*] = decrypt([$($lane),*], keys, |key| {
+                    let ($($lane),*) = key.into();
+                    [$($lane),*][0]
+                });
+                ($($lane),*).into()
+            }
+        }
+    )*};
+}
+
+impl_cipher!(crate::AesBlockX2, (a, b), crate::AesBlockX4, (a, b, c, d));
+
+#[target_feature(enable = "aes")]
+unsafe fn aesenc(a: __m128i, round_key: __m128i) -> __m128i {
+    _mm_aesenc_si128(a, round_key)
+}
+
+#[target_feature(enable = "aes")]
+unsafe fn aesdec(a: __m128i, round_key: __m128i) -> __m128i {
+    _mm_aesdec_si128(a, round_key)
+}
+
+#[target_feature(enable = "aes")]
+unsafe fn aesenclast(a: __m128i, round_key: __m128i) -> __m128i {
+    _mm_aesenclast_si128(a, round_key)
+}
+
+#[target_feature(enable = "aes")]
+unsafe fn aesdeclast(a: __m128i, round_key: __m128i) -> __m128i {
+    _mm_aesdeclast_si128(a, round_key)
+}
+
+#[target_feature(enable = "aes")]
+unsafe fn aesmc(a: __m128i) -> __m128i {
+    _mm_aesenc_si128(
+        _mm_aesdeclast_si128(a, _mm_setzero_si128()),
+        _mm_setzero_si128(),
+    )
+}
+
+#[target_feature(enable = "aes")]
+unsafe fn aesimc(a: __m128i) -> __m128i {
+    _mm_aesimc_si128(a)
+}
+
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+#[must_use]
+pub struct AesBlock(__m128i);
+
+impl PartialEq for AesBlock {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        (*self ^ *other).is_zero()
+    }
+}
+
+impl Eq for AesBlock {}
+
+impl From<[u8; 16]> for AesBlock {
+    #[inline]
+    fn from(value: [u8; 16]) -> Self {
+        Self(unsafe { _mm_loadu_si128(value.as_ptr().cast()) })
+    }
+}
+
+impl BitAnd for AesBlock {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(unsafe { _mm_and_si128(self.0, rhs.0) })
+    }
+}
+
+impl BitOr for AesBlock {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(unsafe { _mm_or_si128(self.0, rhs.0) })
+    }
+}
+
+impl BitXor for AesBlock {
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self(unsafe { _mm_xor_si128(self.0, rhs.0) })
+    }
+}
+
+impl Not for AesBlock {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        Self(unsafe { _mm_xor_si128(self.0, _mm_set1_epi64x(-1)) })
+    }
+}
+
+impl AesBlock {
+    #[inline]
+    pub const fn new(value: [u8; 16]) -> Self {
+        // using transmute in simd is safe
+        unsafe { core::mem::transmute(value) }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn to_bytes(self) -> [u8; 16] {
+        // using transmute in simd is safe
+        unsafe { core::mem::transmute(self) }
+    }
+
+    #[inline]
+    pub fn store_to(self, dst: &mut [u8]) {
+        assert!(dst.len() >= 16);
+        unsafe { _mm_storeu_si128(dst.as_mut_ptr().cast(), self.0) };
+    }
+
+    #[inline]
+    pub fn zero() -> Self {
+        Self(unsafe { _mm_setzero_si128() })
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_zero(self) -> bool {
+        unsafe { _mm_movemask_epi8(_mm_cmpeq_epi8(self.0, _mm_setzero_si128())) == 0xffff }
+    }
+
+    #[inline(always)]
+    fn soft(self) -> soft::AesBlock {
+        soft::AesBlock::new(self.to_bytes())
+    }
+
+    #[inline(always)]
+    fn from_soft(block: soft::AesBlock) -> Self {
+        Self::new(block.to_bytes())
+    }
+
+    /// Shifts the block left by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shl_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shl_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) << 16);
+    /// ```
+    #[inline]
+    pub fn shl_bytes<const N: i32>(self) -> Self {
+        Self::from_soft(self.soft().shl_bytes::<N>())
+    }
+
+    /// Shifts the block right by `N` bytes, treating it as a big-endian 128-bit integer. Shifting by 16 or
+    /// more bytes gives the zero block.
+    ///
+    /// This is a **byte** shift, see [`AesBlock::shr_bits`] for shifting by bits
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let arr = *b"0123456789abcdef";
+    /// let shifted = AesBlock::from(arr).shr_bytes::<2>();
+    /// assert_eq!(u128::from(shifted), u128::from_be_bytes(arr) >> 16);
+    /// ```
+    #[inline]
+    pub fn shr_bytes<const N: i32>(self) -> Self {
+        Self::from_soft(self.soft().shr_bytes::<N>())
+    }
+
+    /// Reverses the order of the bits within each byte of the block, keeping the order of the bytes
+    /// unchanged. This is the bit-reflection between the GHASH and POLYVAL conventions, and is not the
+    /// same as reversing all 128 bits of the block
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from(*b"\x01\x02\x0f\xf0abcdefghijkl");
+    /// let expected = b"\x01\x02\x0f\xf0abcdefghijkl".map(u8::reverse_bits);
+    /// assert_eq!(block.reverse_bits_per_byte(), AesBlock::from(expected));
+    /// ```
+    #[inline]
+    pub fn reverse_bits_per_byte(self) -> Self {
+        Self::from_soft(self.soft().reverse_bits_per_byte())
+    }
+
+    /// Rotates column `c` of the state down by `c` positions, moving the byte in row `r` and column `c` to row
+    /// `(r + c) % 4`. This is the column-wise analogue of `ShiftRows`, as used in some AES-based designs.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 7, 4, 5, 6, 10, 11, 8, 9, 13, 14, 15, 12][i]` of `self`
+    #[inline]
+    pub fn rotate_diagonal(self) -> Self {
+        Self::from_soft(self.soft().rotate_diagonal())
+    }
+
+    /// The inverse of [`AesBlock::rotate_diagonal`], rotating column `c` of the state up by `c` positions.
+    ///
+    /// Byte `i` of the result is byte `[0, 1, 2, 3, 5, 6, 7, 4, 10, 11, 8, 9, 15, 12, 13, 14][i]` of `self`
+    #[inline]
+    pub fn inv_rotate_diagonal(self) -> Self {
+        Self::from_soft(self.soft().inv_rotate_diagonal())
+    }
+
+    /// Performs one round of AES encryption function (`ShiftRows`->`SubBytes`->`MixColumns`->`AddRoundKey`)
+    #[inline]
+    pub fn enc(self, round_key: Self) -> Self {
+        if has_aesni() {
+            Self(unsafe { aesenc(self.0, round_key.0) })
+        } else {
+            Self::from_soft(self.soft().enc(round_key.soft()))
+        }
+    }
+
+    /// Performs one round of AES decryption function (`InvShiftRows`->`InvSubBytes`->`InvMixColumn`s->`AddRoundKey`)
+    #[inline]
+    pub fn dec(self, round_key: Self) -> Self {
+        if has_aesni() {
+            Self(unsafe { aesdec(self.0, round_key.0) })
+        } else {
+            Self::from_soft(self.soft().dec(round_key.soft()))
+        }
+    }
+
+    /// Performs one round of AES encryption function without `MixColumns` (`ShiftRows`->`SubBytes`->`AddRoundKey`)
+    #[inline]
+    pub fn enc_last(self, round_key: Self) -> Self {
+        if has_aesni() {
+            Self(unsafe { aesenclast(self.0, round_key.0) })
+        } else {
+            Self::from_soft(self.soft().enc_last(round_key.soft()))
+        }
+    }
+
+    /// Performs one round of AES decryption function without `InvMixColumn`s (`InvShiftRows`->`InvSubBytes`->`AddRoundKey`)
+    #[inline]
+    pub fn dec_last(self, round_key: Self) -> Self {
+        if has_aesni() {
+            Self(unsafe { aesdeclast(self.0, round_key.0) })
+        } else {
+            Self::from_soft(self.soft().dec_last(round_key.soft()))
+        }
+    }
+
+    /// Performs the `MixColumns` operation
+    #[inline]
+    pub fn mc(self) -> Self {
+        if has_aesni() {
+            Self(unsafe { aesmc(self.0) })
+        } else {
+            Self::from_soft(self.soft().mc())
+        }
+    }
+
+    /// Performs the `InvMixColumn`s operation
+    #[inline]
+    pub fn imc(self) -> Self {
+        if has_aesni() {
+            Self(unsafe { aesimc(self.0) })
+        } else {
+            Self::from_soft(self.soft().imc())
+        }
+    }
+
+    /// Computes the carryless (GF(2) polynomial) product of the two blocks, treated as big-endian 128-bit
+    /// integers, returning the high and low halves of the 256-bit result
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let (hi, lo) = AesBlock::from(0b11).clmul(AesBlock::from(0b11));
+    /// assert_eq!((u128::from(hi), u128::from(lo)), (0, 0b101));
+    /// ```
+    #[inline]
+    pub fn clmul(self, other: Self) -> (Self, Self) {
+        let (hi, lo) = crate::clmul_soft(self.into(), other.into());
+        (hi.into(), lo.into())
+    }
+}
+
+pub(super) fn keygen_128(key: [u8; 16]) -> [AesBlock; 11] {
+    soft::keygen_128(key).map(AesBlock::from_soft)
+}
+
+pub(super) fn keygen_192(key: [u8; 24]) -> [AesBlock; 13] {
+    soft::keygen_192(key).map(AesBlock::from_soft)
+}
+
+pub(super) fn keygen_256(key: [u8; 32]) -> [AesBlock; 15] {
+    soft::keygen_256(key).map(AesBlock::from_soft)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::{Aes128Enc, AesBlockX4, AesDecrypt, AesEncrypt};
+    use std::sync::Mutex;
+
+    /// Held while the detection result is forced, as the hook reports the cached result
+    static FORCED: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn test_both_paths() {
+        let _forced = FORCED.lock().unwrap();
+        if !has_aesni() {
+            return;
+        }
+
+        let mut state = 0x0123_4567_89ab_cdef_u128;
+        for _ in 0..64 {
+            state = state.wrapping_mul(0x2545_f491_4f6c_dd1d).wrapping_add(1);
+            let block = AesBlock::from(state);
+            let key = AesBlock::from(state.rotate_left(64));
+
+            force_aesni(true);
+            let ni = [
+                block.enc(key),
+                block.dec(key),
+                block.enc_last(key),
+                block.dec_last(key),
+                block.mc(),
+                block.imc(),
+            ];
+            force_aesni(false);
+            let sw = [
+                block.enc(key),
+                block.dec(key),
+                block.enc_last(key),
+                block.dec_last(key),
+                block.mc(),
+                block.imc(),
+            ];
+            force_aesni(true);
+
+            assert_eq!(ni, sw);
+
+            let enc = Aes128Enc::from(state.rotate_left(32).to_be_bytes());
+            let dec = enc.decrypter();
+            let blocks = AesBlockX4::from([block, key, !block, !key]);
+            let ni = (enc.encrypt_4_blocks(blocks), dec.decrypt_block(block));
+            force_aesni(false);
+            let sw = (enc.encrypt_4_blocks(blocks), dec.decrypt_block(block));
+            force_aesni(true);
+
+            assert_eq!(ni, sw);
+            assert_eq!(dec.decrypt_4_blocks(ni.0), blocks);
+        }
+
+        force_aesni(false);
+        assert!(crate::self_test());
+        force_aesni(true);
+    }
+
+    #[test]
+    fn test_backend_hook() {
+        let _forced = FORCED.lock().unwrap();
+
+        static CALLS: Mutex<std::vec::Vec<&str>> = Mutex::new(std::vec::Vec::new());
+        fn hook(name: &'static str) {
+            CALLS.lock().unwrap().push(name);
+        }
+
+        assert!(set_backend_hook(hook));
+        assert!(!set_backend_hook(|_| panic!("only the first hook is kept")));
+        for _ in 0..3 {
+            assert!(crate::self_test());
+        }
+
+        let expected = if std::is_x86_feature_detected!("aes") {
+            "AES-NI"
+        } else {
+            SOFT_IMPL
+        };
+        assert_eq!(*CALLS.lock().unwrap(), [expected]);
+    }
+}
@@ -98,6 +98,13 @@ impl AesBlockX2 {
         self.0.is_zero() & self.1.is_zero()
     }
 
+    /// Creates the two consecutive counter blocks `base` and `base + 1`, incrementing only the last 32 bits
+    /// as a big-endian counter (as in GCM), so the second lane wraps around within those bits
+    #[inline]
+    pub fn counter_block(base: AesBlock) -> Self {
+        (base, base.incr_be_32()).into()
+    }
+
     /// Performs one round of AES encryption function (`ShiftRows`->`SubBytes`->`MixColumns`->`AddRoundKey`)
     #[inline]
     pub fn enc(self, round_key: Self) -> Self {
@@ -121,4 +128,16 @@ impl AesBlockX2 {
     pub fn dec_last(self, round_key: Self) -> Self {
         Self(self.0.dec_last(round_key.0), self.1.dec_last(round_key.1))
     }
+
+    /// Performs the `MixColumns` operation
+    #[inline]
+    pub fn mc(self) -> Self {
+        Self(self.0.mc(), self.1.mc())
+    }
+
+    /// Performs the `InvMixColumn`s operation
+    #[inline]
+    pub fn imc(self) -> Self {
+        Self(self.0.imc(), self.1.imc())
+    }
 }
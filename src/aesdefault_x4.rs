@@ -110,6 +110,29 @@ impl AesBlockX4 {
         self.1.store_to(&mut dst[32..]);
     }
 
+    /// Stores only the bytes of the block selected by `byte_mask` into `dst`: byte `i` (in the order of
+    /// `store_to`) is written to `dst[i]` if bit `i` of `byte_mask` is set, and `dst[i]` is left unchanged
+    /// otherwise. `dst` only needs to cover the highest selected byte, so a prefix of `n` bytes (mask
+    /// `(1 << n) - 1`) can be stored into a slice of length `n`, e.g. for the tail of a message
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlockX4;
+    /// let block = AesBlockX4::from([0xaa; 64]);
+    /// let mut dst = [0; 5];
+    /// block.store_masked((1 << 5) - 1, &mut dst);
+    /// assert_eq!(dst, [0xaa; 5]);
+    /// ```
+    #[inline]
+    pub fn store_masked(self, byte_mask: u64, dst: &mut [u8]) {
+        assert!(dst.len() >= (64 - byte_mask.leading_zeros()) as usize);
+        let bytes = <[u8; 64]>::from(self);
+        for (i, (d, &b)) in dst.iter_mut().zip(&bytes).enumerate() {
+            if (byte_mask >> i) & 1 != 0 {
+                *d = b;
+            }
+        }
+    }
+
     #[inline]
     pub fn zero() -> Self {
         Self(AesBlockX2::zero(), AesBlockX2::zero())
@@ -121,6 +144,37 @@ impl AesBlockX4 {
         self.0.is_zero() & self.1.is_zero()
     }
 
+    /// Builds a block by taking each 128-bit lane from `a` or `b` according to `mask`: lane `i` (in the
+    /// order of `From<(AesBlock, AesBlock, AesBlock, AesBlock)>`) is taken from `b` if bit `i` of `mask` is
+    /// set, and from `a` otherwise. The upper 4 bits of `mask` are ignored
+    #[inline]
+    pub fn select_lanes(mask: u8, a: Self, b: Self) -> Self {
+        #[inline(always)]
+        fn select(bit: u8, a: AesBlock, b: AesBlock) -> AesBlock {
+            let m = AesBlock::from([0u8.wrapping_sub(bit & 1); 16]);
+            a ^ ((a ^ b) & m)
+        }
+
+        let (a0, a1, a2, a3) = a.into();
+        let (b0, b1, b2, b3) = b.into();
+        (
+            select(mask, a0, b0),
+            select(mask >> 1, a1, b1),
+            select(mask >> 2, a2, b2),
+            select(mask >> 3, a3, b3),
+        )
+            .into()
+    }
+
+    /// Rotates the four lanes to the left by one position: lane `i` (in the order of
+    /// `From<(AesBlock, AesBlock, AesBlock, AesBlock)>`) of the result is lane `i + 1` of `self`, and lane 3
+    /// of the result is lane 0 of `self`, so `(a, b, c, d)` becomes `(b, c, d, a)`
+    #[inline]
+    pub fn rotate_lanes_left(self) -> Self {
+        let (a, b, c, d) = self.into();
+        (b, c, d, a).into()
+    }
+
     /// Performs one round of AES encryption function (`ShiftRows`->`SubBytes`->`MixColumns`->`AddRoundKey`)
     #[inline]
     pub fn enc(self, round_key: Self) -> Self {
@@ -144,4 +198,16 @@ impl AesBlockX4 {
     pub fn dec_last(self, round_key: Self) -> Self {
         Self(self.0.dec_last(round_key.0), self.1.dec_last(round_key.1))
     }
+
+    /// Performs the `MixColumns` operation
+    #[inline]
+    pub fn mc(self) -> Self {
+        Self(self.0.mc(), self.1.mc())
+    }
+
+    /// Performs the `InvMixColumn`s operation
+    #[inline]
+    pub fn imc(self) -> Self {
+        Self(self.0.imc(), self.1.imc())
+    }
 }
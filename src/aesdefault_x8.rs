@@ -0,0 +1,197 @@
+use core::ops::{BitAnd, BitOr, BitXor, Not};
+
+use crate::{array_from_slice, AesBlock, AesBlockX4};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[repr(C, align(64))]
+#[must_use]
+pub struct AesBlockX8(AesBlockX4, AesBlockX4);
+
+impl From<[u8; 128]> for AesBlockX8 {
+    #[inline]
+    fn from(value: [u8; 128]) -> Self {
+        Self::new(value)
+    }
+}
+
+#[allow(clippy::type_complexity)]
+impl
+    From<(
+        AesBlock,
+        AesBlock,
+        AesBlock,
+        AesBlock,
+        AesBlock,
+        AesBlock,
+        AesBlock,
+        AesBlock,
+    )> for AesBlockX8
+{
+    #[inline]
+    fn from(
+        value: (
+            AesBlock,
+            AesBlock,
+            AesBlock,
+            AesBlock,
+            AesBlock,
+            AesBlock,
+            AesBlock,
+            AesBlock,
+        ),
+    ) -> Self {
+        Self(
+            (value.0, value.1, value.2, value.3).into(),
+            (value.4, value.5, value.6, value.7).into(),
+        )
+    }
+}
+
+impl From<(AesBlockX4, AesBlockX4)> for AesBlockX8 {
+    #[inline]
+    fn from((hi, lo): (AesBlockX4, AesBlockX4)) -> Self {
+        Self(hi, lo)
+    }
+}
+
+impl From<AesBlock> for AesBlockX8 {
+    #[inline]
+    fn from(value: AesBlock) -> Self {
+        Self(value.into(), value.into())
+    }
+}
+
+impl From<AesBlockX4> for AesBlockX8 {
+    #[inline]
+    fn from(value: AesBlockX4) -> Self {
+        Self(value, value)
+    }
+}
+
+#[allow(clippy::type_complexity)]
+impl From<AesBlockX8>
+    for (
+        AesBlock,
+        AesBlock,
+        AesBlock,
+        AesBlock,
+        AesBlock,
+        AesBlock,
+        AesBlock,
+        AesBlock,
+    )
+{
+    #[inline]
+    fn from(value: AesBlockX8) -> Self {
+        let (b0, b1, b2, b3) = value.0.into();
+        let (b4, b5, b6, b7) = value.1.into();
+        (b0, b1, b2, b3, b4, b5, b6, b7)
+    }
+}
+
+impl From<AesBlockX8> for (AesBlockX4, AesBlockX4) {
+    #[inline]
+    fn from(value: AesBlockX8) -> Self {
+        (value.0, value.1)
+    }
+}
+
+impl BitAnd for AesBlockX8 {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0, self.1 & rhs.1)
+    }
+}
+
+impl BitOr for AesBlockX8 {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0, self.1 | rhs.1)
+    }
+}
+
+impl BitXor for AesBlockX8 {
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self(self.0 ^ rhs.0, self.1 ^ rhs.1)
+    }
+}
+
+impl Not for AesBlockX8 {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        Self(!self.0, !self.1)
+    }
+}
+
+impl AesBlockX8 {
+    #[inline]
+    pub const fn new(value: [u8; 128]) -> Self {
+        Self(
+            AesBlockX4::new(array_from_slice(&value, 0)),
+            AesBlockX4::new(array_from_slice(&value, 64)),
+        )
+    }
+
+    #[inline]
+    pub fn store_to(self, dst: &mut [u8]) {
+        assert!(dst.len() >= 128);
+        self.0.store_to(&mut dst[..64]);
+        self.1.store_to(&mut dst[64..]);
+    }
+
+    #[inline]
+    pub fn zero() -> Self {
+        Self(AesBlockX4::zero(), AesBlockX4::zero())
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_zero(self) -> bool {
+        self.0.is_zero() & self.1.is_zero()
+    }
+
+    /// Performs one round of AES encryption function (`ShiftRows`->`SubBytes`->`MixColumns`->`AddRoundKey`)
+    #[inline]
+    pub fn enc(self, round_key: Self) -> Self {
+        Self(self.0.enc(round_key.0), self.1.enc(round_key.1))
+    }
+
+    /// Performs one round of AES decryption function (`InvShiftRows`->`InvSubBytes`->`InvMixColumn`s->`AddRoundKey`)
+    #[inline]
+    pub fn dec(self, round_key: Self) -> Self {
+        Self(self.0.dec(round_key.0), self.1.dec(round_key.1))
+    }
+
+    /// Performs one round of AES encryption function without `MixColumns` (`ShiftRows`->`SubBytes`->`AddRoundKey`)
+    #[inline]
+    pub fn enc_last(self, round_key: Self) -> Self {
+        Self(self.0.enc_last(round_key.0), self.1.enc_last(round_key.1))
+    }
+
+    /// Performs one round of AES decryption function without `InvMixColumn`s (`InvShiftRows`->`InvSubBytes`->`AddRoundKey`)
+    #[inline]
+    pub fn dec_last(self, round_key: Self) -> Self {
+        Self(self.0.dec_last(round_key.0), self.1.dec_last(round_key.1))
+    }
+
+    /// Performs the `MixColumns` operation
+    #[inline]
+    pub fn mc(self) -> Self {
+        Self(self.0.mc(), self.1.mc())
+    }
+
+    /// Performs the `InvMixColumn`s operation
+    #[inline]
+    pub fn imc(self) -> Self {
+        Self(self.0.imc(), self.1.imc())
+    }
+}
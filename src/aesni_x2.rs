@@ -111,6 +111,22 @@ impl AesBlockX2 {
         unsafe { _mm256_testz_si256(self.0, self.0) == 1 }
     }
 
+    /// Creates the two consecutive counter blocks `base` and `base + 1`, incrementing only the last 32 bits
+    /// as a big-endian counter (as in GCM), so the second lane wraps around within those bits
+    #[inline]
+    pub fn counter_block(base: AesBlock) -> Self {
+        unsafe {
+            // swap the last 32-bit word of each lane to little-endian to do the addition
+            let bswap = _mm256_setr_epi8(
+                0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 15, 14, 13, 12, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9,
+                10, 11, 15, 14, 13, 12,
+            );
+            let base = _mm256_shuffle_epi8(_mm256_broadcastsi128_si256(base.0), bswap);
+            let ctr = _mm256_add_epi32(base, _mm256_setr_epi32(0, 0, 0, 0, 0, 0, 0, 1));
+            Self(_mm256_shuffle_epi8(ctr, bswap))
+        }
+    }
+
     /// Performs one round of AES encryption function (`ShiftRows`->`SubBytes`->`MixColumns`->`AddRoundKey`)
     #[inline]
     pub fn enc(self, round_key: Self) -> Self {
@@ -134,4 +150,17 @@ impl AesBlockX2 {
     pub fn dec_last(self, round_key: Self) -> Self {
         Self(unsafe { _mm256_aesdeclast_epi128(self.0, round_key.0) })
     }
+
+    /// Performs the `MixColumns` operation
+    #[inline]
+    pub fn mc(self) -> Self {
+        self.dec_last(Self::zero()).enc(Self::zero())
+    }
+
+    /// Performs the `InvMixColumn`s operation
+    #[inline]
+    pub fn imc(self) -> Self {
+        // there is no 256-bit version of `aesimc`, but `SubBytes` commutes with `ShiftRows`
+        self.enc_last(Self::zero()).dec(Self::zero())
+    }
 }
@@ -136,6 +136,37 @@ impl AesBlockX4 {
         unsafe { _mm512_storeu_si512(dst.as_mut_ptr().cast(), self.0) };
     }
 
+    /// Stores only the bytes of the block selected by `byte_mask` into `dst`: byte `i` (in the order of
+    /// `store_to`) is written to `dst[i]` if bit `i` of `byte_mask` is set, and `dst[i]` is left unchanged
+    /// otherwise. `dst` only needs to cover the highest selected byte, so a prefix of `n` bytes (mask
+    /// `(1 << n) - 1`) can be stored into a slice of length `n`, e.g. for the tail of a message
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlockX4;
+    /// let block = AesBlockX4::from([0xaa; 64]);
+    /// let mut dst = [0; 5];
+    /// block.store_masked((1 << 5) - 1, &mut dst);
+    /// assert_eq!(dst, [0xaa; 5]);
+    /// ```
+    #[inline]
+    pub fn store_masked(self, byte_mask: u64, dst: &mut [u8]) {
+        assert!(dst.len() >= (64 - byte_mask.leading_zeros()) as usize);
+        #[cfg(target_feature = "avx512bw")]
+        // masked-out bytes are never accessed, so this cannot write out of bounds
+        unsafe {
+            _mm512_mask_storeu_epi8(dst.as_mut_ptr().cast(), byte_mask, self.0);
+        }
+        #[cfg(not(target_feature = "avx512bw"))]
+        {
+            let bytes = <[u8; 64]>::from(self);
+            for (i, (d, &b)) in dst.iter_mut().zip(&bytes).enumerate() {
+                if (byte_mask >> i) & 1 != 0 {
+                    *d = b;
+                }
+            }
+        }
+    }
+
     #[inline]
     pub fn zero() -> Self {
         Self(unsafe { _mm512_setzero_si512() })
@@ -147,6 +178,27 @@ impl AesBlockX4 {
         unsafe { _mm512_test_epi64_mask(self.0, self.0) == 0 }
     }
 
+    /// Builds a block by taking each 128-bit lane from `a` or `b` according to `mask`: lane `i` (in the
+    /// order of `From<(AesBlock, AesBlock, AesBlock, AesBlock)>`) is taken from `b` if bit `i` of `mask` is
+    /// set, and from `a` otherwise. The upper 4 bits of `mask` are ignored
+    #[inline]
+    pub fn select_lanes(mask: u8, a: Self, b: Self) -> Self {
+        // each 128-bit lane is made of two 64-bit elements
+        let m = mask & 0xf;
+        let k = ((m & 1) * 3) | ((m & 2) * 6) | ((m & 4) * 12) | ((m & 8) * 24);
+        Self(unsafe { _mm512_mask_blend_epi64(k, a.0, b.0) })
+    }
+
+    /// Rotates the four lanes to the left by one position: lane `i` (in the order of
+    /// `From<(AesBlock, AesBlock, AesBlock, AesBlock)>`) of the result is lane `i + 1` of `self`, and lane 3
+    /// of the result is lane 0 of `self`, so `(a, b, c, d)` becomes `(b, c, d, a)`
+    #[inline]
+    pub fn rotate_lanes_left(self) -> Self {
+        // a lane is two 64-bit elements, so shifting the concatenation `self:self` right by two elements
+        // moves every lane down by one
+        Self(unsafe { _mm512_alignr_epi64::<2>(self.0, self.0) })
+    }
+
     /// Performs one round of AES encryption function (`ShiftRows`->`SubBytes`->`MixColumns`->`AddRoundKey`)
     #[inline]
     pub fn enc(self, round_key: Self) -> Self {
@@ -170,4 +222,17 @@ impl AesBlockX4 {
     pub fn dec_last(self, round_key: Self) -> Self {
         Self(unsafe { _mm512_aesdeclast_epi128(self.0, round_key.0) })
     }
+
+    /// Performs the `MixColumns` operation
+    #[inline]
+    pub fn mc(self) -> Self {
+        self.dec_last(Self::zero()).enc(Self::zero())
+    }
+
+    /// Performs the `InvMixColumn`s operation
+    #[inline]
+    pub fn imc(self) -> Self {
+        // there is no 512-bit version of `aesimc`, but `SubBytes` commutes with `ShiftRows`
+        self.enc_last(Self::zero()).dec(Self::zero())
+    }
 }
@@ -0,0 +1,78 @@
+use crate::AesBlock;
+use core::fmt::{self, Debug, Formatter};
+
+/// A fixed-capacity buffer holding a partial block between calls, for incremental (streaming) modes
+#[derive(Clone, Default)]
+pub struct BlockBuffer {
+    buffer: [u8; 16],
+    pos: usize,
+}
+
+/// Prints only the type name, as the buffered bytes are message data
+impl Debug for BlockBuffer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockBuffer").finish_non_exhaustive()
+    }
+}
+
+impl BlockBuffer {
+    /// Creates an empty buffer
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; 16],
+            pos: 0,
+        }
+    }
+
+    /// The number of bytes currently buffered. This is always less than 16
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.pos
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.pos == 0
+    }
+
+    /// Appends `data` to the buffered bytes, calling `f` on every complete block in order, and keeps the
+    /// remaining (less than 16) bytes for the next call
+    pub fn input_block(&mut self, mut data: &[u8], mut f: impl FnMut(AesBlock)) {
+        if self.pos != 0 {
+            let n = data.len().min(16 - self.pos);
+            self.buffer[self.pos..self.pos + n].copy_from_slice(&data[..n]);
+            self.pos += n;
+            data = &data[n..];
+
+            if self.pos < 16 {
+                return;
+            }
+            f(self.buffer.into());
+            self.pos = 0;
+        }
+
+        let mut chunks = data.chunks_exact(16);
+        for chunk in &mut chunks {
+            f(AesBlock::try_from(chunk).unwrap());
+        }
+
+        let remainder = chunks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.pos = remainder.len();
+    }
+
+    /// Empties the buffer, returning the buffered bytes padded with zeroes to a full block, or `None` if
+    /// there were no buffered bytes
+    pub fn pad_and_finalize(&mut self) -> Option<AesBlock> {
+        if self.pos == 0 {
+            return None;
+        }
+        self.buffer[self.pos..].fill(0);
+        self.pos = 0;
+        Some(self.buffer.into())
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{AesBlock, AesBlockX4, AesDecrypt, AesEncrypt};
+
+/// Encrypts `blocks` in place in CBC mode, starting from `iv`. Returns the last ciphertext block (or `iv`
+/// if `blocks` is empty), which is the IV to continue the chain with
+pub fn cbc_encrypt<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(
+    enc: &E,
+    iv: AesBlock,
+    blocks: &mut [AesBlock],
+) -> AesBlock {
+    let mut prev = iv;
+    for block in blocks {
+        prev = enc.encrypt_block(*block ^ prev);
+        *block = prev;
+    }
+    prev
+}
+
+/// Runs the CBC chain over `blocks` starting from `iv`, and returns only the last ciphertext block (or `iv`
+/// if `blocks` is empty), without writing out the ciphertext. With a zero IV this is the raw CBC-MAC, and it
+/// is the building block of CCM's MAC and of the retail MAC. The length and padding rules that make
+/// CBC-MAC secure for variable-length messages are up to the caller
+///
+/// ```
+/// # use aes_crypto::{cbc::cbc_process, Aes128Enc, AesBlock, AesEncrypt};
+/// let enc = Aes128Enc::from([0; 16]);
+/// let message = [AesBlock::from(1), AesBlock::from(2)];
+/// let mac = cbc_process(&enc, AesBlock::zero(), &message);
+/// assert_eq!(mac, enc.encrypt_block(enc.encrypt_block(message[0]) ^ message[1]));
+/// ```
+pub fn cbc_process<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(
+    enc: &E,
+    iv: AesBlock,
+    blocks: &[AesBlock],
+) -> AesBlock {
+    blocks
+        .iter()
+        .fold(iv, |prev, &block| enc.encrypt_block(block ^ prev))
+}
+
+/// Decrypts `blocks` in place in CBC mode, starting from `iv`. Returns the last ciphertext block (or `iv`
+/// if `blocks` is empty), which is the IV to continue the chain with. Unlike encryption, decryption is
+/// parallel, so the blocks are decrypted four at a time
+pub fn cbc_decrypt<D: AesDecrypt<KEY_LEN>, const KEY_LEN: usize>(
+    dec: &D,
+    iv: AesBlock,
+    blocks: &mut [AesBlock],
+) -> AesBlock {
+    let mut prev = iv;
+
+    let mut chunks = blocks.chunks_exact_mut(4);
+    for chunk in &mut chunks {
+        let ciphertext = AesBlockX4::from_distinct(chunk[0], chunk[1], chunk[2], chunk[3]);
+        let plaintext = dec.decrypt_4_blocks(ciphertext)
+            ^ AesBlockX4::from_distinct(prev, chunk[0], chunk[1], chunk[2]);
+        prev = chunk[3];
+        (chunk[0], chunk[1], chunk[2], chunk[3]) = plaintext.into();
+    }
+
+    for block in chunks.into_remainder() {
+        let ciphertext = *block;
+        *block = dec.decrypt_block(ciphertext) ^ prev;
+        prev = ciphertext;
+    }
+    prev
+}
@@ -0,0 +1,128 @@
+use crate::{AesBlock, AesBlockX4, AesEncrypt};
+use core::fmt::{self, Debug, Formatter};
+
+/// AES in 128-bit cipher feedback mode (CFB-128 of NIST SP 800-38A). Each keystream block is the encryption
+/// of the previous ciphertext block, so encryption is sequential, but decryption has all the ciphertext up
+/// front and generates the keystream four blocks at a time. The feedback register and the position in the
+/// current block are kept across calls, so a message can be processed in several chunks of any length
+///
+/// ```
+/// # use aes_crypto::{cfb::Cfb128, Aes128Enc};
+/// let cipher = Aes128Enc::from([0; 16]);
+/// let mut data = *b"some secret message";
+///
+/// let mut cfb = Cfb128::new(cipher.clone(), [0; 16]);
+/// cfb.encrypt(&mut data[..5]);
+/// cfb.encrypt(&mut data[5..]);
+///
+/// Cfb128::new(cipher, [0; 16]).decrypt(&mut data);
+/// assert_eq!(&data, b"some secret message");
+/// ```
+#[derive(Clone)]
+pub struct Cfb128<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> {
+    cipher: E,
+    keystream: [u8; 16],
+    /// The ciphertext of the current block so far, which is encrypted once it is complete
+    feedback: [u8; 16],
+    pos: usize,
+}
+
+/// Prints only the type name, as the buffered keystream gives away the data it is applied to
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> Debug for Cfb128<E, KEY_LEN> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cfb128").finish_non_exhaustive()
+    }
+}
+
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> Cfb128<E, KEY_LEN> {
+    /// Creates a cipher feedback stream with the feedback register set to `iv`
+    #[inline]
+    pub fn new(cipher: E, iv: [u8; 16]) -> Self {
+        let keystream = cipher.encrypt_block(iv.into()).into();
+        Self {
+            cipher,
+            keystream,
+            feedback: [0; 16],
+            pos: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn next_block(&mut self) {
+        self.keystream = self.cipher.encrypt_block(self.feedback.into()).into();
+        self.pos = 0;
+    }
+
+    /// Encrypts `data` in place
+    pub fn encrypt(&mut self, data: &mut [u8]) {
+        for byte in data {
+            *byte ^= self.keystream[self.pos];
+            self.feedback[self.pos] = *byte;
+            self.pos += 1;
+            if self.pos == 16 {
+                self.next_block();
+            }
+        }
+    }
+
+    /// Decrypts `data` in place
+    pub fn decrypt(&mut self, mut data: &mut [u8]) {
+        while self.pos != 0 && !data.is_empty() {
+            self.decrypt_byte(&mut data[0]);
+            data = &mut data[1..];
+        }
+
+        // at a block boundary, the keystream of the first block is already known, and those of the other
+        // three are the encryptions of the first three ciphertext blocks
+        let mut chunks = data.chunks_exact_mut(64);
+        for chunk in &mut chunks {
+            let ciphertext = AesBlockX4::try_from(&chunk[..]).unwrap();
+            let (c0, c1, c2, c3) = ciphertext.into();
+            let (k1, k2, k3, next) = self
+                .cipher
+                .encrypt_4_blocks(AesBlockX4::from_distinct(c0, c1, c2, c3))
+                .into();
+            let keystream = AesBlockX4::from_distinct(self.keystream.into(), k1, k2, k3);
+            (ciphertext ^ keystream).store_to(chunk);
+
+            self.feedback = c3.into();
+            self.keystream = next.into();
+        }
+
+        for byte in chunks.into_remainder() {
+            self.decrypt_byte(byte);
+        }
+    }
+
+    #[inline(always)]
+    fn decrypt_byte(&mut self, byte: &mut u8) {
+        self.feedback[self.pos] = *byte;
+        *byte ^= self.keystream[self.pos];
+        self.pos += 1;
+        if self.pos == 16 {
+            self.next_block();
+        }
+    }
+}
+
+/// Encrypts `data` in place in CFB-128 mode, starting from `iv`. Use [`Cfb128`] to process a message in
+/// several chunks
+#[inline]
+pub fn cfb_encrypt<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(
+    enc: &E,
+    iv: AesBlock,
+    data: &mut [u8],
+) {
+    Cfb128::new(enc.clone(), iv.into()).encrypt(data);
+}
+
+/// Decrypts `data` in place in CFB-128 mode, starting from `iv`. Use [`Cfb128`] to process a message in
+/// several chunks
+#[inline]
+pub fn cfb_decrypt<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(
+    enc: &E,
+    iv: AesBlock,
+    data: &mut [u8],
+) {
+    Cfb128::new(enc.clone(), iv.into()).decrypt(data);
+}
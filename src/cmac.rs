@@ -0,0 +1,105 @@
+use crate::aead::tags_eq;
+use crate::{AesBlock, AesEncrypt};
+use core::fmt::{self, Debug, Formatter};
+
+/// Doubling in GF(2^128) with the polynomial `x^128 + x^7 + x^2 + x + 1`, as used for the CMAC subkeys
+#[inline]
+fn dbl(block: AesBlock) -> AesBlock {
+    let value = u128::from(block);
+    ((value << 1) ^ ((value >> 127) * 0x87)).into()
+}
+
+/// AES-CMAC (OMAC1), as specified in NIST SP 800-38B and RFC 4493
+///
+/// ```
+/// # use aes_crypto::{cmac::Cmac, Aes128Enc};
+/// let mut mac = Cmac::new(Aes128Enc::from([0; 16]));
+/// mac.update(b"hello, ");
+/// mac.update(b"world!");
+/// let tag = mac.clone().finalize();
+///
+/// assert!(mac.verify(&tag));
+/// ```
+#[derive(Clone)]
+pub struct Cmac<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> {
+    cipher: E,
+    k1: AesBlock,
+    k2: AesBlock,
+    state: AesBlock,
+    buffer: [u8; 16],
+    pos: usize,
+}
+
+/// Prints only the type name, as the subkeys are derived from the key
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> Debug for Cmac<E, KEY_LEN> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cmac").finish_non_exhaustive()
+    }
+}
+
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> Cmac<E, KEY_LEN> {
+    /// Creates a MAC computation with the block cipher, and derives the subkeys `K1` and `K2` from it
+    #[inline]
+    pub fn new(cipher: E) -> Self {
+        let k1 = dbl(cipher.encrypt_block(AesBlock::zero()));
+        let k2 = dbl(k1);
+        Self {
+            cipher,
+            k1,
+            k2,
+            state: AesBlock::zero(),
+            buffer: [0; 16],
+            pos: 0,
+        }
+    }
+
+    /// Feeds `data` into the MAC. A full block is only processed once more data follows it, as the last
+    /// block of the message is treated differently
+    pub fn update(&mut self, mut data: &[u8]) {
+        let n = data.len().min(16 - self.pos);
+        self.buffer[self.pos..self.pos + n].copy_from_slice(&data[..n]);
+        self.pos += n;
+        data = &data[n..];
+
+        if data.is_empty() {
+            return;
+        }
+
+        // the buffer is full, and is not the last block
+        self.state = self.cipher.encrypt_block(self.state ^ self.buffer.into());
+        while data.len() > 16 {
+            let block = AesBlock::try_from(data).unwrap();
+            self.state = self.cipher.encrypt_block(self.state ^ block);
+            data = &data[16..];
+        }
+
+        self.buffer[..data.len()].copy_from_slice(data);
+        self.pos = data.len();
+    }
+
+    fn tag(&self) -> AesBlock {
+        let last = if self.pos == 16 {
+            AesBlock::from(self.buffer) ^ self.k1
+        } else {
+            let mut block = [0; 16];
+            block[..self.pos].copy_from_slice(&self.buffer[..self.pos]);
+            block[self.pos] = 0x80;
+            AesBlock::from(block) ^ self.k2
+        };
+        self.cipher.encrypt_block(self.state ^ last)
+    }
+
+    /// Returns the tag of the message
+    #[inline]
+    #[must_use]
+    pub fn finalize(self) -> [u8; 16] {
+        self.tag().into()
+    }
+
+    /// Checks, in constant time, whether `tag` is the tag of the message fed so far
+    #[inline]
+    #[must_use]
+    pub fn verify(&self, tag: &[u8; 16]) -> bool {
+        tags_eq(&self.tag().to_bytes(), tag)
+    }
+}
@@ -0,0 +1,46 @@
+//! Single-block one-way compression functions built on AES, for simple hashes and commitments. These are
+//! only compression functions: a hash also needs a padding and a chaining rule (e.g. Merkle–Damgård with
+//! length padding), and the 128-bit output gives at most 64 bits of collision resistance
+
+use crate::{AesBlock, AesEncrypt};
+
+/// The Davies–Meyer compression function `h' = E_m(h) ^ h`, where the message chunk `message` is the key.
+///
+/// As the key changes with every message chunk, this runs the key schedule on every call, which costs
+/// about as much as encrypting a block, so compressing a long message costs roughly two block encryptions
+/// per chunk
+///
+/// ```
+/// # use aes_crypto::{compress::davies_meyer, Aes256Enc, AesBlock};
+/// let h = davies_meyer::<Aes256Enc, 32>(AesBlock::zero(), *b"a 32-byte message chunk, exactly");
+/// let h = davies_meyer::<Aes256Enc, 32>(h, [0; 32]);
+/// # let _ = h;
+/// ```
+#[inline]
+pub fn davies_meyer<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(
+    h: AesBlock,
+    message: [u8; KEY_LEN],
+) -> AesBlock {
+    E::from(message).encrypt_block(h) ^ h
+}
+
+/// The Matyas–Meyer–Oseas compression function `h' = E_h(m) ^ m`, where `enc` is keyed with the chaining
+/// value `h` (for AES-128, directly with its bytes), and `message` is the message block.
+///
+/// Building `enc` from `h` runs the key schedule, which is the same per-call cost as
+/// [`davies_meyer`], but the encrypter can be reused when several blocks are compressed under the same
+/// chaining value
+///
+/// ```
+/// # use aes_crypto::{compress::matyas_meyer_oseas, Aes128Enc, AesBlock};
+/// let h = AesBlock::zero();
+/// let h = matyas_meyer_oseas(&Aes128Enc::from(<[u8; 16]>::from(h)), AesBlock::from(*b"16-byte message!"));
+/// # let _ = h;
+/// ```
+#[inline]
+pub fn matyas_meyer_oseas<const KEY_LEN: usize>(
+    enc: &impl AesEncrypt<KEY_LEN>,
+    message: AesBlock,
+) -> AesBlock {
+    enc.encrypt_block(message) ^ message
+}
@@ -0,0 +1,209 @@
+use crate::{Aes128Enc, Aes192Enc, Aes256Enc, AesBlock, AesBlockX4, AesEncrypt, WideSchedule};
+use core::fmt::{self, Debug, Display, Formatter};
+
+/// The number of low-order bits of the counter block that are incremented in counter mode. The remaining
+/// high-order bits (usually holding a nonce) are kept fixed, and the counter wraps around on overflow
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum CounterWidth {
+    /// The last 32 bits are the counter, as used by GCM
+    Bits32,
+    /// The last 64 bits are the counter
+    Bits64,
+    /// The whole block is the counter
+    #[default]
+    Bits128,
+}
+
+impl CounterWidth {
+    /// Returns the counter block following `block`
+    #[inline]
+    pub fn increment(self, block: AesBlock) -> AesBlock {
+        match self {
+            Self::Bits32 => block.incr_be_32(),
+            Self::Bits64 => block.incr_be_64(),
+            Self::Bits128 => block.incr_be(),
+        }
+    }
+
+    /// Returns the counter block following `block`, and whether the counter wrapped around
+    #[inline]
+    pub fn increment_checked(self, block: AesBlock) -> (AesBlock, bool) {
+        if self == Self::Bits128 {
+            block.incr_be_checked()
+        } else {
+            let next = self.increment(block);
+            (next, self.counter(next) == 0)
+        }
+    }
+
+    /// The value of the counter bits of `block`
+    #[inline]
+    fn counter(self, block: AesBlock) -> u128 {
+        let mask = match self {
+            Self::Bits32 => u32::MAX.into(),
+            Self::Bits64 => u64::MAX.into(),
+            Self::Bits128 => u128::MAX,
+        };
+        u128::from(block) & mask
+    }
+}
+
+/// The error returned when a counter mode stream would have to reuse a counter block, because the counter
+/// has run through all of its values
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CounterExhausted;
+
+impl Display for CounterExhausted {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("the counter space is exhausted")
+    }
+}
+
+impl core::error::Error for CounterExhausted {}
+
+/// AES in counter mode, as a stream cipher. The keystream is generated by encrypting successive counter
+/// blocks, four at a time, and the position in the keystream is kept across calls, so applying it to a
+/// message in several chunks gives the same result as applying it in one go.
+///
+/// The counter never wraps around: once every value of the counter has been used, the stream refuses to
+/// produce any more keystream
+///
+/// ```
+/// # use aes_crypto::{ctr::Ctr128, Aes128Enc};
+/// let cipher = Aes128Enc::from([0; 16]);
+/// let mut data = *b"some secret message";
+///
+/// Ctr128::new(cipher.clone(), [0; 16]).apply_keystream(&mut data)?;
+///
+/// let mut ctr = Ctr128::new(cipher, [0; 16]);
+/// ctr.apply_keystream(&mut data[..5])?;
+/// ctr.apply_keystream(&mut data[5..])?;
+/// assert_eq!(&data, b"some secret message");
+/// # Ok::<(), aes_crypto::ctr::CounterExhausted>(())
+/// ```
+#[derive(Clone)]
+pub struct Ctr128<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> {
+    cipher: E,
+    /// The round keys of `cipher` for the four-block loop, broadcast once at construction
+    schedule: WideSchedule,
+    counter: AesBlock,
+    width: CounterWidth,
+    keystream: [u8; 16],
+    pos: usize,
+    /// Whether the last counter value has been used
+    exhausted: bool,
+    wrapping: bool,
+}
+
+/// Prints only the type name, as the buffered keystream gives away the data it is applied to
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> Debug for Ctr128<E, KEY_LEN> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ctr128").finish_non_exhaustive()
+    }
+}
+
+/// AES-128 in counter mode
+pub type Aes128Ctr = Ctr128<Aes128Enc, 16>;
+/// AES-192 in counter mode
+pub type Aes192Ctr = Ctr128<Aes192Enc, 24>;
+/// AES-256 in counter mode
+pub type Aes256Ctr = Ctr128<Aes256Enc, 32>;
+
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> Ctr128<E, KEY_LEN> {
+    /// Creates a counter mode stream starting at the counter block `iv`, incrementing the whole block as a
+    /// big-endian 128-bit integer
+    #[inline]
+    pub fn new(cipher: E, iv: [u8; 16]) -> Self {
+        Self::with_counter_width(cipher, iv, CounterWidth::Bits128)
+    }
+
+    /// Creates a counter mode stream starting at the counter block `iv`, incrementing only the low `width`
+    /// bits of it
+    #[inline]
+    pub fn with_counter_width(cipher: E, iv: [u8; 16], width: CounterWidth) -> Self {
+        Self {
+            schedule: cipher.wide_schedule(),
+            cipher,
+            counter: iv.into(),
+            width,
+            keystream: [0; 16],
+            pos: 16,
+            exhausted: false,
+            wrapping: false,
+        }
+    }
+
+    /// Lets the counter wrap around, for modes like GCM that allow it and limit the message length instead
+    #[inline]
+    pub(crate) fn wrapping(mut self) -> Self {
+        self.wrapping = true;
+        self
+    }
+
+    #[inline(always)]
+    fn next_counter(&mut self) -> AesBlock {
+        let counter = self.counter;
+        let (next, wrapped) = self.width.increment_checked(counter);
+        self.counter = next;
+        self.exhausted |= wrapped;
+        counter
+    }
+
+    /// Returns whether there are `blocks` unused counter blocks left
+    fn has_counters(&self, blocks: usize) -> bool {
+        let Some(last) = (blocks as u128).checked_sub(1) else {
+            return true;
+        };
+        // the counter values from the current one up to all-ones are left
+        let left = self.width.counter(u128::MAX.into()) - self.width.counter(self.counter);
+        self.wrapping || (!self.exhausted && last <= left)
+    }
+
+    /// XORs the keystream into `data` in place, advancing the counter. Encryption and decryption are the
+    /// same operation
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterExhausted`] if the counter would wrap around before the end of `data`. In that case
+    /// `data` is left unchanged
+    pub fn apply_keystream(&mut self, mut data: &mut [u8]) -> Result<(), CounterExhausted> {
+        let buffered = 16 - self.pos;
+        if !self.has_counters(data.len().saturating_sub(buffered).div_ceil(16)) {
+            return Err(CounterExhausted);
+        }
+
+        if self.pos < 16 {
+            let n = data.len().min(16 - self.pos);
+            xor_in_place(&mut data[..n], &self.keystream[self.pos..self.pos + n]);
+            self.pos += n;
+            data = &mut data[n..];
+        }
+
+        let mut chunks = data.chunks_exact_mut(64);
+        for chunk in &mut chunks {
+            let counters = AesBlockX4::from_distinct(
+                self.next_counter(),
+                self.next_counter(),
+                self.next_counter(),
+                self.next_counter(),
+            );
+            let keystream = self.schedule.encrypt_4_blocks(counters);
+            (keystream ^ AesBlockX4::try_from(&chunk[..]).unwrap()).store_to(chunk);
+        }
+
+        for chunk in chunks.into_remainder().chunks_mut(16) {
+            let counter = self.next_counter();
+            self.keystream = self.cipher.encrypt_block(counter).into();
+            xor_in_place(chunk, &self.keystream[..chunk.len()]);
+            self.pos = chunk.len();
+        }
+        Ok(())
+    }
+}
+
+#[inline(always)]
+pub(crate) fn xor_in_place(data: &mut [u8], keystream: &[u8]) {
+    for (x, k) in data.iter_mut().zip(keystream) {
+        *x ^= k;
+    }
+}
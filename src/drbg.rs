@@ -0,0 +1,289 @@
+use crate::buffer::BlockBuffer;
+use crate::ctr::xor_in_place;
+use crate::{Aes128Enc, Aes192Enc, Aes256Enc, AesBlock, AesBlockX4, AesEncrypt};
+use core::fmt::{self, Debug, Display, Formatter};
+
+/// The largest reseed interval allowed by NIST SP 800-90A for `CTR_DRBG`, which is also the default
+pub const MAX_RESEED_INTERVAL: u64 = 1 << 48;
+
+/// The largest number of bytes that can be requested from a single call to
+/// [`generate`](AesCtrDrbg::generate), which is 2<sup>19</sup> bits
+pub const MAX_REQUEST_LEN: usize = 1 << 16;
+
+/// The errors returned by [`AesCtrDrbg`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DrbgError {
+    /// The reseed interval has been reached, so [`reseed`](AesCtrDrbg::reseed) has to be called before
+    /// generating more output
+    ReseedRequired,
+    /// The entropy input has the wrong length. Without a derivation function, it must be exactly the seed
+    /// length (the key length plus 16 bytes), and with one, it must be at least the key length
+    InvalidEntropyLength,
+    /// The personalization string or the additional input is longer than the seed length, which is not
+    /// allowed without a derivation function
+    InputTooLong,
+    /// More than [`MAX_REQUEST_LEN`] bytes were requested at once
+    RequestTooLong,
+}
+
+impl Display for DrbgError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::ReseedRequired => "the DRBG must be reseeded",
+            Self::InvalidEntropyLength => "invalid entropy input length",
+            Self::InputTooLong => "the input is longer than the seed length",
+            Self::RequestTooLong => "too many bytes requested at once",
+        })
+    }
+}
+
+impl core::error::Error for DrbgError {}
+
+/// The `CTR_DRBG` deterministic random bit generator of NIST SP 800-90A (Rev. 1), built on AES. The
+/// counter is the whole block, and the generator can be used with or without the block cipher derivation
+/// function, which is chosen when it is instantiated.
+///
+/// This only implements the deterministic part of the generator. The caller is responsible for supplying
+/// entropy input with enough min-entropy (at least the key length) when instantiating and reseeding.
+///
+/// ```
+/// # use aes_crypto::drbg::Aes256CtrDrbg;
+/// let mut drbg = Aes256CtrDrbg::instantiate(&[0x42; 32], &[0x24; 16], b"my application").unwrap();
+///
+/// let mut key = [0; 32];
+/// drbg.generate(&mut key, &[]).unwrap();
+///
+/// drbg.reseed(&[0x17; 32], b"after some time").unwrap();
+/// drbg.generate(&mut key, &[]).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct AesCtrDrbg<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> {
+    cipher: E,
+    v: u128,
+    reseed_counter: u64,
+    reseed_interval: u64,
+    derivation_function: bool,
+}
+
+/// Prints only the type name, as the key and `V` are the whole internal state, and predict every output
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> Debug for AesCtrDrbg<E, KEY_LEN> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AesCtrDrbg").finish_non_exhaustive()
+    }
+}
+
+/// `CTR_DRBG` with AES-128
+pub type Aes128CtrDrbg = AesCtrDrbg<Aes128Enc, 16>;
+
+/// `CTR_DRBG` with AES-192
+pub type Aes192CtrDrbg = AesCtrDrbg<Aes192Enc, 24>;
+
+/// `CTR_DRBG` with AES-256
+pub type Aes256CtrDrbg = AesCtrDrbg<Aes256Enc, 32>;
+
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> AesCtrDrbg<E, KEY_LEN> {
+    const SEED_LEN: usize = KEY_LEN + 16;
+
+    fn new(derivation_function: bool) -> Self {
+        Self {
+            cipher: E::from([0; KEY_LEN]),
+            v: 0,
+            reseed_counter: 1,
+            reseed_interval: MAX_RESEED_INTERVAL,
+            derivation_function,
+        }
+    }
+
+    /// Instantiates the generator with the derivation function. The entropy input, the nonce and the
+    /// personalization string are concatenated and compressed to the seed length by the derivation function
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DrbgError::InvalidEntropyLength`] if `entropy` is shorter than the key length
+    pub fn instantiate(
+        entropy: &[u8],
+        nonce: &[u8],
+        personalization: &[u8],
+    ) -> Result<Self, DrbgError> {
+        if entropy.len() < KEY_LEN {
+            return Err(DrbgError::InvalidEntropyLength);
+        }
+        let mut drbg = Self::new(true);
+        let mut seed_material = [0; 48];
+        Self::derive(
+            &[entropy, nonce, personalization],
+            &mut seed_material[..Self::SEED_LEN],
+        );
+        drbg.update(&seed_material[..Self::SEED_LEN]);
+        Ok(drbg)
+    }
+
+    /// Instantiates the generator without the derivation function. The entropy input must be exactly the
+    /// seed length (the key length plus 16 bytes) and full-entropy, and the personalization string is
+    /// XORed into it (after padding it with zeroes)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DrbgError::InvalidEntropyLength`] if `entropy` is not the seed length, and
+    /// [`DrbgError::InputTooLong`] if `personalization` is longer than it
+    pub fn instantiate_no_df(entropy: &[u8], personalization: &[u8]) -> Result<Self, DrbgError> {
+        let seed_material = Self::seed_material_no_df(entropy, personalization)?;
+        let mut drbg = Self::new(false);
+        drbg.update(&seed_material[..Self::SEED_LEN]);
+        Ok(drbg)
+    }
+
+    /// Sets the number of [`generate`](Self::generate) calls allowed between two reseeds, which must be
+    /// at most [`MAX_RESEED_INTERVAL`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `reseed_interval` is 0 or larger than [`MAX_RESEED_INTERVAL`]
+    #[must_use]
+    pub fn with_reseed_interval(mut self, reseed_interval: u64) -> Self {
+        assert!((1..=MAX_RESEED_INTERVAL).contains(&reseed_interval));
+        self.reseed_interval = reseed_interval;
+        self
+    }
+
+    /// Mixes fresh entropy input and the optional additional input into the state, and resets the reseed
+    /// counter. The length requirements of `entropy` and `additional` are the same as when instantiating
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DrbgError::InvalidEntropyLength`] or [`DrbgError::InputTooLong`] if the inputs do not
+    /// have the right lengths, leaving the state unchanged
+    pub fn reseed(&mut self, entropy: &[u8], additional: &[u8]) -> Result<(), DrbgError> {
+        let seed_material = if self.derivation_function {
+            if entropy.len() < KEY_LEN {
+                return Err(DrbgError::InvalidEntropyLength);
+            }
+            let mut seed_material = [0; 48];
+            Self::derive(&[entropy, additional], &mut seed_material[..Self::SEED_LEN]);
+            seed_material
+        } else {
+            Self::seed_material_no_df(entropy, additional)?
+        };
+        self.update(&seed_material[..Self::SEED_LEN]);
+        self.reseed_counter = 1;
+        Ok(())
+    }
+
+    /// Fills `out` with pseudorandom bytes, after mixing the optional additional input into the state.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DrbgError::ReseedRequired`] if the reseed interval has been reached,
+    /// [`DrbgError::RequestTooLong`] if `out` is longer than [`MAX_REQUEST_LEN`], and
+    /// [`DrbgError::InputTooLong`] if `additional` is longer than the seed length without a derivation
+    /// function. Nothing is written to `out` on error
+    pub fn generate(&mut self, out: &mut [u8], additional: &[u8]) -> Result<(), DrbgError> {
+        if self.reseed_counter > self.reseed_interval {
+            return Err(DrbgError::ReseedRequired);
+        }
+        if out.len() > MAX_REQUEST_LEN {
+            return Err(DrbgError::RequestTooLong);
+        }
+
+        let mut additional_input = [0; 48];
+        if !additional.is_empty() {
+            if self.derivation_function {
+                Self::derive(&[additional], &mut additional_input[..Self::SEED_LEN]);
+            } else if additional.len() > Self::SEED_LEN {
+                return Err(DrbgError::InputTooLong);
+            } else {
+                additional_input[..additional.len()].copy_from_slice(additional);
+            }
+            self.update(&additional_input[..Self::SEED_LEN]);
+        }
+
+        let mut chunks = out.chunks_exact_mut(64);
+        for chunk in &mut chunks {
+            let v = self.v;
+            let counters = AesBlockX4::from_distinct(
+                v.wrapping_add(1).into(),
+                v.wrapping_add(2).into(),
+                v.wrapping_add(3).into(),
+                v.wrapping_add(4).into(),
+            );
+            self.v = v.wrapping_add(4);
+            self.cipher.encrypt_4_blocks(counters).store_to(chunk);
+        }
+        for chunk in chunks.into_remainder().chunks_mut(16) {
+            self.v = self.v.wrapping_add(1);
+            let block: [u8; 16] = self.cipher.encrypt_block(self.v.into()).into();
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+
+        self.update(&additional_input[..Self::SEED_LEN]);
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    fn seed_material_no_df(entropy: &[u8], input: &[u8]) -> Result<[u8; 48], DrbgError> {
+        if entropy.len() != Self::SEED_LEN {
+            return Err(DrbgError::InvalidEntropyLength);
+        }
+        if input.len() > Self::SEED_LEN {
+            return Err(DrbgError::InputTooLong);
+        }
+        let mut seed_material = [0; 48];
+        seed_material[..Self::SEED_LEN].copy_from_slice(entropy);
+        xor_in_place(&mut seed_material[..input.len()], input);
+        Ok(seed_material)
+    }
+
+    /// The `CTR_DRBG_Update` function, `provided_data` is exactly the seed length
+    fn update(&mut self, provided_data: &[u8]) {
+        let mut temp = [0; 48];
+        for chunk in temp.chunks_exact_mut(16).take(Self::SEED_LEN.div_ceil(16)) {
+            self.v = self.v.wrapping_add(1);
+            self.cipher.encrypt_block(self.v.into()).store_to(chunk);
+        }
+        xor_in_place(&mut temp[..Self::SEED_LEN], provided_data);
+
+        self.cipher = E::from(temp[..KEY_LEN].try_into().unwrap());
+        self.v = u128::from_be_bytes(temp[KEY_LEN..Self::SEED_LEN].try_into().unwrap());
+    }
+
+    /// The `Block_Cipher_df` function, compressing the concatenation of `inputs` to `out.len()` bytes
+    #[allow(clippy::cast_possible_truncation)]
+    fn derive(inputs: &[&[u8]], out: &mut [u8]) {
+        let input_len: usize = inputs.iter().map(|input| input.len()).sum();
+
+        // the key is the first bytes of 00 01 02 ... 1f
+        let cipher = E::from(core::array::from_fn(|i| i as u8));
+        let mut temp = [0; 48];
+        for (i, chunk) in temp
+            .chunks_exact_mut(16)
+            .take(Self::SEED_LEN.div_ceil(16))
+            .enumerate()
+        {
+            // BCC of IV || S, where IV is the 32-bit counter padded to a block, and
+            // S = L || N || input || 0x80, padded with zeroes to a multiple of the block size
+            let mut chaining = cipher.encrypt_block(AesBlock::from((i as u128) << 96));
+            let mut bcc = |block: AesBlock| chaining = cipher.encrypt_block(chaining ^ block);
+
+            let mut buffer = BlockBuffer::new();
+            buffer.input_block(&(input_len as u32).to_be_bytes(), &mut bcc);
+            buffer.input_block(&(out.len() as u32).to_be_bytes(), &mut bcc);
+            for input in inputs {
+                buffer.input_block(input, &mut bcc);
+            }
+            buffer.input_block(&[0x80], &mut bcc);
+            if let Some(block) = buffer.pad_and_finalize() {
+                bcc(block);
+            }
+
+            chaining.store_to(chunk);
+        }
+
+        let cipher = E::from(temp[..KEY_LEN].try_into().unwrap());
+        let mut x = AesBlock::try_from(&temp[KEY_LEN..]).unwrap();
+        for chunk in out.chunks_mut(16) {
+            x = cipher.encrypt_block(x);
+            let block: [u8; 16] = x.into();
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+}
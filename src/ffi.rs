@@ -0,0 +1,226 @@
+//! A C ABI for the block cipher, for calling this crate from C or C++. The header can be generated with
+//! [`cbindgen`](https://github.com/mozilla/cbindgen) (with the `ffi` feature enabled).
+//!
+//! The block and key types of the crate are backend-specific SIMD types, whose layout must not be relied on
+//! outside of Rust, so this module has `#[repr(C)]` byte-array equivalents of them. The key schedules are
+//! stored as round keys, and converted to the native ones on every call, which costs a few loads.
+//!
+//! ```
+//! # use aes_crypto::ffi::*;
+//! let mut key = aes128_new(&[0; 16]);
+//! let ciphertext = aes128_encrypt_block(&key, AesBlockC([0; 16]));
+//! let decrypter = aes128_decrypter(&key);
+//! assert_eq!(aes128_decrypt_block(&decrypter, ciphertext), AesBlockC([0; 16]));
+//! aes128_clear(&mut key);
+//! ```
+//!
+//! The functions take references, which C sees as pointers: every pointer passed to them must be non-null, aligned
+//! and point to a valid, initialized value, and the key schedules must not be written to by another thread during a
+//! call. The schedules are plain data, so they are not wiped when they go out of scope; use the `*_clear` functions
+//! to overwrite them once they are no longer needed.
+
+use crate::*;
+use core::sync::atomic::{compiler_fence, Ordering};
+
+/// A block with a stable C layout, equivalent to `uint8_t[16]`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[repr(C)]
+pub struct AesBlockC(pub [u8; 16]);
+
+impl From<AesBlock> for AesBlockC {
+    #[inline]
+    fn from(value: AesBlock) -> Self {
+        Self(value.into())
+    }
+}
+
+impl From<AesBlockC> for AesBlock {
+    #[inline]
+    fn from(value: AesBlockC) -> Self {
+        value.0.into()
+    }
+}
+
+// the same volatile write and fence as `zeroize`, which the `ffi` feature does not depend on
+fn clear(round_keys: &mut [AesBlockC]) {
+    for round_key in round_keys {
+        unsafe { core::ptr::write_volatile(round_key, AesBlockC::default()) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+macro_rules! implement_ffi {
+    (
+        $enc_name:ident, $dec_name:ident, $enc_c:ident, $dec_c:ident, $key_len:literal, $nr:literal,
+        $new:ident, $decrypter:ident, $encrypt_block:ident, $decrypt_block:ident, $clear:ident, $decrypter_clear:ident
+    ) => {
+        #[doc = concat!("The key schedule of [`", stringify!($enc_name), "`] with a stable C layout")]
+        #[derive(Clone)]
+        #[repr(C)]
+        pub struct $enc_c {
+            round_keys: [AesBlockC; { $nr + 1 }],
+        }
+
+        /// Prints only the type name, as the round keys are the key
+        impl Debug for $enc_c {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($enc_c)).finish_non_exhaustive()
+            }
+        }
+
+        #[doc = concat!("The key schedule of [`", stringify!($dec_name), "`] with a stable C layout")]
+        #[derive(Clone)]
+        #[repr(C)]
+        pub struct $dec_c {
+            round_keys: [AesBlockC; { $nr + 1 }],
+        }
+
+        /// Prints only the type name, as the round keys are the key
+        impl Debug for $dec_c {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($dec_c)).finish_non_exhaustive()
+            }
+        }
+
+        impl From<&$enc_name> for $enc_c {
+            #[inline]
+            fn from(value: &$enc_name) -> Self {
+                Self {
+                    round_keys: core::array::from_fn(|i| value.round_keys()[i].into()),
+                }
+            }
+        }
+
+        impl From<&$enc_c> for $enc_name {
+            #[inline]
+            fn from(value: &$enc_c) -> Self {
+                Self::from_round_keys(value.round_keys.map(Into::into))
+            }
+        }
+
+        impl From<&$dec_name> for $dec_c {
+            #[inline]
+            fn from(value: &$dec_name) -> Self {
+                Self {
+                    round_keys: core::array::from_fn(|i| value.round_keys()[i].into()),
+                }
+            }
+        }
+
+        impl From<&$dec_c> for $dec_name {
+            #[inline]
+            fn from(value: &$dec_c) -> Self {
+                Self::from_round_keys(value.round_keys.map(Into::into))
+            }
+        }
+
+        #[doc = concat!("Expands a ", $key_len, "-byte key into an encryption key schedule")]
+        ///
+        /// # Safety
+        ///
+        #[doc = concat!("`key` must be a non-null, valid pointer to ", $key_len, " readable bytes.")]
+        #[no_mangle]
+        pub extern "C" fn $new(key: &[u8; $key_len]) -> $enc_c {
+            (&$enc_name::from(*key)).into()
+        }
+
+        /// Computes the decryption key schedule from an encryption key schedule
+        ///
+        /// # Safety
+        ///
+        #[doc = concat!("`key` must be a non-null, aligned and valid pointer to an initialized `", stringify!($enc_c), "`.")]
+        #[no_mangle]
+        pub extern "C" fn $decrypter(key: &$enc_c) -> $dec_c {
+            (&$enc_name::from(key).decrypter()).into()
+        }
+
+        /// Encrypts a single block
+        ///
+        /// # Safety
+        ///
+        #[doc = concat!("`key` must be a non-null, aligned and valid pointer to an initialized `", stringify!($enc_c), "`.")]
+        #[no_mangle]
+        pub extern "C" fn $encrypt_block(key: &$enc_c, plaintext: AesBlockC) -> AesBlockC {
+            $enc_name::from(key)
+                .encrypt_block(plaintext.into())
+                .into()
+        }
+
+        /// Decrypts a single block
+        ///
+        /// # Safety
+        ///
+        #[doc = concat!("`key` must be a non-null, aligned and valid pointer to an initialized `", stringify!($dec_c), "`.")]
+        #[no_mangle]
+        pub extern "C" fn $decrypt_block(key: &$dec_c, ciphertext: AesBlockC) -> AesBlockC {
+            $dec_name::from(key)
+                .decrypt_block(ciphertext.into())
+                .into()
+        }
+
+        /// Overwrites an encryption key schedule with zeros, in a way that is not optimized away
+        ///
+        /// # Safety
+        ///
+        #[doc = concat!("`key` must be a non-null, aligned and valid pointer to a `", stringify!($enc_c), "`, not accessed by")]
+        /// anything else during the call.
+        #[no_mangle]
+        pub extern "C" fn $clear(key: &mut $enc_c) {
+            clear(&mut key.round_keys);
+        }
+
+        /// Overwrites a decryption key schedule with zeros, in a way that is not optimized away
+        ///
+        /// # Safety
+        ///
+        #[doc = concat!("`key` must be a non-null, aligned and valid pointer to a `", stringify!($dec_c), "`, not accessed by")]
+        /// anything else during the call.
+        #[no_mangle]
+        pub extern "C" fn $decrypter_clear(key: &mut $dec_c) {
+            clear(&mut key.round_keys);
+        }
+    };
+}
+
+implement_ffi!(
+    Aes128Enc,
+    Aes128Dec,
+    Aes128EncC,
+    Aes128DecC,
+    16,
+    10,
+    aes128_new,
+    aes128_decrypter,
+    aes128_encrypt_block,
+    aes128_decrypt_block,
+    aes128_clear,
+    aes128_decrypter_clear
+);
+implement_ffi!(
+    Aes192Enc,
+    Aes192Dec,
+    Aes192EncC,
+    Aes192DecC,
+    24,
+    12,
+    aes192_new,
+    aes192_decrypter,
+    aes192_encrypt_block,
+    aes192_decrypt_block,
+    aes192_clear,
+    aes192_decrypter_clear
+);
+implement_ffi!(
+    Aes256Enc,
+    Aes256Dec,
+    Aes256EncC,
+    Aes256DecC,
+    32,
+    14,
+    aes256_new,
+    aes256_decrypter,
+    aes256_encrypt_block,
+    aes256_decrypt_block,
+    aes256_clear,
+    aes256_decrypter_clear
+);
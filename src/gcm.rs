@@ -0,0 +1,291 @@
+#[cfg(feature = "heapless")]
+use crate::aead::{decrypt_to_heapless, encrypt_to_heapless, HeaplessError};
+use crate::aead::{tags_eq, verify_and_release, AuthError};
+use crate::buffer::BlockBuffer;
+use crate::ctr::{CounterWidth, Ctr128};
+use crate::ghash::GhashKey;
+use crate::{AesBlock, AesEncrypt};
+use core::fmt::{self, Debug, Formatter};
+
+/// The maximum length of a GCM message in bytes (`2^32 - 2` blocks)
+const MAX_LEN: u64 = ((1 << 32) - 2) * 16;
+
+/// AES-GCM authenticated encryption (NIST SP 800-38D), with 16-byte tags. The keystream is generated four
+/// blocks at a time by [`Ctr128`], and GHASH uses [`AesBlock::gf128_mul`],
+/// or a constant-time 4-bit table method without a hardware carryless multiplier
+///
+/// ```
+/// # use aes_crypto::{gcm::AesGcm, Aes128Enc};
+/// let gcm = AesGcm::new(Aes128Enc::from([0; 16]));
+/// let nonce = [0; 12];
+///
+/// let mut ciphertext = [0; 13];
+/// let mut tag = [0; 16];
+/// gcm.encrypt(&nonce, b"header", b"hello, world!", &mut ciphertext, &mut tag);
+///
+/// assert!(gcm.decrypt(&nonce, b"header", &mut ciphertext, &tag).is_ok());
+/// assert_eq!(&ciphertext, b"hello, world!");
+/// ```
+#[derive(Debug, Clone)]
+pub struct AesGcm<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> {
+    cipher: E,
+    h: GhashKey,
+}
+
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> AesGcm<E, KEY_LEN> {
+    /// Creates the AEAD from the block cipher, and derives the GHASH key `H` from it
+    #[inline]
+    pub fn new(cipher: E) -> Self {
+        let h = GhashKey::new(cipher.encrypt_block(AesBlock::zero()));
+        Self { cipher, h }
+    }
+
+    fn ghash_update(&self, mut acc: AesBlock, data: &[u8]) -> AesBlock {
+        for chunk in data.chunks(16) {
+            let mut block = [0; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            acc = self.h.mul(acc ^ block.into());
+        }
+        acc
+    }
+
+    fn ghash(&self, aad: &[u8], ciphertext: &[u8]) -> AesBlock {
+        let acc = self.ghash_update(AesBlock::zero(), aad);
+        let acc = self.ghash_update(acc, ciphertext);
+        let lengths = ((aad.len() as u128 * 8) << 64) | (ciphertext.len() as u128 * 8);
+        self.h.mul(acc ^ lengths.into())
+    }
+
+    /// The pre-counter block `J0`, or `None` for an empty nonce. A 96-bit nonce is used directly, and any
+    /// other length is hashed
+    fn j0(&self, nonce: &[u8]) -> Option<AesBlock> {
+        if nonce.is_empty() {
+            None
+        } else if let Ok(nonce) = <[u8; 12]>::try_from(nonce) {
+            let mut block = [0; 16];
+            block[..12].copy_from_slice(&nonce);
+            block[15] = 1;
+            Some(block.into())
+        } else {
+            let acc = self.ghash_update(AesBlock::zero(), nonce);
+            Some(self.h.mul(acc ^ AesBlock::from(nonce.len() as u128 * 8)))
+        }
+    }
+
+    /// The keystream following `j0`. The 32-bit counter may wrap around (if `J0` was hashed), so the
+    /// message length is limited instead
+    fn keystream(&self, j0: AesBlock) -> Ctr128<E, KEY_LEN> {
+        Ctr128::with_counter_width(
+            self.cipher.clone(),
+            j0.incr_be_32().into(),
+            CounterWidth::Bits32,
+        )
+        .wrapping()
+    }
+
+    fn apply_keystream(&self, j0: AesBlock, data: &mut [u8]) {
+        self.keystream(j0)
+            .apply_keystream(data)
+            .expect("the GCM counter never runs out");
+    }
+
+    /// Encrypts `plaintext` into `out`, authenticating it together with `aad`, and writes the tag to `tag`.
+    /// 96-bit nonces are recommended, but any non-empty nonce is supported
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nonce` is empty, if `out` is not the same length as `plaintext`, or if `plaintext` is
+    /// longer than the GCM limit of `2^36 - 32` bytes
+    pub fn encrypt(
+        &self,
+        nonce: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+        out: &mut [u8],
+        tag: &mut [u8; 16],
+    ) {
+        assert_eq!(plaintext.len(), out.len());
+        assert!(
+            plaintext.len() as u64 <= MAX_LEN,
+            "GCM messages are limited to 2^36 - 32 bytes"
+        );
+        let j0 = self.j0(nonce).expect("GCM nonces must not be empty");
+
+        out.copy_from_slice(plaintext);
+        self.apply_keystream(j0, out);
+
+        *tag = (self.ghash(aad, out) ^ self.cipher.encrypt_block(j0)).into();
+    }
+
+    /// Decrypts `data` in place, checking it and `aad` against `tag`. The tags are compared in constant
+    /// time, and on failure `data` is zeroed, so the unauthenticated plaintext is never exposed
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AuthError`] if the tag does not match, if `nonce` is empty, or if `data` is longer than the
+    /// GCM limit of `2^36 - 32` bytes, as no such message can have been encrypted
+    pub fn decrypt(
+        &self,
+        nonce: &[u8],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8; 16],
+    ) -> Result<(), AuthError> {
+        let Some(j0) = self.j0(nonce).filter(|_| data.len() as u64 <= MAX_LEN) else {
+            data.fill(0);
+            return Err(AuthError);
+        };
+        let computed: [u8; 16] = (self.ghash(aad, data) ^ self.cipher.encrypt_block(j0)).into();
+
+        self.apply_keystream(j0, data);
+        verify_and_release(data, tag, &computed)
+    }
+
+    /// Encrypts `plaintext` like [`encrypt`](Self::encrypt), into a [`heapless::Vec`] holding the
+    /// ciphertext followed by the tag, so it needs a capacity of at least `plaintext.len() + 16`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HeaplessError::Capacity`] if the output does not fit in `N` bytes
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nonce` is empty, or if `plaintext` is longer than the GCM limit of `2^36 - 32` bytes
+    #[cfg(feature = "heapless")]
+    pub fn encrypt_to_heapless<const N: usize>(
+        &self,
+        nonce: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<heapless::Vec<u8, N>, HeaplessError> {
+        encrypt_to_heapless(plaintext, |out, tag| {
+            self.encrypt(nonce, aad, plaintext, out, tag);
+        })
+    }
+
+    /// Decrypts `ciphertext`, which holds the ciphertext followed by the 16-byte tag (as written by
+    /// [`encrypt_to_heapless`](Self::encrypt_to_heapless)), into a [`heapless::Vec`]. As with
+    /// [`decrypt`](Self::decrypt), no plaintext is returned if the tag does not match
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HeaplessError::Auth`] if `ciphertext` is shorter than a tag or would be rejected by
+    /// [`decrypt`](Self::decrypt), and [`HeaplessError::Capacity`] if the plaintext does not fit in `N` bytes
+    #[cfg(feature = "heapless")]
+    pub fn decrypt_to_heapless<const N: usize>(
+        &self,
+        nonce: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<heapless::Vec<u8, N>, HeaplessError> {
+        decrypt_to_heapless(ciphertext, |data, tag| self.decrypt(nonce, aad, data, tag))
+    }
+
+    /// Starts decrypting a message in pieces, for messages that are too large to hold in memory. See
+    /// [`AesGcmDecryptor`] for the precautions this needs
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AuthError`] if `nonce` is empty, as no message can have been encrypted with it
+    pub fn decryptor(
+        &self,
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<AesGcmDecryptor<E, KEY_LEN>, AuthError> {
+        let j0 = self.j0(nonce).ok_or(AuthError)?;
+        Ok(AesGcmDecryptor {
+            ctr: self.keystream(j0),
+            h: self.h.clone(),
+            acc: self.ghash_update(AesBlock::zero(), aad),
+            buffer: BlockBuffer::new(),
+            aad_len: aad.len() as u64,
+            len: 0,
+            tag_mask: self.cipher.encrypt_block(j0),
+        })
+    }
+}
+
+/// Streaming AES-GCM decryption, created by [`AesGcm::decryptor`]. The ciphertext can be fed in pieces of
+/// any size with [`update`](Self::update), and [`verify`](Self::verify) checks the tag at the end.
+///
+/// **The plaintext returned by `update` is unauthenticated until `verify` succeeds**, and it may have been
+/// chosen by an attacker. It must not be used in any way before then (parsed, acted on, or written where
+/// it can be mistaken for authenticated data), e.g. write it to a temporary file that is only renamed
+/// after a successful `verify`, and deleted otherwise. If the whole message fits in memory,
+/// [`AesGcm::decrypt`] is safer, as it never exposes unauthenticated plaintext.
+///
+/// ```
+/// # use aes_crypto::{gcm::AesGcm, Aes128Enc};
+/// let gcm = AesGcm::new(Aes128Enc::from([0; 16]));
+/// let mut message = *b"a message that arrives in pieces";
+/// let mut tag = [0; 16];
+/// gcm.encrypt(&[0; 12], b"header", &message.clone(), &mut message, &mut tag);
+///
+/// let mut decryptor = gcm.decryptor(&[0; 12], b"header").unwrap();
+/// for piece in message.chunks_mut(5) {
+///     decryptor.update(piece);
+/// }
+/// decryptor.verify(&tag).unwrap();
+/// assert_eq!(&message, b"a message that arrives in pieces");
+/// ```
+#[derive(Clone)]
+pub struct AesGcmDecryptor<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> {
+    ctr: Ctr128<E, KEY_LEN>,
+    h: GhashKey,
+    acc: AesBlock,
+    buffer: BlockBuffer,
+    aad_len: u64,
+    len: u64,
+    tag_mask: AesBlock,
+}
+
+/// Prints only the type name, as the state includes the keystream and the tag mask
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> Debug for AesGcmDecryptor<E, KEY_LEN> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AesGcmDecryptor").finish_non_exhaustive()
+    }
+}
+
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> AesGcmDecryptor<E, KEY_LEN> {
+    /// Authenticates and decrypts the next piece of the ciphertext in place. The result must not be used
+    /// before [`verify`](Self::verify) succeeds. Once the total length exceeds the GCM limit of `2^36 - 32`
+    /// bytes, the pieces are zeroed instead, and `verify` fails
+    pub fn update(&mut self, data: &mut [u8]) {
+        self.len = self.len.saturating_add(data.len() as u64);
+        if self.len > MAX_LEN {
+            data.fill(0);
+            return;
+        }
+
+        let (h, acc) = (&self.h, &mut self.acc);
+        self.buffer
+            .input_block(data, |block| *acc = h.mul(*acc ^ block));
+        self.ctr
+            .apply_keystream(data)
+            .expect("the GCM counter never runs out");
+    }
+
+    /// Checks the whole ciphertext and the associated data against `tag`, in constant time. Only after
+    /// this returns `Ok` can the plaintext returned by [`update`](Self::update) be trusted
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AuthError`] if the tag does not match or the ciphertext was too long, in which case all the
+    /// plaintext must be discarded
+    pub fn verify(mut self, tag: &[u8; 16]) -> Result<(), AuthError> {
+        if self.len > MAX_LEN {
+            return Err(AuthError);
+        }
+        if let Some(block) = self.buffer.pad_and_finalize() {
+            self.acc = self.h.mul(self.acc ^ block);
+        }
+        let lengths = (u128::from(self.aad_len * 8) << 64) | u128::from(self.len * 8);
+        let computed: [u8; 16] = (self.h.mul(self.acc ^ lengths.into()) ^ self.tag_mask).into();
+
+        if tags_eq(tag, &computed) {
+            Ok(())
+        } else {
+            Err(AuthError)
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! AES-GCM-SIV nonce-misuse-resistant authenticated encryption
+//! ([RFC 8452](https://www.rfc-editor.org/rfc/rfc8452)). Repeating a nonce only reveals whether the same
+//! message was encrypted twice with the same AAD, instead of breaking confidentiality and authenticity as
+//! in AES-GCM. The price is that encryption has to make two passes over the plaintext
+//!
+//! ```
+//! # use aes_crypto::{gcm_siv::AesGcmSiv, Aes256Enc};
+//! let siv = AesGcmSiv::new(Aes256Enc::from([0; 32]));
+//! let nonce = [0; 12];
+//!
+//! let mut ciphertext = [0; 13];
+//! let mut tag = [0; 16];
+//! siv.encrypt(&nonce, b"header", b"hello, world!", &mut ciphertext, &mut tag);
+//!
+//! assert!(siv.decrypt(&nonce, b"header", &mut ciphertext, &tag).is_ok());
+//! assert_eq!(&ciphertext, b"hello, world!");
+//! ```
+
+#[cfg(feature = "heapless")]
+use crate::aead::{decrypt_to_heapless, encrypt_to_heapless, HeaplessError};
+use crate::aead::{verify_and_release, AuthError};
+use crate::ctr::xor_in_place;
+use crate::polyval::Polyval;
+use crate::{Aes128Enc, Aes256Enc, AesBlock, AesBlockX4, AesEncrypt};
+
+/// The maximum length of the plaintext and of the AAD in bytes
+const MAX_LEN: u64 = 1 << 36;
+
+/// AES-GCM-SIV with the key-generating key `cipher`. Every message gets its own authentication and
+/// encryption keys, derived from the key-generating key and the nonce, so the key schedule runs once per
+/// message. Only 128- and 256-bit keys are defined by the RFC
+#[derive(Debug, Clone)]
+pub struct AesGcmSiv<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> {
+    cipher: E,
+}
+
+/// AEAD_AES_128_GCM_SIV
+pub type Aes128GcmSiv = AesGcmSiv<Aes128Enc, 16>;
+
+/// AEAD_AES_256_GCM_SIV
+pub type Aes256GcmSiv = AesGcmSiv<Aes256Enc, 32>;
+
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> AesGcmSiv<E, KEY_LEN> {
+    /// Creates the AEAD from the block cipher under the key-generating key
+    ///
+    /// # Panics
+    ///
+    /// Panics for AES-192, which RFC 8452 does not define
+    #[inline]
+    pub fn new(cipher: E) -> Self {
+        assert!(
+            KEY_LEN == 16 || KEY_LEN == 32,
+            "AES-GCM-SIV is only defined for 128- and 256-bit keys"
+        );
+        Self { cipher }
+    }
+
+    /// Derives the message-authentication key and the message-encryption key of `nonce`, from the first
+    /// 8 bytes of the encryptions of the blocks `LE32(i) || nonce` for `i = 0, 1, ...`
+    fn derive_keys(&self, nonce: &[u8; 12]) -> (Polyval, E) {
+        let mut auth_key = [0; 16];
+        let mut enc_key = [0; KEY_LEN];
+
+        let mut block = [0; 16];
+        block[4..].copy_from_slice(nonce);
+        for (i, half) in (0_u32..).zip(
+            auth_key
+                .chunks_exact_mut(8)
+                .chain(enc_key.chunks_exact_mut(8)),
+        ) {
+            block[..4].copy_from_slice(&i.to_le_bytes());
+            half.copy_from_slice(&self.cipher.encrypt_block(block.into()).to_bytes()[..8]);
+        }
+        (Polyval::new(auth_key.into()), E::from(enc_key))
+    }
+
+    /// The tag is the encryption of the POLYVAL of the padded AAD, the padded plaintext and their lengths,
+    /// XORed with the nonce and with its most significant bit cleared
+    fn tag(
+        mut polyval: Polyval,
+        enc_key: &E,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> AesBlock {
+        assert!(
+            aad.len() as u64 <= MAX_LEN && plaintext.len() as u64 <= MAX_LEN,
+            "AES-GCM-SIV messages and AAD are limited to 2^36 bytes"
+        );
+        polyval.update_padded(aad);
+        polyval.update_padded(plaintext);
+        polyval.update(AesBlock::from_u64_le_pair(
+            aad.len() as u64 * 8,
+            plaintext.len() as u64 * 8,
+        ));
+
+        let mut s = polyval.finalize().to_bytes();
+        xor_in_place(&mut s[..12], nonce);
+        s[15] &= 0x7f;
+        enc_key.encrypt_block(s.into())
+    }
+
+    /// Applies the keystream of the counter blocks starting at `tag` with its most significant bit set.
+    /// Only the first 32 bits are a counter, incremented as a little-endian integer that wraps around
+    fn apply_keystream(enc_key: &E, tag: AesBlock, data: &mut [u8]) {
+        let mut block = tag.to_bytes();
+        block[15] |= 0x80;
+        let mut counter = u32::from_le_bytes(block[..4].try_into().unwrap());
+        let mut next_counter = || {
+            block[..4].copy_from_slice(&counter.to_le_bytes());
+            counter = counter.wrapping_add(1);
+            AesBlock::from(block)
+        };
+
+        let mut chunks = data.chunks_exact_mut(64);
+        for chunk in &mut chunks {
+            let counters = AesBlockX4::from((
+                next_counter(),
+                next_counter(),
+                next_counter(),
+                next_counter(),
+            ));
+            let keystream = enc_key.encrypt_4_blocks(counters);
+            (keystream ^ AesBlockX4::try_from(&chunk[..]).unwrap()).store_to(chunk);
+        }
+        for chunk in chunks.into_remainder().chunks_mut(16) {
+            let keystream = enc_key.encrypt_block(next_counter()).to_bytes();
+            xor_in_place(chunk, &keystream[..chunk.len()]);
+        }
+    }
+
+    /// Encrypts `plaintext` into `out`, authenticating it together with `aad`, and writes the tag to `tag`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is not the same length as `plaintext`, or if `plaintext` or `aad` is longer than
+    /// `2^36` bytes
+    pub fn encrypt(
+        &self,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        plaintext: &[u8],
+        out: &mut [u8],
+        tag: &mut [u8; 16],
+    ) {
+        assert_eq!(plaintext.len(), out.len());
+        let (polyval, enc_key) = self.derive_keys(nonce);
+        let computed = Self::tag(polyval, &enc_key, nonce, aad, plaintext);
+
+        out.copy_from_slice(plaintext);
+        Self::apply_keystream(&enc_key, computed, out);
+        *tag = computed.into();
+    }
+
+    /// Decrypts `data` in place, checking it and `aad` against `tag`. The tags are compared in constant
+    /// time, and on failure `data` is zeroed, so the unauthenticated plaintext is never exposed
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AuthError`] if the tag does not match
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` or `aad` is longer than `2^36` bytes
+    pub fn decrypt(
+        &self,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8; 16],
+    ) -> Result<(), AuthError> {
+        let (polyval, enc_key) = self.derive_keys(nonce);
+        Self::apply_keystream(&enc_key, tag.into(), data);
+
+        let computed: [u8; 16] = Self::tag(polyval, &enc_key, nonce, aad, data).into();
+        verify_and_release(data, tag, &computed)
+    }
+
+    /// Encrypts `plaintext` like [`encrypt`](Self::encrypt), into a [`heapless::Vec`] holding the
+    /// ciphertext followed by the tag, so it needs a capacity of at least `plaintext.len() + 16`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HeaplessError::Capacity`] if the output does not fit in `N` bytes
+    ///
+    /// # Panics
+    ///
+    /// Panics if `plaintext` or `aad` is longer than `2^36` bytes
+    #[cfg(feature = "heapless")]
+    pub fn encrypt_to_heapless<const N: usize>(
+        &self,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<heapless::Vec<u8, N>, HeaplessError> {
+        encrypt_to_heapless(plaintext, |out, tag| {
+            self.encrypt(nonce, aad, plaintext, out, tag);
+        })
+    }
+
+    /// Decrypts `ciphertext`, which holds the ciphertext followed by the 16-byte tag (as written by
+    /// [`encrypt_to_heapless`](Self::encrypt_to_heapless)), into a [`heapless::Vec`]. As with
+    /// [`decrypt`](Self::decrypt), no plaintext is returned if the tag does not match
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HeaplessError::Auth`] if `ciphertext` is shorter than a tag or the tag does not match,
+    /// and [`HeaplessError::Capacity`] if the plaintext does not fit in `N` bytes
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ciphertext or `aad` is longer than `2^36` bytes
+    #[cfg(feature = "heapless")]
+    pub fn decrypt_to_heapless<const N: usize>(
+        &self,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<heapless::Vec<u8, N>, HeaplessError> {
+        decrypt_to_heapless(ciphertext, |data, tag| self.decrypt(nonce, aad, data, tag))
+    }
+}
@@ -0,0 +1,171 @@
+//! The GHASH universal hash of AES-GCM (NIST SP 800-38D), for protocols that need it outside of
+//! [`AesGcm`](crate::gcm::AesGcm), e.g. to compute a GMAC over data that is not contiguous in memory
+
+use crate::{AesBlock, AesBlockX4, HAS_HARDWARE_CLMUL};
+use core::fmt::{self, Debug, Formatter};
+
+/// The hash key `H` of GHASH, used for multiplications in GF(2^128) with the GCM conventions.
+///
+/// With a hardware carryless multiplier (see [`HAS_HARDWARE_CLMUL`]), this uses [`AesBlock::gf128_mul`].
+/// Otherwise the bitwise software `clmul` is very slow, so the multiples of `H` by every 4-bit polynomial
+/// are precomputed, and the product is computed 4 bits at a time. The table is scanned in full for every
+/// lookup, so this is still constant-time
+#[derive(Clone)]
+pub(crate) struct GhashKey {
+    h: AesBlock,
+    table: [u128; 16],
+}
+
+/// Prints only the type name, as `H` is secret: it is enough to forge GCM tags
+impl Debug for GhashKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GhashKey").finish_non_exhaustive()
+    }
+}
+
+/// Multiplies by `x` in the bit-reflected convention, which is a right shift followed by a conditional
+/// reduction by `x^128 = x^7 + x^2 + x + 1`
+#[inline(always)]
+pub(crate) const fn mul_x(value: u128) -> u128 {
+    (value >> 1) ^ (0xe1 << 120 & 0_u128.wrapping_sub(value & 1))
+}
+
+impl GhashKey {
+    #[inline]
+    pub(crate) fn new(h: AesBlock) -> Self {
+        let table = if HAS_HARDWARE_CLMUL {
+            [0; 16]
+        } else {
+            Self::table(h.into())
+        };
+        Self { h, table }
+    }
+
+    /// `table[i]` is the product of `H` and the polynomial whose coefficients of `x^0..x^3` are the bits of
+    /// `i` from the most significant one
+    fn table(h: u128) -> [u128; 16] {
+        let mut table = [0; 16];
+        table[8] = h;
+        table[4] = mul_x(h);
+        table[2] = mul_x(table[4]);
+        table[1] = mul_x(table[2]);
+        for i in [3, 5, 6, 7, 9, 10, 11, 12, 13, 14, 15] {
+            let low = i & (i - 1);
+            table[i] = table[low] ^ table[i ^ low];
+        }
+        table
+    }
+
+    /// Multiplies `x` by `H`
+    #[inline]
+    pub(crate) fn mul(&self, x: AesBlock) -> AesBlock {
+        if HAS_HARDWARE_CLMUL {
+            x.gf128_mul(self.h)
+        } else {
+            self.mul_table(x)
+        }
+    }
+
+    /// Multiplies `x` by `H` using the 4-bit table, from the highest powers of `x` down (Horner's rule)
+    pub(crate) fn mul_table(&self, x: AesBlock) -> AesBlock {
+        let table = if HAS_HARDWARE_CLMUL {
+            &Self::table(self.h.into())
+        } else {
+            &self.table
+        };
+
+        let x = u128::from(x);
+        let mut acc = 0;
+        for nibble in 0..32 {
+            acc = mul_x(mul_x(mul_x(mul_x(acc))));
+
+            let index = (x >> (4 * nibble)) & 0xf;
+            for (i, &entry) in (0..).zip(table) {
+                // all-ones if `i == index`, without branching on the secret index
+                let mask = 0_u128.wrapping_sub((i ^ index).wrapping_sub(1) >> 127);
+                acc ^= entry & mask;
+            }
+        }
+        acc.into()
+    }
+}
+
+/// A GHASH computation under the hash key `H`, which absorbs 16-byte blocks and returns the final
+/// accumulator. For AES-GCM, `H` is the encryption of the zero block, and the tag is the hash of the
+/// padded AAD, the padded ciphertext and the lengths block, XORed with the encrypted pre-counter block.
+///
+/// The powers `H^1..H^4` are precomputed, so four blocks are absorbed with four independent
+/// multiplications (`acc' = (acc ^ b0)·H^4 ^ b1·H^3 ^ b2·H^2 ^ b3·H`) instead of four dependent ones,
+/// which keeps up with [`AesBlockX4`] encryption
+///
+/// ```
+/// # use aes_crypto::{ghash::GHash, AesBlock};
+/// let mut ghash = GHash::new(AesBlock::from(0x66e94bd4ef8a2c3b884cfa59ca342b2e));
+/// ghash.update(AesBlock::from(0x0388dace60b6a392f328c2b971b2fe78));
+/// ghash.update(AesBlock::from(0x80));
+/// assert_eq!(ghash.finalize(), AesBlock::from(0xf38cbb1ad69223dcc3457ae5b6b0f885));
+/// ```
+#[derive(Clone)]
+pub struct GHash {
+    /// `H^1..H^4`
+    powers: [GhashKey; 4],
+    acc: AesBlock,
+}
+
+/// Prints only the type name, as the accumulator gives away the hash key once a block is known
+impl Debug for GHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GHash").finish_non_exhaustive()
+    }
+}
+
+impl GHash {
+    /// Creates a GHASH computation with hash key `h` and an all-zero accumulator
+    #[inline]
+    #[must_use]
+    pub fn new(h: AesBlock) -> Self {
+        let h1 = GhashKey::new(h);
+        let h2 = h1.mul(h);
+        let h3 = h1.mul(h2);
+        let h4 = h1.mul(h3);
+        Self {
+            powers: [h1, GhashKey::new(h2), GhashKey::new(h3), GhashKey::new(h4)],
+            acc: AesBlock::zero(),
+        }
+    }
+
+    /// Absorbs a single block
+    #[inline]
+    pub fn update(&mut self, block: AesBlock) {
+        self.acc = self.powers[0].mul(self.acc ^ block);
+    }
+
+    /// Absorbs four blocks, in the order of [`AesBlockX4::to_gcm_order`]
+    #[inline]
+    pub fn update_4_blocks(&mut self, blocks: AesBlockX4) {
+        let [b0, b1, b2, b3] = blocks.to_gcm_order();
+        let [h1, h2, h3, h4] = &self.powers;
+        self.acc = h4.mul(self.acc ^ b0) ^ h3.mul(b1) ^ h2.mul(b2) ^ h1.mul(b3);
+    }
+
+    /// Absorbs `bytes`, zero-padding the last block if the length is not a multiple of 16, as GCM does
+    /// for the AAD and the ciphertext. The padding is applied at the end of every call, so data split
+    /// across several calls has to be split at block boundaries to hash the same as a single call
+    pub fn update_padded(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(64);
+        for chunk in &mut chunks {
+            self.update_4_blocks(AesBlockX4::try_from(chunk).unwrap());
+        }
+        for chunk in chunks.remainder().chunks(16) {
+            let mut block = [0; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.update(block.into());
+        }
+    }
+
+    /// Returns the accumulator, which is the GHASH of all the blocks absorbed so far
+    #[inline]
+    pub fn finalize(self) -> AesBlock {
+        self.acc
+    }
+}
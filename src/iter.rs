@@ -0,0 +1,107 @@
+use crate::{AesBlock, AesBlockX4, AesEncrypt};
+
+/// Extension methods for iterators over [`AesBlock`]s
+pub trait AesBlockIteratorExt: Iterator<Item = AesBlock> + Sized {
+    /// Lazily encrypts every block of the iterator with `cipher`, i.e. in ECB mode. The blocks are pulled
+    /// from the underlying iterator four at a time, to encrypt them with `encrypt_4_blocks`.
+    ///
+    /// ECB mode encrypts equal plaintext blocks to equal ciphertext blocks, so it leaks patterns in the
+    /// data, and is not a secure way to encrypt messages longer than a block. It is only useful as a
+    /// building block for other constructions
+    ///
+    /// ```
+    /// # use aes_crypto::{iter::AesBlockIteratorExt, AesBlock, AesEncrypt, Aes128Enc};
+    /// let cipher = Aes128Enc::from([0; 16]);
+    /// for (i, block) in (0..5_u128).zip((0..5).map(AesBlock::from).encrypt_with(&cipher)) {
+    ///     assert_eq!(block, cipher.encrypt_block(AesBlock::from(i)));
+    /// }
+    /// ```
+    fn encrypt_with<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(
+        self,
+        cipher: &E,
+    ) -> EncryptWith<'_, Self, E, KEY_LEN>;
+}
+
+impl<I: Iterator<Item = AesBlock>> AesBlockIteratorExt for I {
+    #[inline]
+    fn encrypt_with<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(
+        self,
+        cipher: &E,
+    ) -> EncryptWith<'_, Self, E, KEY_LEN> {
+        EncryptWith {
+            iter: self,
+            cipher,
+            buffer: [AesBlock::zero(); 4],
+            pos: 0,
+            len: 0,
+        }
+    }
+}
+
+/// The iterator returned by [`AesBlockIteratorExt::encrypt_with`]
+#[derive(Debug, Clone)]
+pub struct EncryptWith<'a, I, E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> {
+    iter: I,
+    cipher: &'a E,
+    buffer: [AesBlock; 4],
+    pos: usize,
+    len: usize,
+}
+
+impl<I: Iterator<Item = AesBlock>, E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>
+    EncryptWith<'_, I, E, KEY_LEN>
+{
+    fn refill(&mut self) {
+        self.pos = 0;
+        self.len = 0;
+        while self.len < 4 {
+            let Some(block) = self.iter.next() else {
+                break;
+            };
+            self.buffer[self.len] = block;
+            self.len += 1;
+        }
+
+        if self.len == 4 {
+            let data = AesBlockX4::from(self.buffer);
+            (
+                self.buffer[0],
+                self.buffer[1],
+                self.buffer[2],
+                self.buffer[3],
+            ) = self.cipher.encrypt_4_blocks(data).into();
+        } else {
+            for block in &mut self.buffer[..self.len] {
+                *block = self.cipher.encrypt_block(*block);
+            }
+        }
+    }
+}
+
+impl<I: Iterator<Item = AesBlock>, E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> Iterator
+    for EncryptWith<'_, I, E, KEY_LEN>
+{
+    type Item = AesBlock;
+
+    #[inline]
+    fn next(&mut self) -> Option<AesBlock> {
+        if self.pos == self.len {
+            self.refill();
+            if self.len == 0 {
+                return None;
+            }
+        }
+        self.pos += 1;
+        Some(self.buffer[self.pos - 1])
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.len - self.pos;
+        let (lower, upper) = self.iter.size_hint();
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
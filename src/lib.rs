@@ -38,8 +38,85 @@
 )]
 
 use cfg_if::cfg_if;
+use core::cmp::Ordering;
 use core::fmt::{self, Binary, Debug, Display, Formatter, LowerHex, UpperHex};
-use core::ops::{BitAndAssign, BitOrAssign, BitXorAssign};
+use core::hash::{Hash, Hasher};
+use core::ops::{BitAndAssign, BitOrAssign, BitXorAssign, Neg};
+
+// Only the software implementations can expand keys in `const` contexts, so only their branches below
+// add `new_const` to the encryption key types
+#[allow(unused_macros)]
+macro_rules! impl_new_const {
+    ($($enc_name:ident, $key_len:literal, $keygen:ident);*) => {$(
+        impl $enc_name {
+            /// Expands `key` in a `const` context, e.g. to embed a precomputed key schedule in a `static`, so
+            /// that no key expansion happens at runtime. This is only available with the software
+            /// implementations, as the hardware ones cannot be evaluated at compile time
+            ///
+            /// ```ignore
+            #[doc = concat!("# use aes_crypto::{", stringify!($enc_name), ", AesEncrypt};")]
+            #[doc = concat!("static KEY: ", stringify!($enc_name), " = ", stringify!($enc_name), "::new_const([0x42; ", $key_len, "]);")]
+            ///
+            #[doc = concat!("assert_eq!(KEY.round_keys(), ", stringify!($enc_name), "::from([0x42; ", $key_len, "]).round_keys());")]
+            /// ```
+            #[inline]
+            #[must_use]
+            pub const fn new_const(key: [u8; $key_len]) -> Self {
+                Self::from_round_keys($keygen(key))
+            }
+        }
+    )*};
+}
+
+// Every backend except the table-based one stores a block as its 16 bytes in order, so those branches
+// below borrow the storage in `as_bytes`, and the table-based one has to return a copy instead
+macro_rules! impl_as_bytes {
+    (borrowed) => {
+        const _: () = assert!(BLOCKS_ARE_BYTES);
+
+        impl AesBlock {
+            /// Returns the bytes of the block, in the same order as [`to_bytes`](Self::to_bytes) on every
+            /// backend and host (the first byte is the first byte of the block in memory, and the most
+            /// significant byte of `u128::from`). This borrows the block's storage without copying, except
+            /// with the table-based software implementation, which stores big-endian words, so there this
+            /// returns a copy of the bytes. Portable code can use the result as `&[u8; 16]` through
+            /// indexing, iteration and `&block.as_bytes()[..]`
+            #[inline]
+            #[must_use]
+            pub fn as_bytes(&self) -> &[u8; 16] {
+                // the block is 16 bytes of plain data laid out as its bytes in order
+                unsafe { &*core::ptr::from_ref(self).cast() }
+            }
+        }
+
+        impl AsRef<[u8; 16]> for AesBlock {
+            #[inline]
+            fn as_ref(&self) -> &[u8; 16] {
+                self.as_bytes()
+            }
+        }
+
+        impl AsRef<[u8]> for AesBlock {
+            #[inline]
+            fn as_ref(&self) -> &[u8] {
+                self.as_bytes()
+            }
+        }
+    };
+    (copied) => {
+        impl AesBlock {
+            /// Returns the bytes of the block, in the same order as [`to_bytes`](Self::to_bytes). The other
+            /// backends borrow the block's storage here, but the table-based software implementation stores
+            /// big-endian words, so this returns a copy. Portable code can use the result as `&[u8; 16]`
+            /// through indexing, iteration and `&block.as_bytes()[..]`
+            #[inline]
+            #[must_use]
+            pub const fn as_bytes(&self) -> [u8; 16] {
+                self.to_bytes()
+            }
+        }
+    };
+}
 
 cfg_if! {
     if #[cfg(all(
@@ -49,7 +126,52 @@ cfg_if! {
     ))] {
         mod aes_x86;
         pub use aes_x86::AesBlock;
+        impl_as_bytes!(borrowed);
         use aes_x86::*;
+        const AES_IMPL: &str = "AES-NI";
+        const INTERLEAVE_BLOCKS: bool = true;
+        /// Whether the selected `AesBlock` implementation is constant-time
+        pub const IS_CONSTANT_TIME: bool = true;
+        /// Whether [`AesBlock::clmul`] uses a hardware carryless multiplier
+        pub const HAS_HARDWARE_CLMUL: bool = cfg!(target_feature = "pclmulqdq");
+    } else if #[cfg(all(
+        feature = "gfni",
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse4.1",
+        target_feature = "gfni",
+    ))] {
+        mod aes_gfni;
+        pub use aes_gfni::AesBlock;
+        impl_as_bytes!(borrowed);
+        use aes_gfni::*;
+        const AES_IMPL: &str = "AES-GFNI";
+        const INTERLEAVE_BLOCKS: bool = true;
+        /// Whether the selected `AesBlock` implementation is constant-time
+        pub const IS_CONSTANT_TIME: bool = true;
+        /// Whether [`AesBlock::clmul`] uses a hardware carryless multiplier
+        pub const HAS_HARDWARE_CLMUL: bool = cfg!(target_feature = "pclmulqdq");
+    } else if #[cfg(all(
+        feature = "runtime-detect",
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2",
+    ))] {
+        // only the round functions and key expansion of the software fallback are used
+        #[cfg(feature = "constant-time")]
+        #[allow(dead_code)]
+        mod aes_bitslice;
+        #[cfg(not(feature = "constant-time"))]
+        #[allow(dead_code)]
+        mod aes_table_based;
+        mod aes_x86_detect;
+        pub use aes_x86_detect::{set_backend_hook, AesBlock};
+        impl_as_bytes!(borrowed);
+        use aes_x86_detect::*;
+        const AES_IMPL: &str = "Runtime-detected AES-NI or Software AES";
+        const INTERLEAVE_BLOCKS: bool = true;
+        /// Whether the selected `AesBlock` implementation is constant-time
+        pub const IS_CONSTANT_TIME: bool = cfg!(feature = "constant-time");
+        /// Whether [`AesBlock::clmul`] uses a hardware carryless multiplier
+        pub const HAS_HARDWARE_CLMUL: bool = false;
     } else if #[cfg(all(
         any(
             target_arch = "aarch64",
@@ -61,7 +183,14 @@ cfg_if! {
     ))] {
         mod aes_arm;
         pub use aes_arm::AesBlock;
+        impl_as_bytes!(borrowed);
         use aes_arm::*;
+        const AES_IMPL: &str = "AES-Neon";
+        const INTERLEAVE_BLOCKS: bool = true;
+        /// Whether the selected `AesBlock` implementation is constant-time
+        pub const IS_CONSTANT_TIME: bool = true;
+        /// Whether [`AesBlock::clmul`] uses a hardware carryless multiplier
+        pub const HAS_HARDWARE_CLMUL: bool = cfg!(not(target_arch = "arm"));
     } else if #[cfg(all(
         feature = "nightly",
         target_arch = "riscv64",
@@ -70,7 +199,14 @@ cfg_if! {
     ))] {
         mod aes_riscv64;
         pub use aes_riscv64::AesBlock;
+        impl_as_bytes!(borrowed);
         use aes_riscv64::*;
+        const AES_IMPL: &str = "AES-RV64";
+        const INTERLEAVE_BLOCKS: bool = true;
+        /// Whether the selected `AesBlock` implementation is constant-time
+        pub const IS_CONSTANT_TIME: bool = true;
+        /// Whether [`AesBlock::clmul`] uses a hardware carryless multiplier
+        pub const HAS_HARDWARE_CLMUL: bool = false;
     } else if #[cfg(all(
         feature = "nightly",
         target_arch = "riscv32",
@@ -79,15 +215,53 @@ cfg_if! {
     ))] {
         mod aes_riscv32;
         pub use aes_riscv32::AesBlock;
+        impl_as_bytes!(borrowed);
         use aes_riscv32::*;
+        const AES_IMPL: &str = "AES-RV32";
+        const INTERLEAVE_BLOCKS: bool = true;
+        /// Whether the selected `AesBlock` implementation is constant-time
+        pub const IS_CONSTANT_TIME: bool = true;
+        /// Whether [`AesBlock::clmul`] uses a hardware carryless multiplier
+        pub const HAS_HARDWARE_CLMUL: bool = false;
+    } else if #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))] {
+        mod aes_wasm;
+        pub use aes_wasm::AesBlock;
+        impl_as_bytes!(borrowed);
+        use aes_wasm::*;
+        const AES_IMPL: &str = "WASM SIMD128";
+        const INTERLEAVE_BLOCKS: bool = true;
+        /// Whether the selected `AesBlock` implementation is constant-time
+        pub const IS_CONSTANT_TIME: bool = true;
+        /// Whether [`AesBlock::clmul`] uses a hardware carryless multiplier
+        pub const HAS_HARDWARE_CLMUL: bool = false;
     } else if #[cfg(feature = "constant-time")]{
         mod aes_bitslice;
         pub use aes_bitslice::AesBlock;
+        impl_as_bytes!(borrowed);
         use aes_bitslice::*;
+        const AES_IMPL: &str = "Constant-time Software AES";
+        const INTERLEAVE_BLOCKS: bool = false;
+        impl_new_const!(Aes128Enc, 16, keygen_128; Aes192Enc, 24, keygen_192; Aes256Enc, 32, keygen_256);
+        /// Whether the selected `AesBlock` implementation is constant-time
+        pub const IS_CONSTANT_TIME: bool = true;
+        /// Whether [`AesBlock::clmul`] uses a hardware carryless multiplier
+        pub const HAS_HARDWARE_CLMUL: bool = false;
     } else {
         mod aes_table_based;
         pub use aes_table_based::AesBlock;
+        impl_as_bytes!(copied);
         use aes_table_based::*;
+        const AES_IMPL: &str = "Software AES";
+        const INTERLEAVE_BLOCKS: bool = false;
+        impl_new_const!(
+            Aes128Enc, 16, keygen_128_const;
+            Aes192Enc, 24, keygen_192_const;
+            Aes256Enc, 32, keygen_256_const
+        );
+        /// Whether the selected `AesBlock` implementation is constant-time
+        pub const IS_CONSTANT_TIME: bool = false;
+        /// Whether [`AesBlock::clmul`] uses a hardware carryless multiplier
+        pub const HAS_HARDWARE_CLMUL: bool = false;
     }
 }
 
@@ -99,9 +273,15 @@ cfg_if! {
     ))] {
         mod aesni_x2;
         pub use aesni_x2::AesBlockX2;
+        const AES_X2_IMPL: &str = "AES-NI with VAES";
+        /// Whether [`AesBlockX2`] is a single native vector, rather than a pair of [`AesBlock`]s
+        pub const HAS_NATIVE_X2: bool = true;
     } else {
         mod aesdefault_x2;
         pub use aesdefault_x2::AesBlockX2;
+        const AES_X2_IMPL: &str = "2 x AesBlock";
+        /// Whether [`AesBlockX2`] is a single native vector, rather than a pair of [`AesBlock`]s
+        pub const HAS_NATIVE_X2: bool = false;
     }
 }
 
@@ -114,21 +294,67 @@ cfg_if! {
     ))] {
         mod aesni_x4;
         pub use aesni_x4::AesBlockX4;
+        const AES_X4_IMPL: &str = "AES-NI with VAES and AVX-512";
+        /// Whether [`AesBlockX4`] is a single native vector, rather than a pair of [`AesBlockX2`]s
+        pub const HAS_NATIVE_X4: bool = true;
     } else {
         mod aesdefault_x4;
         pub use aesdefault_x4::AesBlockX4;
+        const AES_X4_IMPL: &str = "2 x AesBlockX2";
+        /// Whether [`AesBlockX4`] is a single native vector, rather than a pair of [`AesBlockX2`]s
+        pub const HAS_NATIVE_X4: bool = false;
     }
 }
 
+mod aesdefault_x8;
+pub use aesdefault_x8::AesBlockX8;
+
+// a native 4-wide backend is always native 2-wide too, so the bulk functions never emulate their tails
+const _: () = assert!(!HAS_NATIVE_X4 || HAS_NATIVE_X2);
+
+// The bulk functions keep several blocks in flight with every backend except the software ones (`INTERLEAVE_BLOCKS`),
+// as the round instructions have a latency that independent blocks hide, even with an emulated `AesBlockX4`
+const _: () = assert!(!HAS_NATIVE_X4 || INTERLEAVE_BLOCKS);
+
+/// Re-exported so that the zeroizing variants of the getters (e.g. `Aes128Enc::round_keys_zeroizing`) can be
+/// used without depending on `zeroize`
+#[cfg(feature = "zeroize")]
+pub use zeroize::Zeroizing;
+
+pub mod aead;
+pub mod aegis;
+pub mod buffer;
+pub mod cbc;
+pub mod cfb;
+pub mod cmac;
+pub mod compress;
+pub mod ctr;
+pub mod drbg;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod gcm;
+pub mod gcm_siv;
+pub mod ghash;
+pub mod iter;
+pub mod ofb;
+pub mod polyval;
+pub mod xts;
+
+#[cfg(feature = "serde")]
+mod serde_impls;
+
 #[cfg(test)]
 mod tests;
 
 #[inline(always)]
-fn try_from_slice<const N: usize, T: From<[u8; N]>>(value: &[u8]) -> Result<T, usize> {
+fn try_from_slice<const N: usize, T: From<[u8; N]>>(value: &[u8]) -> Result<T, InvalidLength> {
     if value.len() >= N {
         Ok(array_from_slice(value, 0).into())
     } else {
-        Err(value.len())
+        Err(InvalidLength {
+            got: value.len(),
+            need: N,
+        })
     }
 }
 
@@ -139,6 +365,53 @@ const fn array_from_slice<const N: usize>(value: &[u8], offset: usize) -> [u8; N
     unsafe { *value.as_ptr().add(offset).cast() }
 }
 
+/// The byte permutation of [`AesBlock::rotate_diagonal`]
+pub(crate) const ROTATE_DIAGONAL: [u8; 16] = [0, 1, 2, 3, 7, 4, 5, 6, 10, 11, 8, 9, 13, 14, 15, 12];
+/// The byte permutation of [`AesBlock::inv_rotate_diagonal`]
+pub(crate) const INV_ROTATE_DIAGONAL: [u8; 16] =
+    [0, 1, 2, 3, 5, 6, 7, 4, 10, 11, 8, 9, 15, 12, 13, 14];
+/// The byte permutation of `ShiftRows`: byte `i` of the result is byte `SHIFT_ROWS[i]` of the state
+pub(crate) const SHIFT_ROWS: [u8; 16] = [0, 5, 10, 15, 4, 9, 14, 3, 8, 13, 2, 7, 12, 1, 6, 11];
+/// The byte permutation of `InvShiftRows`
+pub(crate) const INV_SHIFT_ROWS: [u8; 16] = [0, 13, 10, 7, 4, 1, 14, 11, 8, 5, 2, 15, 12, 9, 6, 3];
+
+const LOW_BITS: u128 = 0x0101_0101_0101_0101_0101_0101_0101_0101;
+
+/// Multiplies every byte of `a` by `2` in GF(2^8)
+#[inline(always)]
+const fn xtime_bytes(a: u128) -> u128 {
+    ((a & (0x7f * LOW_BITS)) << 1) ^ (((a >> 7) & LOW_BITS) * 0x1b)
+}
+
+/// Multiplies every byte of `a` by the corresponding byte of `b` in GF(2^8), in constant time
+#[inline(always)]
+const fn gf_mul_bytes(mut a: u128, b: u128) -> u128 {
+    let mut result = 0;
+    let mut i = 0;
+    while i < 8 {
+        result ^= a & (((b >> i) & LOW_BITS) * 0xff);
+        a = xtime_bytes(a);
+        i += 1;
+    }
+    result
+}
+
+/// Carryless multiplication of two 128-bit polynomials, returning the high and low halves of the product.
+/// This runs in constant time, for backends without a hardware carryless multiplier
+#[allow(unused)]
+#[inline(always)]
+pub(crate) const fn clmul_soft(a: u128, b: u128) -> (u128, u128) {
+    let (mut hi, mut lo) = (0, a & 0_u128.wrapping_sub(b & 1));
+    let mut i = 1;
+    while i < 128 {
+        let mask = 0_u128.wrapping_sub((b >> i) & 1);
+        lo ^= (a << i) & mask;
+        hi ^= (a >> (128 - i)) & mask;
+        i += 1;
+    }
+    (hi, lo)
+}
+
 impl From<u128> for AesBlock {
     #[inline]
     fn from(value: u128) -> Self {
@@ -153,6 +426,527 @@ impl From<AesBlock> for u128 {
     }
 }
 
+/// Creates a block from two lanes given as big-endian 128-bit integers, as with `From<u128>` for
+/// [`AesBlock`]
+///
+/// ```
+/// # use aes_crypto::{AesBlock, AesBlockX2};
+/// let block = AesBlockX2::from([1, 2]);
+/// assert_eq!(<(_, _)>::from(block), (AesBlock::from(1), AesBlock::from(2)));
+/// assert_eq!(<[u128; 2]>::from(block), [1, 2]);
+/// ```
+impl From<[u128; 2]> for AesBlockX2 {
+    #[inline]
+    fn from([a, b]: [u128; 2]) -> Self {
+        (AesBlock::from(a), AesBlock::from(b)).into()
+    }
+}
+
+impl From<AesBlockX2> for [u128; 2] {
+    #[inline]
+    fn from(value: AesBlockX2) -> Self {
+        let (a, b): (AesBlock, AesBlock) = value.into();
+        [a.into(), b.into()]
+    }
+}
+
+impl From<(u128, u128)> for AesBlockX2 {
+    #[inline]
+    fn from((a, b): (u128, u128)) -> Self {
+        [a, b].into()
+    }
+}
+
+/// Creates a block from four lanes given as big-endian 128-bit integers, as with `From<u128>` for
+/// [`AesBlock`]
+impl From<[u128; 4]> for AesBlockX4 {
+    #[inline]
+    fn from([a, b, c, d]: [u128; 4]) -> Self {
+        (
+            AesBlock::from(a),
+            AesBlock::from(b),
+            AesBlock::from(c),
+            AesBlock::from(d),
+        )
+            .into()
+    }
+}
+
+impl From<AesBlockX4> for [u128; 4] {
+    #[inline]
+    fn from(value: AesBlockX4) -> Self {
+        let [a, b, c, d] = value.to_gcm_order();
+        [a.into(), b.into(), c.into(), d.into()]
+    }
+}
+
+impl AesBlock {
+    /// Loads a block from `value`, returning `None` if it is the all-zero block. This is useful where a
+    /// zero tweak or IV is invalid
+    #[inline]
+    #[must_use]
+    pub fn new_nonzero(value: [u8; 16]) -> Option<Self> {
+        let block = Self::from(value);
+        if block.is_zero() {
+            None
+        } else {
+            Some(block)
+        }
+    }
+
+    /// Stores the block into the first 16 bytes of `dst`. Unlike [`AesBlock::store_to`], this does not panic
+    /// if `dst` is too short
+    ///
+    /// # Errors
+    ///
+    /// Returns the required length (16) if `dst` is shorter than that, leaving `dst` unchanged
+    #[inline]
+    pub fn write_to_slice(self, dst: &mut [u8]) -> Result<(), usize> {
+        if dst.len() >= 16 {
+            self.store_to(dst);
+            Ok(())
+        } else {
+            Err(16)
+        }
+    }
+
+    /// Returns the raw in-memory representation of the block as a `u128`, without any endianness
+    /// normalization. This is useful for FFI boundaries that pass the backend's native vector type (e.g.
+    /// `__m128i` or `uint8x16_t`) by value.
+    ///
+    /// The result depends on both the selected backend and the host endianness, so it is **not** portable.
+    /// Use `u128::from` for the canonical (big-endian) integer value
+    #[inline]
+    #[must_use]
+    pub const fn to_ne_u128(self) -> u128 {
+        // every backend represents a block with exactly 16 bytes of plain data
+        unsafe { core::mem::transmute(self) }
+    }
+
+    /// Inverse of [`AesBlock::to_ne_u128`]. Only values obtained from the same backend on the same host
+    /// are guaranteed to round-trip
+    #[inline]
+    pub const fn from_ne_u128(value: u128) -> Self {
+        unsafe { core::mem::transmute(value) }
+    }
+
+    /// Compares two blocks in a `const` context, e.g. to check a known-answer test at compile time.
+    /// Outside of `const`, use `==`, which is faster, and [`ConstantTimeEq`](subtle::ConstantTimeEq) (with
+    /// the `subtle` feature) for comparing secrets, as this is not constant-time
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// const KAT: AesBlock = AesBlock::new(*b"expected output!");
+    /// const { assert!(KAT.const_eq(AesBlock::new(*b"expected output!"))) };
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn const_eq(self, other: Self) -> bool {
+        u128::from_ne_bytes(self.to_bytes()) == u128::from_ne_bytes(other.to_bytes())
+    }
+
+    /// Creates a block from two 64-bit limbs in big-endian order, i.e. the big-endian 128-bit integer
+    /// `(high << 64) | low`, as in GHASH. See [`from_u64_le_pair`](Self::from_u64_le_pair) for the
+    /// little-endian limbs of POLYVAL
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from_high_low(0x0001_0203_0405_0607, 0x0809_0a0b_0c0d_0e0f);
+    /// assert_eq!(block, AesBlock::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]));
+    /// ```
+    #[inline]
+    pub const fn from_high_low(high: u64, low: u64) -> Self {
+        Self::new((((high as u128) << 64) | low as u128).to_be_bytes())
+    }
+
+    /// The inverse of [`from_high_low`](Self::from_high_low), returning `(high, low)`
+    #[inline]
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn to_high_low(self) -> (u64, u64) {
+        let value = u128::from_be_bytes(self.to_bytes());
+        ((value >> 64) as u64, value as u64)
+    }
+
+    /// Creates a block from two 64-bit limbs in little-endian order, i.e. the little-endian 128-bit
+    /// integer `(hi << 64) | lo`, as in POLYVAL (RFC 8452): the first 8 bytes are `lo` in little-endian
+    /// order, and the last 8 bytes are `hi`. This is the byte reversal of
+    /// [`from_high_low(hi, lo)`](Self::from_high_low), mixing the two up is a classic GCM-SIV bug
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from_u64_le_pair(0x0706_0504_0302_0100, 0x0f0e_0d0c_0b0a_0908);
+    /// assert_eq!(block, AesBlock::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]));
+    /// ```
+    #[inline]
+    pub const fn from_u64_le_pair(lo: u64, hi: u64) -> Self {
+        Self::new((((hi as u128) << 64) | lo as u128).to_le_bytes())
+    }
+
+    /// The inverse of [`from_u64_le_pair`](Self::from_u64_le_pair), returning `(lo, hi)`
+    #[inline]
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn to_u64_le_pair(self) -> (u64, u64) {
+        let value = u128::from_le_bytes(self.to_bytes());
+        (value as u64, (value >> 64) as u64)
+    }
+
+    /// Reverses the order of the 16 bytes of the block, which converts between the big-endian and the
+    /// little-endian 128-bit integer interpretations
+    #[inline]
+    pub const fn reverse_bytes(self) -> Self {
+        Self::new(u128::from_le_bytes(self.to_bytes()).to_be_bytes())
+    }
+
+    /// Returns the all-ones block if `cond` is true, and the all-zero block otherwise, without branching on
+    /// `cond`, e.g. to build a mask for constant-time selection with `(a & mask) | (b & !mask)`
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// assert_eq!(AesBlock::broadcast_bit(true), AesBlock::from(u128::MAX));
+    /// assert!(AesBlock::broadcast_bit(false).is_zero());
+    /// ```
+    #[inline]
+    pub const fn broadcast_bit(cond: bool) -> Self {
+        Self::new(0_u128.wrapping_sub(cond as u128).to_ne_bytes())
+    }
+
+    /// Expands the least significant bit of every byte to the whole byte: bytes with an odd value become
+    /// `0xff`, and bytes with an even value become `0x00`, so a byte-wise comparison result of `0x01` or
+    /// `0x00` becomes a byte mask. The other 7 bits of every byte are ignored. This is branch-free: the low
+    /// bits are isolated, and `(bits << 8) - bits` multiplies each of them by `0xff` without carries between
+    /// bytes
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let flags = AesBlock::new([1, 0, 3, 2, 0xff, 0xfe, 0, 1, 1, 1, 0, 0, 0x81, 0x80, 0, 1]);
+    /// assert_eq!(
+    ///     flags.expand_lsb_to_mask(),
+    ///     AesBlock::new([0xff, 0, 0xff, 0, 0xff, 0, 0, 0xff, 0xff, 0xff, 0, 0, 0xff, 0, 0, 0xff]),
+    /// );
+    /// ```
+    #[inline]
+    pub const fn expand_lsb_to_mask(self) -> Self {
+        // every byte is handled on its own, so the byte order of the integer does not matter
+        let bits = u128::from_ne_bytes(self.to_bytes()) & LOW_BITS;
+        Self::new((bits << 8).wrapping_sub(bits).to_ne_bytes())
+    }
+
+    /// Shifts the block left by `N` **bits**, treating it as a big-endian 128-bit integer. Shifting by 128 or
+    /// more bits gives the zero block. See [`AesBlock::shl_bytes`] for the (usually faster) byte shift
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from(0x8000_0000_0000_0000_0000_0000_0000_0001);
+    /// assert_eq!(block.shl_bits::<1>(), AesBlock::from(2));
+    /// ```
+    #[inline]
+    pub fn shl_bits<const N: u32>(self) -> Self {
+        u128::from(self).checked_shl(N).unwrap_or(0).into()
+    }
+
+    /// Shifts the block right by `N` **bits**, treating it as a big-endian 128-bit integer. Shifting by 128
+    /// or more bits gives the zero block. See [`AesBlock::shr_bytes`] for the (usually faster) byte shift
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from(0x8000_0000_0000_0000_0000_0000_0000_0001);
+    /// assert_eq!(block.shr_bits::<1>(), AesBlock::from(1 << 126));
+    /// ```
+    #[inline]
+    pub fn shr_bits<const N: u32>(self) -> Self {
+        u128::from(self).checked_shr(N).unwrap_or(0).into()
+    }
+
+    /// Shifts the block left by `N` bytes
+    #[deprecated(note = "renamed to `shl_bytes`, to tell it apart from `shl_bits`")]
+    #[inline]
+    pub fn shl<const N: i32>(self) -> Self {
+        self.shl_bytes::<N>()
+    }
+
+    /// Shifts the block right by `N` bytes
+    #[deprecated(note = "renamed to `shr_bytes`, to tell it apart from `shr_bits`")]
+    #[inline]
+    pub fn shr<const N: i32>(self) -> Self {
+        self.shr_bytes::<N>()
+    }
+
+    /// Loads a 128-bit key (or the first 16 bytes of a longer one) as a block. The key bytes are taken in
+    /// order, exactly as in FIPS-197, so the result is the same on every backend and host endianness, and
+    /// is the first round key of the key schedule
+    #[inline]
+    pub fn from_key_bytes(key: &[u8; 16]) -> Self {
+        Self::new(*key)
+    }
+
+    /// Increments the block as a big-endian 128-bit counter, wrapping around on overflow. On every backend
+    /// this compiles to a byte swap and a 128-bit add with carry, which is as fast as a vector add-with-carry
+    /// would be
+    #[inline]
+    pub fn incr_be(self) -> Self {
+        u128::from(self).wrapping_add(1).into()
+    }
+
+    /// Increments the block as a big-endian 128-bit counter, wrapping around on overflow. The same as
+    /// [`incr_be`](Self::incr_be), under the name that pairs with [`inc_le`](Self::inc_le)
+    #[inline]
+    pub fn inc_be(self) -> Self {
+        self.incr_be()
+    }
+
+    /// Increments the block as a little-endian 128-bit integer (so the first byte is the least significant
+    /// one), wrapping around from all-ones to zero
+    #[inline]
+    pub fn inc_le(self) -> Self {
+        u128::from_le_bytes(self.into())
+            .wrapping_add(1)
+            .to_le_bytes()
+            .into()
+    }
+
+    /// Adds `n` to the block as a big-endian 128-bit integer, wrapping around on overflow. This skips `n`
+    /// blocks ahead in counter mode
+    #[inline]
+    pub fn inc_be_by(self, n: u64) -> Self {
+        u128::from(self).wrapping_add(n.into()).into()
+    }
+
+    /// Adds two blocks as big-endian 128-bit **integers**, wrapping around on overflow, e.g. to offset a
+    /// counter block. This is integer addition with carries, not the addition of GF(2)^128 used by GHASH and
+    /// friends, which is [`BitXor`](core::ops::BitXor)
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let counter = AesBlock::from(0x00ff_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+    /// assert_eq!(counter.wrapping_add_be(AesBlock::from(1)), AesBlock::from(1 << 120));
+    /// assert_ne!(counter.wrapping_add_be(AesBlock::from(1)), counter ^ AesBlock::from(1));
+    /// ```
+    #[inline]
+    pub fn wrapping_add_be(self, rhs: Self) -> Self {
+        u128::from(self).wrapping_add(rhs.into()).into()
+    }
+
+    /// Subtracts two blocks as big-endian 128-bit **integers**, wrapping around on underflow, e.g. to find
+    /// how many blocks apart two counters are. See [`wrapping_add_be`](Self::wrapping_add_be) for the
+    /// difference from XOR
+    #[inline]
+    pub fn wrapping_sub_be(self, rhs: Self) -> Self {
+        u128::from(self).wrapping_sub(rhs.into()).into()
+    }
+
+    /// Increments the block as a big-endian 128-bit counter, returning the result and whether the counter
+    /// wrapped around from all-ones to zero. Counter modes must never reuse a counter, so a wrap means the
+    /// counter space is exhausted
+    #[inline]
+    pub fn incr_be_checked(self) -> (Self, bool) {
+        let (value, wrapped) = u128::from(self).overflowing_add(1);
+        (value.into(), wrapped)
+    }
+
+    /// Increments the last 64 bits of the block as a big-endian counter, wrapping around on overflow. The
+    /// first 64 bits are left unchanged
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn incr_be_64(self) -> Self {
+        let value = u128::from(self);
+        let ctr = (value as u64).wrapping_add(1);
+        ((value & !u128::from(u64::MAX)) | u128::from(ctr)).into()
+    }
+
+    /// Increments the last 32 bits of the block as a big-endian counter, wrapping around on overflow (as in
+    /// GCM). The first 96 bits are left unchanged
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn incr_be_32(self) -> Self {
+        let value = u128::from(self);
+        let ctr = (value as u32).wrapping_add(1);
+        ((value & !u128::from(u32::MAX)) | u128::from(ctr)).into()
+    }
+
+    /// Multiplies two elements of GF(2^128), with the GCM polynomial `x^128 + x^7 + x^2 + x + 1` and the
+    /// bit-reflected convention of the GCM specification (the most significant bit of the first byte is
+    /// the coefficient of `x^0`), so this is exactly the multiplication used in GHASH
+    #[inline]
+    pub fn gf128_mul(self, other: Self) -> Self {
+        let (hi, lo) = self.clmul(other);
+        let (hi, lo) = (u128::from(hi), u128::from(lo));
+
+        // in the reflected convention the product is shifted right by one bit
+        let (hi, lo) = ((hi << 1) | (lo >> 127), lo << 1);
+
+        // `lo` holds the coefficients of x^128 and above, which are reduced by x^128 = x^7 + x^2 + x + 1
+        // (multiplying by x^k is a right shift by k here). The bits shifted out are reduced once more
+        let spill = (lo << 127) ^ (lo << 126) ^ (lo << 121);
+        let reduced = lo ^ (lo >> 1) ^ (lo >> 2) ^ (lo >> 7);
+        (hi ^ reduced ^ spill ^ (spill >> 1) ^ (spill >> 2) ^ (spill >> 7)).into()
+    }
+
+    /// Multiplies an XTS tweak by the primitive element `α` (i.e. `x`) of GF(2^128), with the modulus
+    /// `x^128 + x^7 + x^2 + x + 1`, as in IEEE 1619.
+    ///
+    /// XTS uses the **little-endian** convention: the first byte of the block holds the coefficients of
+    /// `x^0..x^7`, and bit `i` of a byte (with bit 0 the least significant) is the coefficient of `x^(8k + i)`
+    /// for byte `k`. So this is a left shift of the block as a little-endian 128-bit integer, followed by
+    /// a XOR of `0x87` into the first byte if the top bit was set. The reduction uses a mask, without
+    /// branching on the tweak
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let mut tweak = [0; 16];
+    /// tweak[15] = 0x80;
+    /// let mut expected = [0; 16];
+    /// expected[0] = 0x87;
+    /// assert_eq!(AesBlock::from(tweak).xts_mul_x(), AesBlock::from(expected));
+    /// ```
+    #[inline]
+    pub fn xts_mul_x(self) -> Self {
+        let value = u128::from_le_bytes(self.into());
+        let reduced = (value << 1) ^ (0x87 & 0_u128.wrapping_sub(value >> 127));
+        reduced.to_le_bytes().into()
+    }
+
+    /// Computes the multiplicative inverse in GF(2^8) (with the AES polynomial `x^8 + x^4 + x^3 + x + 1`)
+    /// of every byte of the block, mapping 0 to 0. This is the nonlinear core of `SubBytes`, and is **not**
+    /// the full S-box, as the affine transform is not applied.
+    ///
+    /// The inverse is computed in constant time as `x^254`
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from([0x53; 16]);
+    /// assert_eq!(block.gf_inv_bytes(), AesBlock::from([0xca; 16]));
+    /// ```
+    #[inline]
+    pub fn gf_inv_bytes(self) -> Self {
+        let x = u128::from(self);
+
+        // x^254 = x^2 * x^4 * x^8 * x^16 * x^32 * x^64 * x^128
+        let mut square = gf_mul_bytes(x, x);
+        let mut result = square;
+        for _ in 0..6 {
+            square = gf_mul_bytes(square, square);
+            result = gf_mul_bytes(result, square);
+        }
+        result.into()
+    }
+
+    /// Multiplies every byte of the block by `2` (i.e. `x`) in GF(2^8), with the AES polynomial
+    /// `x^8 + x^4 + x^3 + x + 1`: each byte is shifted left by one bit, and `0x1b` is XORed in if its top
+    /// bit was set. This is the building block of `MixColumns`, and is branch-free
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from([0x57; 16]);
+    /// assert_eq!(block.xtime(), AesBlock::from([0xae; 16]));
+    /// assert_eq!(block.xtime().xtime(), AesBlock::from([0x47; 16]));
+    /// ```
+    #[inline]
+    pub fn xtime(self) -> Self {
+        xtime_bytes(self.into()).into()
+    }
+
+    /// Multiplies every byte of the block by the constant `C` in GF(2^8), with the AES polynomial. This
+    /// runs in constant time with respect to the block, e.g. `MixColumns` multiplies by 2 and 3, and
+    /// `InvMixColumns` by 9, 11, 13 and 14
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from([0x57; 16]);
+    /// assert_eq!(block.gf_mul_const::<0x13>(), AesBlock::from([0xfe; 16]));
+    /// assert_eq!(block.gf_mul_const::<2>(), block.xtime());
+    /// ```
+    #[inline]
+    pub fn gf_mul_const<const C: u8>(self) -> Self {
+        gf_mul_bytes(self.into(), u128::from(C) * LOW_BITS).into()
+    }
+
+    /// Extracts the low nibble of every byte, so every byte `b` of the result is `b & 0x0f` (and the high
+    /// nibbles are zero). Bytes stay in place, so every byte of the result can index a 16-entry table
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// assert_eq!(AesBlock::from([0xa7; 16]).nibbles_lo(), AesBlock::from([0x07; 16]));
+    /// ```
+    #[inline]
+    pub fn nibbles_lo(self) -> Self {
+        (u128::from(self) & (0x0f * LOW_BITS)).into()
+    }
+
+    /// Extracts the high nibble of every byte, shifted down to the low 4 bits, so every byte `b` of the
+    /// result is `b >> 4` (and the high nibbles are zero)
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// assert_eq!(AesBlock::from([0xa7; 16]).nibbles_hi(), AesBlock::from([0x0a; 16]));
+    /// ```
+    #[inline]
+    pub fn nibbles_hi(self) -> Self {
+        ((u128::from(self) >> 4) & (0x0f * LOW_BITS)).into()
+    }
+
+    /// Combines the low 4 bits of the bytes of `lo` and `hi` into the low and high nibbles of the bytes of
+    /// the result, the inverse of [`nibbles_lo`](Self::nibbles_lo) and [`nibbles_hi`](Self::nibbles_hi).
+    /// The high nibbles of the bytes of `lo` and `hi` are ignored
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from(0x0123456789abcdeffedcba9876543210);
+    /// assert_eq!(AesBlock::combine_nibbles(block.nibbles_lo(), block.nibbles_hi()), block);
+    /// ```
+    #[inline]
+    pub fn combine_nibbles(lo: Self, hi: Self) -> Self {
+        let mask = 0x0f * LOW_BITS;
+        ((u128::from(lo) & mask) | ((u128::from(hi) & mask) << 4)).into()
+    }
+}
+
+// The GFNI implementation has its own `sub_bytes` and `inv_sub_bytes`, with the S-box instructions
+#[cfg(not(all(
+    feature = "gfni",
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse4.1",
+    target_feature = "gfni",
+    not(target_feature = "aes"),
+)))]
+impl AesBlock {
+    /// Applies the AES S-box to every byte of the block (the `SubBytes` step on its own). This is computed
+    /// with the round function of the selected implementation, so it is constant-time if that is
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from([0x53; 16]);
+    /// assert_eq!(block.sub_bytes(), AesBlock::from([0xed; 16]));
+    /// ```
+    #[inline]
+    pub fn sub_bytes(self) -> Self {
+        // `enc_last` with a zero key is `SubBytes` after `ShiftRows`, and the two commute, so undoing the
+        // `ShiftRows` up front leaves only `SubBytes`
+        self.permute_bytes(INV_SHIFT_ROWS).enc_last(Self::zero())
+    }
+
+    /// Applies the inverse AES S-box to every byte of the block (the `InvSubBytes` step on its own), the
+    /// inverse of [`sub_bytes`](Self::sub_bytes)
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let block = AesBlock::from([0xed; 16]);
+    /// assert_eq!(block.inv_sub_bytes(), AesBlock::from([0x53; 16]));
+    /// ```
+    #[inline]
+    pub fn inv_sub_bytes(self) -> Self {
+        self.permute_bytes(SHIFT_ROWS).dec_last(Self::zero())
+    }
+
+    /// Byte `i` of the result is byte `permutation[i]` of `self`
+    #[inline(always)]
+    fn permute_bytes(self, permutation: [u8; 16]) -> Self {
+        let bytes = self.to_bytes();
+        Self::new(permutation.map(|i| bytes[i as usize]))
+    }
+}
+
 macro_rules! impl_common_ops {
     ($($name:ty, $key_len:literal),*) => {$(
     impl Default for $name {
@@ -170,7 +964,7 @@ macro_rules! impl_common_ops {
     }
 
     impl TryFrom<&[u8]> for $name {
-        type Error = usize;
+        type Error = InvalidLength;
 
         #[inline]
         fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
@@ -178,6 +972,18 @@ macro_rules! impl_common_ops {
         }
     }
 
+    impl $name {
+        #[doc = concat!("Loads the first ", $key_len, " bytes of `value`. This is the same as the `TryFrom<&[u8]>` impl")]
+        ///
+        /// # Errors
+        ///
+        #[doc = concat!("Returns [`InvalidLength`] if `value` is shorter than ", $key_len, " bytes")]
+        #[inline]
+        pub fn from_slice(value: &[u8]) -> Result<Self, InvalidLength> {
+            try_from_slice(value)
+        }
+    }
+
     impl From<$name> for [u8; $key_len] {
         #[inline]
         fn from(value: $name) -> Self {
@@ -187,6 +993,57 @@ macro_rules! impl_common_ops {
         }
     }
 
+    impl $name {
+        /// Computes `self ^ !other`, which is the same as `!(self ^ other)` (the bitwise XNOR)
+        #[inline]
+        pub fn xor_not(self, other: Self) -> Self {
+            self ^ !other
+        }
+
+        /// Performs one round of encryption in place. Exactly equivalent to
+        /// `*self = self.enc(round_key)`, see [`enc`](Self::enc)
+        #[inline]
+        pub fn enc_assign(&mut self, round_key: Self) {
+            *self = self.enc(round_key);
+        }
+
+        /// Performs one round of decryption in place. Exactly equivalent to
+        /// `*self = self.dec(round_key)`, see [`dec`](Self::dec)
+        #[inline]
+        pub fn dec_assign(&mut self, round_key: Self) {
+            *self = self.dec(round_key);
+        }
+
+        /// Performs two rounds of encryption, a convenience wrapper exactly equivalent to
+        /// `self.enc(rk0).enc(rk1)`. It is always inlined, so both rounds stay in registers (with AES-NI this
+        /// is two dependent `aesenc`s)
+        #[inline(always)]
+        pub fn enc2(self, rk0: Self, rk1: Self) -> Self {
+            self.enc(rk0).enc(rk1)
+        }
+
+        /// Performs two rounds of decryption, a convenience wrapper exactly equivalent to
+        /// `self.dec(rk0).dec(rk1)`, see [`enc2`](Self::enc2)
+        #[inline(always)]
+        pub fn dec2(self, rk0: Self, rk1: Self) -> Self {
+            self.dec(rk0).dec(rk1)
+        }
+
+        /// Performs the `MixColumns` operation in place. Exactly equivalent to `*self = self.mc()`, see
+        /// [`mc`](Self::mc)
+        #[inline]
+        pub fn mc_assign(&mut self) {
+            *self = self.mc();
+        }
+
+        /// Performs the `InvMixColumns` operation in place. Exactly equivalent to `*self = self.imc()`, see
+        /// [`imc`](Self::imc)
+        #[inline]
+        pub fn imc_assign(&mut self) {
+            *self = self.imc();
+        }
+    }
+
     impl BitAndAssign for $name {
         #[inline]
         fn bitand_assign(&mut self, rhs: Self) {
@@ -210,7 +1067,112 @@ macro_rules! impl_common_ops {
     )*};
 }
 
-impl_common_ops!(AesBlock, 16, AesBlockX2, 32, AesBlockX4, 64);
+impl_common_ops!(AesBlock, 16, AesBlockX2, 32, AesBlockX4, 64, AesBlockX8, 128);
+
+/// The additive inverse in GF(2)^128, where addition is XOR. Every element is its own inverse, so this is
+/// the **identity**, and not the bitwise not. It is provided for code that is generic over numeric types.
+/// There is no `Add` or `Sub`, so that no operator disagrees with it; integer arithmetic on blocks is
+/// [`wrapping_add_be`](AesBlock::wrapping_add_be) and [`wrapping_sub_be`](AesBlock::wrapping_sub_be)
+impl Neg for AesBlock {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        self
+    }
+}
+
+// the blocks are plain data with a zero `Default`, so zeroize overwrites them with a volatile write. They are
+// `Copy` though, so they are never zeroized automatically
+#[cfg(feature = "zeroize")]
+impl zeroize::DefaultIsZeroes for AesBlock {}
+#[cfg(feature = "zeroize")]
+impl zeroize::DefaultIsZeroes for AesBlockX2 {}
+#[cfg(feature = "zeroize")]
+impl zeroize::DefaultIsZeroes for AesBlockX4 {}
+#[cfg(feature = "zeroize")]
+impl zeroize::DefaultIsZeroes for AesBlockX8 {}
+
+impl AesBlockX2 {
+    /// Creates a block with the two lanes `a` and `b` (in that order). Same as `From<(AesBlock, AesBlock)>`
+    #[inline]
+    pub fn from_distinct(a: AesBlock, b: AesBlock) -> Self {
+        (a, b).into()
+    }
+
+    /// Creates a block with `value` copied into both lanes. Same as `From<AesBlock>`
+    #[inline]
+    pub fn from_broadcast(value: AesBlock) -> Self {
+        value.into()
+    }
+}
+
+impl AesBlockX4 {
+    /// Creates a block with the four lanes `a`, `b`, `c` and `d` (in that order). Same as
+    /// `From<(AesBlock, AesBlock, AesBlock, AesBlock)>`
+    ///
+    /// ```
+    /// # use aes_crypto::{AesBlock, AesBlockX4};
+    /// let (a, b) = (AesBlock::from(1), AesBlock::from(2));
+    ///
+    /// let distinct = AesBlockX4::from_distinct(a, b, a, b);
+    /// let broadcast = AesBlockX4::from_broadcast(a);
+    ///
+    /// assert_eq!(<(_, _, _, _)>::from(distinct), (a, b, a, b));
+    /// assert_eq!(<(_, _, _, _)>::from(broadcast), (a, a, a, a));
+    /// ```
+    #[inline]
+    pub fn from_distinct(a: AesBlock, b: AesBlock, c: AesBlock, d: AesBlock) -> Self {
+        (a, b, c, d).into()
+    }
+
+    /// Creates a block with `value` copied into all four lanes. Same as `From<AesBlock>`
+    #[inline]
+    pub fn from_broadcast(value: AesBlock) -> Self {
+        value.into()
+    }
+
+    /// Returns the lanes in the order GHASH has to consume them. Lane `i` is the `i`-th block in memory
+    /// (the one stored at `16 * i` by [`store_to`](Self::store_to)), so when four counter blocks are
+    /// encrypted together as `(ctr, ctr + 1, ctr + 2, ctr + 3)`, the resulting ciphertext blocks have to be
+    /// absorbed in the order returned here, whatever the backend's register layout is
+    ///
+    /// ```
+    /// # use aes_crypto::{AesBlock, AesBlockX4};
+    /// let blocks = [1, 2, 3, 4].map(AesBlock::from);
+    /// assert_eq!(AesBlockX4::from(blocks).to_gcm_order(), blocks);
+    /// ```
+    #[inline]
+    pub fn to_gcm_order(self) -> [AesBlock; 4] {
+        let (a, b, c, d) = self.into();
+        [a, b, c, d]
+    }
+
+    /// Returns a mask with bit `i` set if lane `i` is equal to `target`, in the lane order of
+    /// [`select_lanes`](Self::select_lanes). This is not constant-time
+    #[inline]
+    #[must_use]
+    pub fn lane_eq_mask(self, target: AesBlock) -> u8 {
+        self.to_gcm_order()
+            .iter()
+            .enumerate()
+            .fold(0, |mask, (i, &lane)| mask | (u8::from(lane == target) << i))
+    }
+
+    /// Returns how many of the four lanes are equal to `target`, e.g. to check which of four candidate
+    /// ciphertexts matches. This is not constant-time, as a search has to branch on the result anyway
+    ///
+    /// ```
+    /// # use aes_crypto::{AesBlock, AesBlockX4};
+    /// let (a, b) = (AesBlock::from(1), AesBlock::from(2));
+    /// assert_eq!(AesBlockX4::from((a, b, a, a)).count_eq(a), 3);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn count_eq(self, target: AesBlock) -> u32 {
+        self.lane_eq_mask(target).count_ones()
+    }
+}
 
 impl Debug for AesBlock {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -264,6 +1226,53 @@ impl UpperHex for AesBlock {
     }
 }
 
+macro_rules! impl_wide_fmt {
+    ($($name:ty, $len:literal),*) => {$(
+    impl $name {
+        /// The lanes of the block, in order
+        fn fmt_lanes(self) -> impl Iterator<Item = AesBlock> {
+            let bytes = <[u8; $len]>::from(self);
+            (0..$len / 16).map(move |i| {
+                let mut lane = [0; 16];
+                lane.copy_from_slice(&bytes[16 * i..][..16]);
+                AesBlock::from(lane)
+            })
+        }
+    }
+
+    impl Display for $name {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            if f.alternate() {
+                write!(f, "{self:X}")
+            } else {
+                write!(f, "{self:x}")
+            }
+        }
+    }
+
+    impl LowerHex for $name {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            if f.alternate() {
+                f.write_str("0x")?;
+            }
+            self.fmt_lanes().try_for_each(|lane| write!(f, "{lane:x}"))
+        }
+    }
+
+    impl UpperHex for $name {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            if f.alternate() {
+                f.write_str("0X")?;
+            }
+            self.fmt_lanes().try_for_each(|lane| write!(f, "{lane:X}"))
+        }
+    }
+    )*};
+}
+
+// the lanes are joined without a separator, so this is a contiguous dump of the bytes
+impl_wide_fmt!(AesBlockX2, 32, AesBlockX4, 64, AesBlockX8, 128);
+
 impl Debug for AesBlockX2 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         <(AesBlock, AesBlock)>::from(*self).fmt(f)
@@ -276,12 +1285,232 @@ impl Debug for AesBlockX4 {
     }
 }
 
-mod private {
-    pub trait Sealed {}
+impl Debug for AesBlockX8 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <(
+            AesBlock,
+            AesBlock,
+            AesBlock,
+            AesBlock,
+            AesBlock,
+            AesBlock,
+            AesBlock,
+            AesBlock,
+        )>::from(*self)
+        .fmt(f)
+    }
 }
 
-pub trait AesEncrypt<const KEY_LEN: usize>:
-    From<[u8; KEY_LEN]> + private::Sealed + Debug + Clone
+macro_rules! impl_hash {
+    ($($name:ty, $len:literal),*) => {$(
+    impl Hash for $name {
+        #[inline]
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            // hash the canonical byte representation so that all backends agree
+            <[u8; $len]>::from(*self).hash(state);
+        }
+    }
+    )*};
+}
+
+impl_hash!(AesBlock, 16, AesBlockX2, 32, AesBlockX4, 64, AesBlockX8, 128);
+
+macro_rules! impl_ord {
+    ($($name:ty, $len:literal),*) => {$(
+    /// Compares the canonical bytes (as given by `From<Self> for [u8; N]`) lexicographically, whatever the
+    /// in-register layout of the backend. For a single block this is the same as comparing the blocks as
+    /// big-endian `u128`s (i.e. `u128::from`), and for the wide types it compares the blocks lane by lane,
+    /// starting from the first
+    impl PartialOrd for $name {
+        #[inline]
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for $name {
+        #[inline]
+        fn cmp(&self, other: &Self) -> Ordering {
+            <[u8; $len]>::from(*self).cmp(&<[u8; $len]>::from(*other))
+        }
+    }
+    )*};
+}
+
+impl_ord!(AesBlock, 16, AesBlockX2, 32, AesBlockX4, 64, AesBlockX8, 128);
+
+macro_rules! impl_from_blocks {
+    ($($name:ty, $n:literal, ($($i:literal),*)),*) => {$(
+    impl From<[AesBlock; $n]> for $name {
+        #[inline]
+        fn from(value: [AesBlock; $n]) -> Self {
+            ($(value[$i]),*).into()
+        }
+    }
+
+    impl $name {
+        #[doc = concat!("Packs a slice of exactly ", $n, " blocks")]
+        ///
+        /// # Panics
+        ///
+        #[doc = concat!("Panics if `blocks` does not have exactly ", $n, " elements")]
+        #[inline]
+        pub fn from_slice_blocks(blocks: &[AesBlock]) -> Self {
+            assert_eq!(blocks.len(), $n, "expected exactly {} blocks", $n);
+            ($(blocks[$i]),*).into()
+        }
+    }
+    )*};
+}
+
+impl_from_blocks!(
+    AesBlockX2,
+    2,
+    (0, 1),
+    AesBlockX4,
+    4,
+    (0, 1, 2, 3),
+    AesBlockX8,
+    8,
+    (0, 1, 2, 3, 4, 5, 6, 7)
+);
+
+#[cfg(feature = "subtle")]
+macro_rules! impl_subtle {
+    ($($name:ty, $len:literal),*) => {$(
+    impl subtle::ConstantTimeEq for $name {
+        #[inline]
+        fn ct_eq(&self, other: &Self) -> subtle::Choice {
+            // `PartialEq` may exit early (e.g. `ptest` on x86), so compare the stored bytes instead, which
+            // `subtle` folds into a single `Choice`
+            <[u8; $len]>::from(*self)[..].ct_eq(&<[u8; $len]>::from(*other)[..])
+        }
+    }
+
+    impl $name {
+        /// Compares two values in constant time. Shorthand for [`subtle::ConstantTimeEq::ct_eq`]
+        #[inline]
+        #[must_use]
+        pub fn ct_eq(&self, other: &Self) -> subtle::Choice {
+            subtle::ConstantTimeEq::ct_eq(self, other)
+        }
+
+        /// Swaps `a` and `b` if `choice` is set, without branching on it. This is the building block of
+        /// constant-time sorting networks and shuffles
+        #[inline]
+        pub fn conditional_swap(a: &mut Self, b: &mut Self, choice: subtle::Choice) {
+            subtle::ConditionallySelectable::conditional_swap(a, b, choice);
+        }
+    }
+
+    impl subtle::ConditionallySelectable for $name {
+        #[inline]
+        fn conditional_select(a: &Self, b: &Self, choice: subtle::Choice) -> Self {
+            let mask = 0_u128.wrapping_sub(u128::from(choice.unwrap_u8()));
+            let mask = Self::from(AesBlock::from(mask));
+            *a ^ ((*a ^ *b) & mask)
+        }
+    }
+    )*};
+}
+
+#[cfg(feature = "subtle")]
+impl_subtle!(AesBlock, 16, AesBlockX2, 32, AesBlockX4, 64, AesBlockX8, 128);
+
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeGreater for AesBlock {
+    /// Compares the blocks as big-endian 128-bit integers (as in [`u128::from`]), in constant time
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    fn ct_gt(&self, other: &Self) -> subtle::Choice {
+        use subtle::ConstantTimeEq;
+
+        // `subtle` only implements this for up to 64-bit integers, so compare the high halves first, and
+        // the low halves if the high halves are equal
+        let (a, b) = (u128::from(*self), u128::from(*other));
+        let (a_hi, a_lo, b_hi, b_lo) = ((a >> 64) as u64, a as u64, (b >> 64) as u64, b as u64);
+        a_hi.ct_gt(&b_hi) | (a_hi.ct_eq(&b_hi) & a_lo.ct_gt(&b_lo))
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeLess for AesBlock {}
+
+#[cfg(feature = "subtle")]
+impl AesBlock {
+    /// Whether `self > other` as big-endian 128-bit integers, computed in constant time. Shorthand for
+    /// [`subtle::ConstantTimeGreater::ct_gt`], useful for checking counters without leaking their position
+    #[inline]
+    #[must_use]
+    pub fn ct_gt(&self, other: &Self) -> subtle::Choice {
+        subtle::ConstantTimeGreater::ct_gt(self, other)
+    }
+
+    /// Whether `self < other` as big-endian 128-bit integers, computed in constant time. Shorthand for
+    /// [`subtle::ConstantTimeLess::ct_lt`]
+    #[inline]
+    #[must_use]
+    pub fn ct_lt(&self, other: &Self) -> subtle::Choice {
+        subtle::ConstantTimeLess::ct_lt(self, other)
+    }
+}
+
+/// Whether a block is stored in memory as its 16 bytes in order, so that byte buffers can be viewed as
+/// blocks. This holds for every backend except the table-based one on little-endian hosts, which stores
+/// big-endian words
+const BLOCKS_ARE_BYTES: bool = {
+    let bytes = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+    AesBlock::new(bytes).to_ne_u128() == u128::from_ne_bytes(bytes)
+};
+
+/// Splits `data` into a prefix of whole blocks, viewed in place as `&mut [AesBlock]`, and the trailing
+/// bytes, so that mode code can work on typed blocks (e.g. with `encrypt_blocks`) without copying.
+///
+/// Blocks are 16-byte aligned on most backends, and the view is only possible if `data` starts at a
+/// multiple of the alignment of [`AesBlock`] and the backend stores blocks as their bytes. Otherwise the
+/// prefix is empty and all of `data` is returned as the trailing bytes, so callers must be prepared to
+/// process whole blocks from the trailing bytes as well. The only way to guarantee the view is to keep the
+/// data in a type with `#[repr(align(16))]`, as in the example
+///
+/// ```
+/// # use aes_crypto::{as_blocks_mut, Aes128Enc, AesBlock, AesEncrypt};
+/// #[repr(align(16))]
+/// struct Buffer([u8; 40]);
+///
+/// let cipher = Aes128Enc::from([0; 16]);
+/// let mut buffer = Buffer([0; 40]);
+/// let (blocks, rest) = as_blocks_mut(&mut buffer.0);
+/// cipher.encrypt_blocks(blocks);
+/// for chunk in rest.chunks_exact_mut(16) {
+///     cipher.encrypt_block(AesBlock::try_from(&*chunk).unwrap()).store_to(chunk);
+/// }
+///
+/// let expected = cipher.encrypt_block(AesBlock::zero());
+/// assert_eq!(AesBlock::try_from(&buffer.0[16..32]).unwrap(), expected);
+/// ```
+#[inline]
+pub fn as_blocks_mut(data: &mut [u8]) -> (&mut [AesBlock], &mut [u8]) {
+    if !BLOCKS_ARE_BYTES
+        || data
+            .as_ptr()
+            .align_offset(core::mem::align_of::<AesBlock>())
+            != 0
+    {
+        return (&mut [], data);
+    }
+    let (blocks, rest) = data.split_at_mut(data.len() & !15);
+    // the pointer is aligned, and a block is 16 bytes of plain data laid out as its bytes in order
+    let blocks =
+        unsafe { core::slice::from_raw_parts_mut(blocks.as_mut_ptr().cast(), blocks.len() / 16) };
+    (blocks, rest)
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+pub trait AesEncrypt<const KEY_LEN: usize>:
+    From<[u8; KEY_LEN]> + private::Sealed + Debug + Clone
 {
     type Decrypter: AesDecrypt<KEY_LEN, Encrypter = Self>;
 
@@ -292,6 +1521,18 @@ pub trait AesEncrypt<const KEY_LEN: usize>:
     fn encrypt_2_blocks(&self, plaintext: AesBlockX2) -> AesBlockX2;
 
     fn encrypt_4_blocks(&self, plaintext: AesBlockX4) -> AesBlockX4;
+
+    /// Encrypts eight blocks, as two sequential calls to `encrypt_4_blocks` on the halves. It is no faster than
+    /// making the two calls directly, and is there for code that works with [`AesBlockX8`]
+    #[inline]
+    fn encrypt_8_blocks(&self, plaintext: AesBlockX8) -> AesBlockX8 {
+        let (a, b) = plaintext.into();
+        (self.encrypt_4_blocks(a), self.encrypt_4_blocks(b)).into()
+    }
+
+    /// Broadcasts the round keys to [`AesBlockX4`]s once, for modes that encrypt many four-block chunks
+    /// with the same key. See [`WideSchedule`]
+    fn wide_schedule(&self) -> WideSchedule;
 }
 
 pub trait AesDecrypt<const KEY_LEN: usize>:
@@ -306,6 +1547,14 @@ pub trait AesDecrypt<const KEY_LEN: usize>:
     fn decrypt_2_blocks(&self, ciphertext: AesBlockX2) -> AesBlockX2;
 
     fn decrypt_4_blocks(&self, ciphertext: AesBlockX4) -> AesBlockX4;
+
+    /// Decrypts eight blocks, as two sequential calls to `decrypt_4_blocks` on the halves. It is no faster than
+    /// making the two calls directly, and is there for code that works with [`AesBlockX8`]
+    #[inline]
+    fn decrypt_8_blocks(&self, ciphertext: AesBlockX8) -> AesBlockX8 {
+        let (a, b) = ciphertext.into();
+        (self.decrypt_4_blocks(a), self.decrypt_4_blocks(b)).into()
+    }
 }
 
 #[inline(always)]
@@ -319,6 +1568,19 @@ fn dec_round_keys<const N: usize>(enc_round_keys: &[AesBlock; N]) -> [AesBlock;
     drk
 }
 
+/// Converts a key schedule in the layout of OpenSSL's `AES_KEY` to round keys
+#[inline(always)]
+fn round_keys_from_words<const N: usize>(words: &[u32]) -> [AesBlock; N] {
+    assert_eq!(words.len(), 4 * N, "expected a schedule of {} words", 4 * N);
+    core::array::from_fn(|i| {
+        let mut bytes = [0; 16];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(&words[4 * i..]) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        bytes.into()
+    })
+}
+
 #[inline(always)]
 fn enc_round_keys<const N: usize>(dec_round_keys: &[AesBlock; N]) -> [AesBlock; N] {
     let mut rk = [AesBlock::zero(); N];
@@ -348,13 +1610,19 @@ cfg_if! {
         macro_rules! impl_pre_encdec {
             ($($name:ident),*) => {$(
                 impl $name {
-                    fn pre_enc(self, round_key: Self) -> Self {
+                    /// Performs one round of AES encryption function with the round key added first, and not at
+                    /// the end (`AddRoundKey`->`ShiftRows`->`SubBytes`->`MixColumns`)
+                    #[inline]
+                    pub fn pre_enc(self, round_key: Self) -> Self {
                         let (a, b) = self.into();
                         let (rk_a, rk_b) = round_key.into();
                         (a.pre_enc(rk_a), b.pre_enc(rk_b)).into()
                     }
 
-                    fn pre_dec(self, round_key: Self) -> Self {
+                    /// Performs one round of AES decryption function with the round key added first, and not at
+                    /// the end (`AddRoundKey`->`InvShiftRows`->`InvSubBytes`->`InvMixColumn`s)
+                    #[inline]
+                    pub fn pre_dec(self, round_key: Self) -> Self {
                         let (a, b) = self.into();
                         let (rk_a, rk_b) = round_key.into();
                         (a.pre_dec(rk_a), b.pre_dec(rk_b)).into()
@@ -363,8 +1631,43 @@ cfg_if! {
             )*};
         }
 
-        impl_pre_encdec!(AesBlockX2, AesBlockX4);
+        impl_pre_encdec!(AesBlockX2, AesBlockX4, AesBlockX8);
+    } else {
+        macro_rules! impl_pre_encdec {
+            ($($name:ident),*) => {$(
+                impl $name {
+                    /// Performs one round of AES encryption function with the round key added first, and not at
+                    /// the end (`AddRoundKey`->`ShiftRows`->`SubBytes`->`MixColumns`)
+                    #[inline]
+                    pub fn pre_enc(self, round_key: Self) -> Self {
+                        (self ^ round_key).enc(Self::zero())
+                    }
 
+                    /// Performs one round of AES decryption function with the round key added first, and not at
+                    /// the end (`AddRoundKey`->`InvShiftRows`->`InvSubBytes`->`InvMixColumn`s)
+                    #[inline]
+                    pub fn pre_dec(self, round_key: Self) -> Self {
+                        (self ^ round_key).dec(Self::zero())
+                    }
+                }
+            )*};
+        }
+
+        impl_pre_encdec!(AesBlock, AesBlockX2, AesBlockX4, AesBlockX8);
+    }
+}
+
+cfg_if! {
+    // `aese` adds the round key before the round, so chaining `pre_enc` saves an XOR per round. On
+    // everything else, the round key is added at the end (or it makes no difference), so `enc` is chained
+    if #[cfg(all(
+        any(
+            target_arch = "aarch64",
+            target_arch = "arm64ec",
+            all(feature = "nightly", target_arch = "arm", target_feature = "v8")
+        ),
+        target_feature = "aes",
+    ))] {
         macro_rules! declare_chain {
             ($($name:ty),*) => {$(
                 impl $name {
@@ -423,17 +1726,158 @@ cfg_if! {
     }
 }
 
-declare_chain!(AesBlock, AesBlockX2, AesBlockX4);
+declare_chain!(AesBlock, AesBlockX2, AesBlockX4, AesBlockX8);
+
+// With runtime detection, `aes_x86_detect` implements these itself, checking for AES-NI once per call
+// rather than once per round
+cfg_if! {
+    if #[cfg(not(all(
+        feature = "runtime-detect",
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2",
+        not(all(target_feature = "sse4.1", target_feature = "aes")),
+        not(all(feature = "gfni", target_feature = "sse4.1", target_feature = "gfni")),
+    )))] {
+        macro_rules! declare_cipher {
+            ($($name:ty),*) => {$(
+                impl $name {
+                    /// Computes `self.chain_enc(&keys[..keys.len() - 1]).enc_last(keys[keys.len() - 1])`
+                    #[inline(always)]
+                    pub(crate) fn cipher_enc(self, keys: &[Self]) -> Self {
+                        let (&last, keys) = keys.split_last().unwrap();
+                        self.chain_enc(keys).enc_last(last)
+                    }
+
+                    /// Computes `self.chain_dec(&keys[..keys.len() - 1]).dec_last(keys[keys.len() - 1])`
+                    #[inline(always)]
+                    pub(crate) fn cipher_dec(self, keys: &[Self]) -> Self {
+                        let (&last, keys) = keys.split_last().unwrap();
+                        self.chain_dec(keys).dec_last(last)
+                    }
+                }
+            )*};
+        }
+
+        declare_cipher!(AesBlock, AesBlockX2, AesBlockX4);
+    }
+}
+
+impl AesBlock {
+    /// Computes `transform(...transform((self ^ keys[0]).enc(keys[1]))...).enc(keys[key.len() - 1])`, i.e.
+    /// like [`AesBlock::chain_enc`] but applying `transform` between every two AES rounds. This can be
+    /// used to insert a custom linear layer (rotation, permutation, etc.) into an AES-round-based network.
+    ///
+    /// `transform` is taken generically so that it can be inlined, prefer passing a closure or a function
+    /// item over a function pointer in hot loops
+    ///
+    /// ```
+    /// # use aes_crypto::AesBlock;
+    /// let keys = [AesBlock::from(1), AesBlock::from(2), AesBlock::from(3)];
+    /// let block = AesBlock::zero();
+    /// assert_eq!(block.chain_enc_with_transform(&keys, |x| x), block.chain_enc(&keys));
+    /// ```
+    pub fn chain_enc_with_transform(self, keys: &[Self], transform: impl Fn(Self) -> Self) -> Self {
+        assert_ne!(keys.len(), 0);
+
+        let mut acc = self ^ keys[0];
+        if let Some((&first, rest)) = keys[1..].split_first() {
+            acc = acc.enc(first);
+            for &key in rest {
+                acc = transform(acc).enc(key);
+            }
+        }
+        acc
+    }
+}
+
+/// The round keys of an encrypter broadcast to every lane of an [`AesBlockX4`], created by
+/// [`AesEncrypt::wide_schedule`]. `encrypt_4_blocks` broadcasts the round keys on every call, which is
+/// significant next to the rounds themselves, so bulk modes (like [`Ctr128`](ctr::Ctr128), and so GCM)
+/// build this once and use it in their inner loops instead
+///
+/// ```
+/// # use aes_crypto::{Aes256Enc, AesBlockX4, AesEncrypt};
+/// let cipher = Aes256Enc::from([0; 32]);
+/// let schedule = cipher.wide_schedule();
+///
+/// let blocks = AesBlockX4::from([1, 2, 3, 4]);
+/// assert_eq!(schedule.encrypt_4_blocks(blocks), cipher.encrypt_4_blocks(blocks));
+/// ```
+#[derive(Clone)]
+pub struct WideSchedule {
+    /// Only the first `rounds + 1` keys are used, so this fits every key size
+    round_keys: [AesBlockX4; 15],
+    rounds: usize,
+}
+
+impl WideSchedule {
+    fn new(round_keys: &[AesBlock]) -> Self {
+        let mut wide = [AesBlockX4::zero(); 15];
+        for (wide, &key) in wide.iter_mut().zip(round_keys) {
+            *wide = key.into();
+        }
+        Self {
+            round_keys: wide,
+            rounds: round_keys.len() - 1,
+        }
+    }
+
+    /// Encrypts four blocks, with the same result as [`AesEncrypt::encrypt_4_blocks`]
+    #[inline]
+    pub fn encrypt_4_blocks(&self, plaintext: AesBlockX4) -> AesBlockX4 {
+        plaintext.cipher_enc(&self.round_keys[..=self.rounds])
+    }
+
+    /// Encrypts eight blocks as two groups of four, with the same result as
+    /// [`AesEncrypt::encrypt_8_blocks`]
+    #[inline]
+    pub fn encrypt_8_blocks(&self, plaintext: AesBlockX8) -> AesBlockX8 {
+        let (a, b) = plaintext.into();
+        (self.encrypt_4_blocks(a), self.encrypt_4_blocks(b)).into()
+    }
+}
+
+/// Prints only the type name, as the round keys are the key
+impl Debug for WideSchedule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WideSchedule").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for WideSchedule {
+    fn zeroize(&mut self) {
+        self.round_keys.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for WideSchedule {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for WideSchedule {}
 
 macro_rules! implement_aes {
-    ($enc_name:ident, $dec_name:ident, $key_len:literal, $nr:literal, $keygen:ident) => {
-        #[derive(Debug, Clone)]
+    ($enc_name:ident, $dec_name:ident, $key_len:literal, $nr:literal, $keygen:ident, $kat:literal) => {
+        #[derive(Clone)]
         pub struct $enc_name {
             round_keys: [AesBlock; { $nr + 1 }],
         }
 
         impl private::Sealed for $enc_name {}
 
+        /// Prints only the type name, as the round keys are the key
+        impl Debug for $enc_name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($enc_name))
+                    .finish_non_exhaustive()
+            }
+        }
+
         impl From<[u8; $key_len]> for $enc_name {
             fn from(value: [u8; $key_len]) -> Self {
                 $enc_name {
@@ -442,13 +1886,55 @@ macro_rules! implement_aes {
             }
         }
 
-        #[derive(Debug, Clone)]
+        #[derive(Clone)]
         pub struct $dec_name {
             round_keys: [AesBlock; { $nr + 1 }],
         }
 
         impl private::Sealed for $dec_name {}
 
+        /// Prints only the type name, as the round keys are the key
+        impl Debug for $dec_name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($dec_name))
+                    .finish_non_exhaustive()
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::Zeroize for $enc_name {
+            fn zeroize(&mut self) {
+                self.round_keys.zeroize();
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl Drop for $enc_name {
+            fn drop(&mut self) {
+                zeroize::Zeroize::zeroize(self);
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::ZeroizeOnDrop for $enc_name {}
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::Zeroize for $dec_name {
+            fn zeroize(&mut self) {
+                self.round_keys.zeroize();
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl Drop for $dec_name {
+            fn drop(&mut self) {
+                zeroize::Zeroize::zeroize(self);
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::ZeroizeOnDrop for $dec_name {}
+
         impl From<[u8; $key_len]> for $dec_name {
             fn from(value: [u8; $key_len]) -> Self {
                 $enc_name::from(value).decrypter()
@@ -465,23 +1951,117 @@ macro_rules! implement_aes {
             }
 
             fn encrypt_block(&self, plaintext: AesBlock) -> AesBlock {
-                plaintext
-                    .chain_enc(&self.round_keys[..$nr])
-                    .enc_last(self.round_keys[$nr])
+                plaintext.cipher_enc(&self.round_keys)
             }
 
             fn encrypt_2_blocks(&self, plaintext: AesBlockX2) -> AesBlockX2 {
                 let round_keys = self.round_keys.map(Into::into);
-                plaintext
-                    .chain_enc(&round_keys[..$nr])
-                    .enc_last(round_keys[$nr])
+                plaintext.cipher_enc(&round_keys)
             }
 
             fn encrypt_4_blocks(&self, plaintext: AesBlockX4) -> AesBlockX4 {
                 let round_keys = self.round_keys.map(Into::into);
-                plaintext
-                    .chain_enc(&round_keys[..$nr])
-                    .enc_last(round_keys[$nr])
+                plaintext.cipher_enc(&round_keys)
+            }
+
+            fn wide_schedule(&self) -> WideSchedule {
+                WideSchedule::new(&self.round_keys)
+            }
+        }
+
+        impl $dec_name {
+            #[doc = concat!("Expands `key` like `from`, but first rejects the all-zero key, like [`", stringify!($enc_name), "::from_checked`]")]
+            ///
+            /// # Errors
+            ///
+            /// Returns [`WeakKeyError::Zero`] if `key` is all zeros
+            #[inline]
+            pub fn from_checked(key: [u8; $key_len]) -> Result<Self, WeakKeyError> {
+                $enc_name::from_checked(key).map(|enc| enc.decrypter())
+            }
+
+            #[doc = concat!("Expands `key` like `from`, but first rejects it if it fails `check`, like [`", stringify!($enc_name), "::from_checked_with`]")]
+            ///
+            /// # Errors
+            ///
+            /// Returns the [`WeakKeyError`] describing the key if it fails `check`
+            #[inline]
+            pub fn from_checked_with(
+                key: [u8; $key_len],
+                check: KeyCheck,
+            ) -> Result<Self, WeakKeyError> {
+                $enc_name::from_checked_with(key, check).map(|enc| enc.decrypter())
+            }
+
+            /// The round keys of the equivalent inverse cipher, in the order they are used, so the first
+            /// one is the last encryption round key, and the middle ones have `InvMixColumns` applied
+            #[inline]
+            pub fn round_keys(&self) -> &[AesBlock] {
+                &self.round_keys
+            }
+
+            /// A copy of the round keys, in a [`Zeroizing`] wrapper that wipes it when dropped
+            #[cfg(feature = "zeroize")]
+            #[inline]
+            pub fn round_keys_zeroizing(&self) -> Zeroizing<[AesBlock; { $nr + 1 }]> {
+                Zeroizing::new(self.round_keys)
+            }
+
+            /// Constructs a decrypter from an existing schedule, in the layout returned by
+            /// [`round_keys`](Self::round_keys). The schedule is trusted to be a valid decryption schedule.
+            /// Passing an encryption schedule here (or vice versa) is a logic error, and will silently produce
+            /// wrong output
+            #[inline]
+            #[must_use]
+            pub const fn from_round_keys(round_keys: [AesBlock; { $nr + 1 }]) -> Self {
+                Self { round_keys }
+            }
+
+            /// Imports a decryption key schedule in the layout of OpenSSL's `AES_KEY`, as filled by the
+            /// portable `AES_set_decrypt_key`. The layout is the same as in `from_openssl_schedule` of the
+            /// encrypter, and the round keys must be those of the equivalent inverse cipher, in the order
+            /// they are used (which is what OpenSSL produces)
+            ///
+            /// # Panics
+            ///
+            /// Panics if `words` does not have the right length
+            #[must_use]
+            pub fn from_openssl_schedule(words: &[u32]) -> Self {
+                Self {
+                    round_keys: round_keys_from_words(words),
+                }
+            }
+
+            /// Decrypts every block of `blocks` in place (i.e. in ECB mode). Except with the software
+            /// implementations, the main loop processes four blocks at a time as an [`AesBlockX4`], to keep
+            /// them in flight together and hide the latency of the round instructions (even where the type is
+            /// emulated, see [`HAS_NATIVE_X4`]). Then, with a native [`AesBlockX2`], two blocks at a time, and
+            /// the rest one by one
+            #[inline]
+            pub fn decrypt_blocks(&self, mut blocks: &mut [AesBlock]) {
+                if INTERLEAVE_BLOCKS {
+                    let mut chunks = core::mem::take(&mut blocks).chunks_exact_mut(4);
+                    for chunk in &mut chunks {
+                        let data =
+                            AesBlockX4::from_distinct(chunk[0], chunk[1], chunk[2], chunk[3]);
+                        (chunk[0], chunk[1], chunk[2], chunk[3]) =
+                            self.decrypt_4_blocks(data).into();
+                    }
+                    blocks = chunks.into_remainder();
+                }
+
+                if HAS_NATIVE_X2 {
+                    let mut chunks = core::mem::take(&mut blocks).chunks_exact_mut(2);
+                    for chunk in &mut chunks {
+                        let data = AesBlockX2::from_distinct(chunk[0], chunk[1]);
+                        (chunk[0], chunk[1]) = self.decrypt_2_blocks(data).into();
+                    }
+                    blocks = chunks.into_remainder();
+                }
+
+                for block in blocks {
+                    *block = self.decrypt_block(*block);
+                }
             }
         }
 
@@ -495,28 +2075,556 @@ macro_rules! implement_aes {
             }
 
             fn decrypt_block(&self, ciphertext: AesBlock) -> AesBlock {
-                ciphertext
-                    .chain_dec(&self.round_keys[..$nr])
-                    .dec_last(self.round_keys[$nr])
+                ciphertext.cipher_dec(&self.round_keys)
             }
 
             fn decrypt_2_blocks(&self, ciphertext: AesBlockX2) -> AesBlockX2 {
                 let round_keys = self.round_keys.map(Into::into);
-                ciphertext
-                    .chain_dec(&round_keys[..$nr])
-                    .dec_last(round_keys[$nr])
+                ciphertext.cipher_dec(&round_keys)
             }
 
             fn decrypt_4_blocks(&self, ciphertext: AesBlockX4) -> AesBlockX4 {
                 let round_keys = self.round_keys.map(Into::into);
-                ciphertext
-                    .chain_dec(&round_keys[..$nr])
-                    .dec_last(round_keys[$nr])
+                ciphertext.cipher_dec(&round_keys)
+            }
+        }
+
+        impl $enc_name {
+            /// Expands `key` like `from`, but first rejects the all-zero key, which usually means that the key
+            /// was never initialized. See [`from_checked_with`](Self::from_checked_with)
+            ///
+            /// # Errors
+            ///
+            /// Returns [`WeakKeyError::Zero`] if `key` is all zeros
+            ///
+            /// ```
+            #[doc = concat!("# use aes_crypto::{", stringify!($enc_name), ", WeakKeyError};")]
+            #[doc = concat!("assert_eq!(", stringify!($enc_name), "::from_checked([0; ", $key_len, "]).err(), Some(WeakKeyError::Zero));")]
+            #[doc = concat!("assert!(", stringify!($enc_name), "::from_checked([0x42; ", $key_len, "]).is_ok());")]
+            /// ```
+            #[inline]
+            pub fn from_checked(key: [u8; $key_len]) -> Result<Self, WeakKeyError> {
+                Self::from_checked_with(key, KeyCheck::Zero)
+            }
+
+            /// Expands `key` like `from`, but first rejects it if it fails `check`.
+            ///
+            /// AES has no known weak keys in the sense of DES, so this is only a sanity check against
+            /// uninitialized or placeholder key material, not a cryptographic test: a key that passes is not
+            /// any stronger for it, and a key from a broken generator usually passes too
+            ///
+            /// # Errors
+            ///
+            /// Returns the [`WeakKeyError`] describing the key if it fails `check`
+            #[inline]
+            pub fn from_checked_with(
+                key: [u8; $key_len],
+                check: KeyCheck,
+            ) -> Result<Self, WeakKeyError> {
+                check.check(&key)?;
+                Ok(Self::from(key))
+            }
+
+            /// The expanded round keys, in the order they are used
+            #[inline]
+            pub fn round_keys(&self) -> &[AesBlock] {
+                &self.round_keys
+            }
+
+            /// The round keys of FIPS-197's equivalent inverse cipher (section 5.3.5), which is the decryption
+            /// schedule, in the order `decrypt_block` uses them: element 0 is the last encryption round key,
+            /// elements `1..Nr` are the encryption round keys `Nr - 1` down to 1 with `InvMixColumns` applied,
+            /// and element `Nr` is the first encryption round key (the cipher key, or its first 16 bytes).
+            ///
+            /// This is the same as `self.decrypter().round_keys()`, and can be loaded directly by hardware
+            /// or libraries that expect pre-transformed decryption keys
+            ///
+            /// ```
+            #[doc = concat!("# use aes_crypto::{", stringify!($enc_name), ", AesEncrypt};")]
+            #[doc = concat!("let enc = ", stringify!($enc_name), "::from([1; ", $key_len, "]);")]
+            /// let keys = enc.equiv_inv_round_keys();
+            /// assert_eq!(keys, enc.decrypter().round_keys());
+            /// assert_eq!(keys[0], *enc.round_keys().last().unwrap());
+            /// assert_eq!(keys[1], enc.round_keys()[keys.len() - 2].imc());
+            /// ```
+            #[inline]
+            pub fn equiv_inv_round_keys(&self) -> [AesBlock; { $nr + 1 }] {
+                dec_round_keys(&self.round_keys)
+            }
+
+            /// A copy of the round keys, in a [`Zeroizing`] wrapper that wipes it when dropped
+            #[cfg(feature = "zeroize")]
+            #[inline]
+            pub fn round_keys_zeroizing(&self) -> Zeroizing<[AesBlock; { $nr + 1 }]> {
+                Zeroizing::new(self.round_keys)
+            }
+
+            /// Same as [`equiv_inv_round_keys`](Self::equiv_inv_round_keys), in a [`Zeroizing`] wrapper that
+            /// wipes the keys when dropped
+            #[cfg(feature = "zeroize")]
+            #[inline]
+            pub fn equiv_inv_round_keys_zeroizing(&self) -> Zeroizing<[AesBlock; { $nr + 1 }]> {
+                Zeroizing::new(self.equiv_inv_round_keys())
+            }
+
+            /// Constructs an encrypter from an existing schedule, e.g. one loaded from a file. The schedule
+            /// is trusted to be a valid encryption schedule. Passing a decryption schedule here (or vice
+            /// versa) is a logic error, and will silently produce wrong output
+            #[inline]
+            #[must_use]
+            pub const fn from_round_keys(round_keys: [AesBlock; { $nr + 1 }]) -> Self {
+                Self { round_keys }
+            }
+
+            /// Imports an encryption key schedule in the layout of OpenSSL's `AES_KEY`, as filled by the
+            /// portable `AES_set_encrypt_key`. `words` must have exactly `4 * (Nr + 1)` words (44, 52 or 60),
+            /// where round key `i` is made of the words `4i..4i + 4`, and each word holds 4 bytes of the round
+            /// key in big-endian order (`GETU32`), independently of the host endianness. For example, the first
+            /// word of the schedule of the key `k` is `u32::from_be_bytes([k[0], k[1], k[2], k[3]])`.
+            ///
+            /// The AES-NI code paths of OpenSSL store the round keys as raw bytes instead, and those
+            /// schedules must be converted to this layout first
+            ///
+            /// # Panics
+            ///
+            /// Panics if `words` does not have the right length
+            #[must_use]
+            pub fn from_openssl_schedule(words: &[u32]) -> Self {
+                Self {
+                    round_keys: round_keys_from_words(words),
+                }
+            }
+
+            /// Encrypts every block of `blocks` in place (i.e. in ECB mode). Except with the software
+            /// implementations, the main loop processes four blocks at a time as an [`AesBlockX4`], to keep
+            /// them in flight together and hide the latency of the round instructions (even where the type is
+            /// emulated, see [`HAS_NATIVE_X4`]). Then, with a native [`AesBlockX2`], two blocks at a time, and
+            /// the rest one by one
+            #[inline]
+            pub fn encrypt_blocks(&self, mut blocks: &mut [AesBlock]) {
+                if INTERLEAVE_BLOCKS {
+                    let mut chunks = core::mem::take(&mut blocks).chunks_exact_mut(4);
+                    for chunk in &mut chunks {
+                        let data =
+                            AesBlockX4::from_distinct(chunk[0], chunk[1], chunk[2], chunk[3]);
+                        (chunk[0], chunk[1], chunk[2], chunk[3]) =
+                            self.encrypt_4_blocks(data).into();
+                    }
+                    blocks = chunks.into_remainder();
+                }
+
+                if HAS_NATIVE_X2 {
+                    let mut chunks = core::mem::take(&mut blocks).chunks_exact_mut(2);
+                    for chunk in &mut chunks {
+                        let data = AesBlockX2::from_distinct(chunk[0], chunk[1]);
+                        (chunk[0], chunk[1]) = self.encrypt_2_blocks(data).into();
+                    }
+                    blocks = chunks.into_remainder();
+                }
+
+                for block in blocks {
+                    *block = self.encrypt_block(*block);
+                }
+            }
+
+            /// Encrypts every block of `src` into the same position of `dst` (i.e. in ECB mode), leaving
+            /// `src` intact; [`encrypt_blocks`](Self::encrypt_blocks) is the in-place version. Except with
+            /// the software implementations, the main loop encrypts eight blocks at a time as an
+            /// [`AesBlockX8`], to keep enough blocks in flight to hide the latency of the round
+            /// instructions, and the tail is handled four, two (with a native [`AesBlockX2`]) and then one
+            /// block at a time.
+            ///
+            /// The slices must have the same length. This is only checked in debug builds, and otherwise
+            /// only the blocks that fit in both are encrypted
+            ///
+            /// ```
+            /// # use aes_crypto::{Aes128Enc, AesBlock, AesEncrypt};
+            /// let cipher = Aes128Enc::from([0; 16]);
+            /// let plaintext: Vec<_> = (0..11).map(AesBlock::from).collect();
+            /// let mut ciphertext = vec![AesBlock::zero(); plaintext.len()];
+            ///
+            /// cipher.encrypt_par(&plaintext, &mut ciphertext);
+            /// assert_eq!(ciphertext[10], cipher.encrypt_block(plaintext[10]));
+            /// ```
+            #[inline]
+            pub fn encrypt_par(&self, src: &[AesBlock], dst: &mut [AesBlock]) {
+                debug_assert_eq!(
+                    src.len(),
+                    dst.len(),
+                    "`src` and `dst` must have the same length"
+                );
+                let len = src.len().min(dst.len());
+                let (mut src, mut dst) = (&src[..len], &mut dst[..len]);
+
+                if INTERLEAVE_BLOCKS {
+                    let src_chunks = src.chunks_exact(8);
+                    let mut dst_chunks = core::mem::take(&mut dst).chunks_exact_mut(8);
+                    src = src_chunks.remainder();
+                    for (s, d) in src_chunks.zip(&mut dst_chunks) {
+                        let data = AesBlockX8::from((
+                            AesBlockX4::from_distinct(s[0], s[1], s[2], s[3]),
+                            AesBlockX4::from_distinct(s[4], s[5], s[6], s[7]),
+                        ));
+                        let (a, b) = self.encrypt_8_blocks(data).into();
+                        (d[0], d[1], d[2], d[3]) = a.into();
+                        (d[4], d[5], d[6], d[7]) = b.into();
+                    }
+                    dst = dst_chunks.into_remainder();
+
+                    if src.len() >= 4 {
+                        let (s, d) = (&src[..4], &mut dst[..4]);
+                        let data = AesBlockX4::from_distinct(s[0], s[1], s[2], s[3]);
+                        (d[0], d[1], d[2], d[3]) = self.encrypt_4_blocks(data).into();
+                        src = &src[4..];
+                        dst = &mut core::mem::take(&mut dst)[4..];
+                    }
+                }
+
+                if HAS_NATIVE_X2 {
+                    let src_chunks = src.chunks_exact(2);
+                    let mut dst_chunks = core::mem::take(&mut dst).chunks_exact_mut(2);
+                    src = src_chunks.remainder();
+                    for (s, d) in src_chunks.zip(&mut dst_chunks) {
+                        (d[0], d[1]) = self
+                            .encrypt_2_blocks(AesBlockX2::from_distinct(s[0], s[1]))
+                            .into();
+                    }
+                    dst = dst_chunks.into_remainder();
+                }
+
+                for (s, d) in src.iter().zip(dst) {
+                    *d = self.encrypt_block(*s);
+                }
+            }
+
+            /// Encrypts the four counter blocks in `counters` and XORs the keystream into `data` in place,
+            /// then advances every lane of `counters` by four, incrementing its last 32 bits as a big-endian
+            /// counter (as in GCM). Consecutive calls therefore process consecutive 64-byte chunks of the
+            /// keystream, if the lanes start out as four consecutive counter blocks
+            #[inline]
+            #[allow(clippy::cast_possible_truncation)]
+            pub fn encrypt_4_blocks_xor(&self, counters: &mut AesBlockX4, data: &mut [u8; 64]) {
+                let keystream = self.encrypt_4_blocks(*counters);
+                (keystream ^ AesBlockX4::from(&*data)).store_to(data);
+
+                let advance = |block: AesBlock| {
+                    let value = u128::from(block);
+                    let ctr = (value as u32).wrapping_add(4);
+                    AesBlock::from((value & !u128::from(u32::MAX)) | u128::from(ctr))
+                };
+                let (a, b, c, d) = (*counters).into();
+                *counters =
+                    AesBlockX4::from_distinct(advance(a), advance(b), advance(c), advance(d));
+            }
+
+            /// Runs a known-answer self-test of this key size, checking `encrypt_block`, `decrypt_block`
+            /// and their 4-block versions. Returns `true` if the backend produces the correct output
+            #[must_use]
+            #[allow(clippy::cast_possible_truncation)]
+            pub fn self_test() -> bool {
+                let key: [u8; $key_len] = core::array::from_fn(|i| i as u8);
+                let plaintext = AesBlock::from(0x0011_2233_4455_6677_8899_aabb_ccdd_eeff);
+                let ciphertext = AesBlock::from($kat);
+
+                let enc = Self::from(key);
+                let dec = $dec_name::from(key);
+
+                enc.encrypt_block(plaintext) == ciphertext
+                    && dec.decrypt_block(ciphertext) == plaintext
+                    && enc.encrypt_4_blocks(plaintext.into()) == ciphertext.into()
+                    && dec.decrypt_4_blocks(ciphertext.into()) == plaintext.into()
             }
         }
     };
 }
 
-implement_aes!(Aes128Enc, Aes128Dec, 16, 10, keygen_128);
-implement_aes!(Aes192Enc, Aes192Dec, 24, 12, keygen_192);
-implement_aes!(Aes256Enc, Aes256Dec, 32, 14, keygen_256);
+// the known-answers are the FIPS-197 Appendix C example vectors
+implement_aes!(
+    Aes128Enc,
+    Aes128Dec,
+    16,
+    10,
+    keygen_128,
+    0x69c4_e0d8_6a7b_0430_d8cd_b780_70b4_c55a
+);
+implement_aes!(
+    Aes192Enc,
+    Aes192Dec,
+    24,
+    12,
+    keygen_192,
+    0xdda9_7ca4_864c_dfe0_6eaf_70a0_ec0d_7191
+);
+implement_aes!(
+    Aes256Enc,
+    Aes256Dec,
+    32,
+    14,
+    keygen_256,
+    0x8ea2_b7ca_5167_45bf_eafc_4990_4b49_6089
+);
+
+/// A summary of the implementations selected at compile time, e.g. for logging a diagnostic line about
+/// the crypto backend
+///
+/// ```
+/// let info = aes_crypto::backend_info();
+/// assert_eq!(info.is_constant_time, aes_crypto::IS_CONSTANT_TIME);
+/// std::println!("{info}");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct BackendInfo {
+    /// The implementation of [`AesBlock`]
+    pub aes_impl: &'static str,
+    /// The implementation of [`AesBlockX2`]
+    pub aes_x2_impl: &'static str,
+    /// The implementation of [`AesBlockX4`]
+    pub aes_x4_impl: &'static str,
+    /// Whether the `nightly` feature is enabled
+    pub nightly: bool,
+    /// See [`IS_CONSTANT_TIME`]
+    pub is_constant_time: bool,
+    /// See [`HAS_NATIVE_X2`]
+    pub has_native_x2: bool,
+    /// See [`HAS_NATIVE_X4`]
+    pub has_native_x4: bool,
+}
+
+impl Display for BackendInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AES: {}, AES-X2: {}, AES-X4: {}, nightly: {}, constant-time: {}",
+            self.aes_impl, self.aes_x2_impl, self.aes_x4_impl, self.nightly, self.is_constant_time
+        )
+    }
+}
+
+/// Returns the implementations selected at compile time
+#[must_use]
+pub const fn backend_info() -> BackendInfo {
+    BackendInfo {
+        aes_impl: AES_IMPL,
+        aes_x2_impl: AES_X2_IMPL,
+        aes_x4_impl: AES_X4_IMPL,
+        nightly: cfg!(feature = "nightly"),
+        is_constant_time: IS_CONSTANT_TIME,
+        has_native_x2: HAS_NATIVE_X2,
+        has_native_x4: HAS_NATIVE_X4,
+    }
+}
+
+/// The error returned when a key does not have one of the AES key lengths (16, 24 or 32 bytes). It holds
+/// the length of the rejected key
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidKeyLength(pub usize);
+
+impl Display for InvalidKeyLength {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid AES key length {}, expected 16, 24 or 32 bytes",
+            self.0
+        )
+    }
+}
+
+impl core::error::Error for InvalidKeyLength {}
+
+/// The checks done by the `from_checked_with` constructors of the key schedules (e.g.
+/// [`Aes128Enc::from_checked_with`]). Each check includes the ones before it
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum KeyCheck {
+    /// Rejects the all-zero key
+    #[default]
+    Zero,
+    /// Rejects keys that are a single byte repeated, like all-zero or all-`0xff` keys
+    RepeatedByte,
+}
+
+impl KeyCheck {
+    fn check(self, key: &[u8]) -> Result<(), WeakKeyError> {
+        // fold over the whole key instead of returning early, so the time taken does not depend on where
+        // the key differs
+        let first = key[0];
+        let same = key.iter().fold(0, |acc, &x| acc | (x ^ first));
+        if same == 0 && (first == 0 || self == KeyCheck::RepeatedByte) {
+            Err(if first == 0 {
+                WeakKeyError::Zero
+            } else {
+                WeakKeyError::RepeatedByte(first)
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The error returned when a key is rejected by a [`KeyCheck`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WeakKeyError {
+    /// The key is all zeros
+    Zero,
+    /// The key is a single byte, which is held, repeated
+    RepeatedByte(u8),
+}
+
+impl Display for WeakKeyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Zero => f.write_str("the AES key is all zeros"),
+            Self::RepeatedByte(x) => write!(f, "the AES key is the byte {x:#04x} repeated"),
+        }
+    }
+}
+
+impl core::error::Error for WeakKeyError {}
+
+/// The error returned when a slice is too short to load a block from. It holds the length of the slice, and
+/// the length that was needed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidLength {
+    pub got: usize,
+    pub need: usize,
+}
+
+impl Display for InvalidLength {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid slice length {}, expected at least {} bytes",
+            self.got, self.need
+        )
+    }
+}
+
+impl core::error::Error for InvalidLength {}
+
+macro_rules! dispatch {
+    ($self:ident, $cipher:ident => $expr:expr) => {
+        match $self {
+            Self::Aes128($cipher) => $expr,
+            Self::Aes192($cipher) => $expr,
+            Self::Aes256($cipher) => $expr,
+        }
+    };
+}
+
+/// An AES encrypter whose key size is chosen at runtime. Every method forwards to the wrapped
+/// key-size-specific encrypter
+///
+/// ```
+/// # use aes_crypto::{Aes, AesBlock};
+/// let key = [0; 24];
+/// let aes = Aes::new(&key).unwrap();
+///
+/// let ciphertext = aes.encrypt_block(AesBlock::zero());
+/// assert_eq!(aes.decrypter().decrypt_block(ciphertext), AesBlock::zero());
+/// assert!(Aes::new(&key[..20]).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub enum Aes {
+    Aes128(Aes128Enc),
+    Aes192(Aes192Enc),
+    Aes256(Aes256Enc),
+}
+
+impl Aes {
+    /// Creates an encrypter, choosing the key size from the length of `key`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidKeyLength`] if `key` is not 16, 24 or 32 bytes long
+    pub fn new(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        Ok(match key.len() {
+            16 => Self::Aes128(Aes128Enc::from(array_from_slice::<16>(key, 0))),
+            24 => Self::Aes192(Aes192Enc::from(array_from_slice::<24>(key, 0))),
+            32 => Self::Aes256(Aes256Enc::from(array_from_slice::<32>(key, 0))),
+            len => return Err(InvalidKeyLength(len)),
+        })
+    }
+
+    #[must_use]
+    pub fn decrypter(&self) -> AesDec {
+        match self {
+            Self::Aes128(enc) => AesDec::Aes128(enc.decrypter()),
+            Self::Aes192(enc) => AesDec::Aes192(enc.decrypter()),
+            Self::Aes256(enc) => AesDec::Aes256(enc.decrypter()),
+        }
+    }
+
+    #[inline]
+    pub fn encrypt_block(&self, plaintext: AesBlock) -> AesBlock {
+        dispatch!(self, enc => enc.encrypt_block(plaintext))
+    }
+
+    #[inline]
+    pub fn encrypt_2_blocks(&self, plaintext: AesBlockX2) -> AesBlockX2 {
+        dispatch!(self, enc => enc.encrypt_2_blocks(plaintext))
+    }
+
+    #[inline]
+    pub fn encrypt_4_blocks(&self, plaintext: AesBlockX4) -> AesBlockX4 {
+        dispatch!(self, enc => enc.encrypt_4_blocks(plaintext))
+    }
+}
+
+/// An AES decrypter whose key size is chosen at runtime. Every method forwards to the wrapped
+/// key-size-specific decrypter
+#[derive(Debug, Clone)]
+pub enum AesDec {
+    Aes128(Aes128Dec),
+    Aes192(Aes192Dec),
+    Aes256(Aes256Dec),
+}
+
+impl AesDec {
+    /// Creates a decrypter, choosing the key size from the length of `key`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidKeyLength`] if `key` is not 16, 24 or 32 bytes long
+    pub fn new(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        Aes::new(key).map(|enc| enc.decrypter())
+    }
+
+    #[must_use]
+    pub fn encrypter(&self) -> Aes {
+        match self {
+            Self::Aes128(dec) => Aes::Aes128(dec.encrypter()),
+            Self::Aes192(dec) => Aes::Aes192(dec.encrypter()),
+            Self::Aes256(dec) => Aes::Aes256(dec.encrypter()),
+        }
+    }
+
+    #[inline]
+    pub fn decrypt_block(&self, ciphertext: AesBlock) -> AesBlock {
+        dispatch!(self, dec => dec.decrypt_block(ciphertext))
+    }
+
+    #[inline]
+    pub fn decrypt_2_blocks(&self, ciphertext: AesBlockX2) -> AesBlockX2 {
+        dispatch!(self, dec => dec.decrypt_2_blocks(ciphertext))
+    }
+
+    #[inline]
+    pub fn decrypt_4_blocks(&self, ciphertext: AesBlockX4) -> AesBlockX4 {
+        dispatch!(self, dec => dec.decrypt_4_blocks(ciphertext))
+    }
+}
+
+/// Runs a known-answer self-test of every key size on the selected backend, returning `true` if all of
+/// them produce the correct output.
+///
+/// This is intended as a power-on self-test (as required by e.g. FIPS 140), or simply to guard against
+/// a miscompiled or misdetected backend. Call it once at application startup, and refuse to continue if
+/// it fails
+///
+/// ```
+/// assert!(aes_crypto::self_test(), "AES backend is broken");
+/// ```
+#[must_use]
+pub fn self_test() -> bool {
+    Aes128Enc::self_test() & Aes192Enc::self_test() & Aes256Enc::self_test()
+}
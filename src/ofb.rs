@@ -0,0 +1,72 @@
+use crate::ctr::xor_in_place;
+use crate::{AesBlock, AesEncrypt};
+use core::fmt::{self, Debug, Formatter};
+
+/// AES in output feedback mode (NIST SP 800-38A), as a stream cipher. The keystream is generated by
+/// repeatedly encrypting the feedback register, starting from the IV, so unlike counter mode it is strictly
+/// sequential. The position in the keystream is kept across calls, so applying it to a message in several
+/// chunks gives the same result as applying it in one go
+///
+/// ```
+/// # use aes_crypto::{ofb::Ofb, Aes128Enc};
+/// let cipher = Aes128Enc::from([0; 16]);
+/// let mut data = *b"some secret message";
+///
+/// Ofb::new(cipher.clone(), [0; 16]).apply_keystream(&mut data);
+///
+/// let mut ofb = Ofb::new(cipher, [0; 16]);
+/// ofb.apply_keystream(&mut data[..5]);
+/// ofb.apply_keystream(&mut data[5..]);
+/// assert_eq!(&data, b"some secret message");
+/// ```
+#[derive(Clone)]
+pub struct Ofb<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> {
+    cipher: E,
+    register: [u8; 16],
+    pos: usize,
+}
+
+/// Prints only the type name, as the feedback register is the current keystream block
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> Debug for Ofb<E, KEY_LEN> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ofb").finish_non_exhaustive()
+    }
+}
+
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> Ofb<E, KEY_LEN> {
+    /// Creates an output feedback stream with the feedback register set to `iv`
+    #[inline]
+    pub fn new(cipher: E, iv: [u8; 16]) -> Self {
+        Self {
+            cipher,
+            register: iv,
+            pos: 16,
+        }
+    }
+
+    /// XORs the keystream into `data` in place. Encryption and decryption are the same operation
+    pub fn apply_keystream(&mut self, mut data: &mut [u8]) {
+        while !data.is_empty() {
+            if self.pos == 16 {
+                self.register = self.cipher.encrypt_block(self.register.into()).into();
+                self.pos = 0;
+            }
+            let n = data.len().min(16 - self.pos);
+            let (head, rest) = data.split_at_mut(n);
+            xor_in_place(head, &self.register[self.pos..self.pos + n]);
+            self.pos += n;
+            data = rest;
+        }
+    }
+}
+
+/// Applies the OFB keystream starting from `iv` to `data` in place. Use [`Ofb`] to process a message in
+/// several chunks
+#[inline]
+pub fn ofb_apply<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(
+    enc: &E,
+    iv: AesBlock,
+    data: &mut [u8],
+) {
+    Ofb::new(enc.clone(), iv.into()).apply_keystream(data);
+}
@@ -0,0 +1,72 @@
+//! The POLYVAL universal hash of AES-GCM-SIV ([RFC 8452](https://www.rfc-editor.org/rfc/rfc8452)). It is
+//! GHASH with the bytes of every block reversed, so it is computed with [`GHash`] under the key
+//! `mulX_GHASH(ByteReverse(H))`, as described in Appendix A of the RFC, and shares its carryless
+//! multiplication
+
+use crate::ghash::{mul_x, GHash};
+use crate::{AesBlock, AesBlockX4};
+use core::fmt::{self, Debug, Formatter};
+
+/// A POLYVAL computation under the hash key `H`, which absorbs 16-byte blocks and returns the final
+/// accumulator. Blocks are little-endian polynomials, so [`AesBlock::from_u64_le_pair`] builds the
+/// lengths block of AES-GCM-SIV
+///
+/// ```
+/// # use aes_crypto::{polyval::Polyval, AesBlock};
+/// let mut polyval = Polyval::new(AesBlock::from(0x25629347589242761d31f826ba4b757b));
+/// polyval.update(AesBlock::from(0x4f4f95668c83dfb6401762bb2d01a262));
+/// polyval.update(AesBlock::from(0xd1a24ddd2721d006bbe45f20d3c9f362));
+/// assert_eq!(polyval.finalize(), AesBlock::from(0xf7a3b47b846119fae5b7866cf5e5b77e));
+/// ```
+#[derive(Clone)]
+pub struct Polyval(GHash);
+
+/// Prints only the type name, as the accumulator gives away the hash key once a block is known
+impl Debug for Polyval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Polyval").finish_non_exhaustive()
+    }
+}
+
+impl Polyval {
+    /// Creates a POLYVAL computation with hash key `h` and an all-zero accumulator
+    #[inline]
+    #[must_use]
+    pub fn new(h: AesBlock) -> Self {
+        Self(GHash::new(mul_x(h.reverse_bytes().into()).into()))
+    }
+
+    /// Absorbs a single block
+    #[inline]
+    pub fn update(&mut self, block: AesBlock) {
+        self.0.update(block.reverse_bytes());
+    }
+
+    /// Absorbs `bytes`, zero-padding the last block if the length is not a multiple of 16, as AES-GCM-SIV
+    /// does for the AAD and the plaintext. The padding is applied at the end of every call, so data split
+    /// across several calls has to be split at block boundaries to hash the same as a single call
+    pub fn update_padded(&mut self, bytes: &[u8]) {
+        let block = |chunk: &[u8]| AesBlock::try_from(chunk).unwrap().reverse_bytes();
+
+        let mut chunks = bytes.chunks_exact(64);
+        for chunk in &mut chunks {
+            self.0.update_4_blocks(AesBlockX4::from((
+                block(&chunk[..16]),
+                block(&chunk[16..32]),
+                block(&chunk[32..48]),
+                block(&chunk[48..]),
+            )));
+        }
+        for chunk in chunks.remainder().chunks(16) {
+            let mut padded = [0; 16];
+            padded[..chunk.len()].copy_from_slice(chunk);
+            self.update(padded.into());
+        }
+    }
+
+    /// Returns the accumulator, which is the POLYVAL of all the blocks absorbed so far
+    #[inline]
+    pub fn finalize(self) -> AesBlock {
+        self.0.finalize().reverse_bytes()
+    }
+}
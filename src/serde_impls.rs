@@ -0,0 +1,100 @@
+//! `Serialize`/`Deserialize` for the block types. Blocks are serialized as their canonical bytes, as a
+//! fixed-length tuple of bytes for binary formats, and as a lowercase hex string for human-readable ones
+
+use crate::{AesBlock, AesBlockX2, AesBlockX4, AesBlockX8};
+use core::fmt::{self, Formatter};
+use core::marker::PhantomData;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|x| write!(f, "{x:>02x}"))
+    }
+}
+
+fn hex_digit(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        b'A'..=b'F' => Some(digit - b'A' + 10),
+        _ => None,
+    }
+}
+
+struct BytesVisitor<T, const N: usize>(PhantomData<T>);
+
+impl<'de, T: From<[u8; N]>, const N: usize> Visitor<'de> for BytesVisitor<T, N> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{N} bytes, or a string of {} hex digits", 2 * N)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        if v.len() != 2 * N {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        let mut bytes = [0; N];
+        for (byte, pair) in bytes.iter_mut().zip(v.as_bytes().chunks_exact(2)) {
+            match (hex_digit(pair[0]), hex_digit(pair[1])) {
+                (Some(hi), Some(lo)) => *byte = (hi << 4) | lo,
+                _ => return Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+            }
+        }
+        Ok(bytes.into())
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<T, E> {
+        <[u8; N]>::try_from(v)
+            .map(T::from)
+            .map_err(|_| E::invalid_length(v.len(), &self))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let mut bytes = [0; N];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(N + 1, &self));
+        }
+        Ok(bytes.into())
+    }
+}
+
+macro_rules! impl_serde {
+    ($($name:ty, $len:literal),*) => {$(
+    impl Serialize for $name {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let bytes = <[u8; $len]>::from(*self);
+            if serializer.is_human_readable() {
+                serializer.collect_str(&Hex(&bytes))
+            } else {
+                let mut tuple = serializer.serialize_tuple($len)?;
+                for byte in &bytes {
+                    tuple.serialize_element(byte)?;
+                }
+                tuple.end()
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for $name {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let visitor = BytesVisitor::<Self, $len>(PhantomData);
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_str(visitor)
+            } else {
+                deserializer.deserialize_tuple($len, visitor)
+            }
+        }
+    }
+    )*};
+}
+
+impl_serde!(AesBlock, 16, AesBlockX2, 32, AesBlockX4, 64, AesBlockX8, 128);
@@ -1,3 +1,5 @@
+extern crate std;
+
 use hex::FromHex;
 use lazy_static::lazy_static;
 
@@ -24,6 +26,105 @@ fn aesenc_test() {
     assert_eq!((enc ^ rk).imc().dec_last(AesBlock::zero()), block);
 }
 
+#[test]
+fn chain_encdec_test() {
+    // FIPS-197 Appendix C, with the rounds run through `chain_enc`/`chain_dec`, which some backends
+    // implement with fused instructions
+    let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+    let plaintext = AesBlock::from(0x00112233445566778899aabbccddeeff);
+
+    let enc128 = Aes128Enc::from(<[u8; 16]>::try_from(&key[..16]).unwrap());
+    let enc192 = Aes192Enc::from(<[u8; 24]>::try_from(&key[..24]).unwrap());
+    let enc256 = Aes256Enc::from(key);
+    let (dec128, dec192, dec256) = (enc128.decrypter(), enc192.decrypter(), enc256.decrypter());
+    let cases: [(&[AesBlock], &[AesBlock], u128); 3] = [
+        (
+            enc128.round_keys(),
+            dec128.round_keys(),
+            0x69c4e0d86a7b0430d8cdb78070b4c55a,
+        ),
+        (
+            enc192.round_keys(),
+            dec192.round_keys(),
+            0xdda97ca4864cdfe06eaf70a0ec0d7191,
+        ),
+        (
+            enc256.round_keys(),
+            dec256.round_keys(),
+            0x8ea2b7ca516745bfeafc49904b496089,
+        ),
+    ];
+
+    for (enc_keys, dec_keys, ciphertext) in cases {
+        let nr = enc_keys.len() - 1;
+        let ciphertext = AesBlock::from(ciphertext);
+        assert_eq!(
+            plaintext.chain_enc(&enc_keys[..nr]).enc_last(enc_keys[nr]),
+            ciphertext
+        );
+        assert_eq!(
+            ciphertext.chain_dec(&dec_keys[..nr]).dec_last(dec_keys[nr]),
+            plaintext
+        );
+
+        let wide_enc: [AesBlockX4; 15] = core::array::from_fn(|i| enc_keys[i.min(nr)].into());
+        let wide_dec: [AesBlockX4; 15] = core::array::from_fn(|i| dec_keys[i.min(nr)].into());
+        assert_eq!(
+            AesBlockX4::from(plaintext)
+                .chain_enc(&wide_enc[..nr])
+                .enc_last(wide_enc[nr]),
+            ciphertext.into()
+        );
+        assert_eq!(
+            AesBlockX4::from(ciphertext)
+                .chain_dec(&wide_dec[..nr])
+                .dec_last(wide_dec[nr]),
+            plaintext.into()
+        );
+    }
+}
+
+#[test]
+fn pre_encdec_test() {
+    let block = AesBlock::from(0x000102030405060708090a0b0c0d0e0f);
+    let rk = AesBlock::from(0x101112131415161718191a1b1c1d1e1f);
+    let enc = AesBlock::from(0x7a7b4e5638782546a8c0477a3b813f43);
+    let pre_key = AesBlock::from(0x2b7e151628aed2a6abf7158809cf4f3c);
+
+    // the same contract on every backend: the key is added before the round, not after it
+    assert_eq!((block ^ pre_key).pre_enc(pre_key) ^ rk, enc);
+    assert_eq!(block.pre_enc(rk), (block ^ rk).enc(AesBlock::zero()));
+    assert_eq!(enc.pre_dec(rk), (enc ^ rk).dec(AesBlock::zero()));
+    assert_eq!(
+        (enc ^ rk ^ pre_key).pre_dec(pre_key),
+        (enc ^ rk).dec(AesBlock::zero())
+    );
+
+    let keys = [rk, pre_key, block, enc];
+    let chained = keys[1..3]
+        .iter()
+        .fold(block ^ keys[0], |acc, &key| acc.enc(key));
+    assert_eq!(block.pre_enc(keys[0]).pre_enc(keys[1]) ^ keys[2], chained);
+    assert_eq!(block.chain_enc(&keys[..3]), chained);
+
+    let x2 = AesBlockX2::from((block, enc));
+    let rk2 = AesBlockX2::from((rk, pre_key));
+    assert_eq!(
+        x2.pre_enc(rk2),
+        (block.pre_enc(rk), enc.pre_enc(pre_key)).into()
+    );
+    assert_eq!(
+        x2.pre_dec(rk2),
+        (block.pre_dec(rk), enc.pre_dec(pre_key)).into()
+    );
+    let x4 = AesBlockX4::from((x2, x2));
+    let rk4 = AesBlockX4::from((rk2, rk2));
+    assert_eq!(x4.pre_enc(rk4), (x2.pre_enc(rk2), x2.pre_enc(rk2)).into());
+    let x8 = AesBlockX8::from((x4, x4));
+    let rk8 = AesBlockX8::from((rk4, rk4));
+    assert_eq!(x8.pre_dec(rk8), (x4.pre_dec(rk4), x4.pre_dec(rk4)).into());
+}
+
 #[test]
 fn expansion_of_128_bit_key() {
     let expanded = keygen_128(*AES_128_KEY);
@@ -78,6 +179,27 @@ fn expansion_of_256_bit_key() {
     assert_eq!(expanded[14], 0xfe4890d1e6188d0b046df344706c631e_u128.into());
 }
 
+#[test]
+fn equiv_inv_round_keys_test() {
+    // FIPS-197, Appendix C.1 (the `ik_sch` of the equivalent inverse cipher)
+    let enc = Aes128Enc::from(<[u8; 16]>::from_hex("000102030405060708090a0b0c0d0e0f").unwrap());
+    let keys = enc.equiv_inv_round_keys();
+    assert_eq!(keys[0], 0x13111d7fe3944a17f307a78b4d2b30c5_u128.into());
+    assert_eq!(keys[1], 0x13aa29be9c8faff6f770f58000f7bf03_u128.into());
+    assert_eq!(keys[2], 0x1362a4638f2586486bff5a76f7874a83_u128.into());
+    assert_eq!(keys[10], 0x000102030405060708090a0b0c0d0e0f_u128.into());
+
+    let enc = Aes192Enc::from(*AES_192_KEY);
+    assert_eq!(enc.equiv_inv_round_keys(), enc.decrypter().round_keys());
+    let enc = Aes256Enc::from(*AES_256_KEY);
+    let keys = enc.equiv_inv_round_keys();
+    assert_eq!(keys, enc.decrypter().round_keys());
+    assert_eq!(
+        Aes256Dec::from_round_keys(keys).decrypt_block(enc.encrypt_block(AesBlock::zero())),
+        AesBlock::zero()
+    );
+}
+
 macro_rules! aes_test {
     (enc: $enc:ident, $vectors:ident) => {
         assert_eq!($enc.encrypt_block($vectors[0].0), $vectors[0].1);
@@ -237,3 +359,3271 @@ fn aes_256_test() {
 
     aes_test!(dec: dec, AES_256_VECTORS);
 }
+
+#[test]
+fn new_nonzero_test() {
+    assert_eq!(AesBlock::new_nonzero([0; 16]), None);
+
+    let mut bytes = [0; 16];
+    bytes[15] = 1;
+    assert_eq!(AesBlock::new_nonzero(bytes), Some(AesBlock::from(1)));
+    assert_eq!(
+        AesBlock::new_nonzero([0xff; 16]),
+        Some(AesBlock::from(u128::MAX))
+    );
+}
+
+#[test]
+fn wide_constructors_test() {
+    let a = AesBlock::from(0x000102030405060708090a0b0c0d0e0f);
+    let b = AesBlock::from(0x101112131415161718191a1b1c1d1e1f);
+    let c = AesBlock::from(0x202122232425262728292a2b2c2d2e2f);
+    let d = AesBlock::from(0x303132333435363738393a3b3c3d3e3f);
+
+    assert_eq!(AesBlockX2::from_distinct(a, b), AesBlockX2::from((a, b)));
+    assert_eq!(<(_, _)>::from(AesBlockX2::from_distinct(a, b)), (a, b));
+    assert_eq!(<(_, _)>::from(AesBlockX2::from_broadcast(a)), (a, a));
+
+    assert_eq!(
+        AesBlockX4::from_distinct(a, b, c, d),
+        AesBlockX4::from((a, b, c, d))
+    );
+    assert_eq!(
+        <(_, _, _, _)>::from(AesBlockX4::from_distinct(a, b, c, d)),
+        (a, b, c, d)
+    );
+    assert_eq!(
+        <(_, _, _, _)>::from(AesBlockX4::from_broadcast(c)),
+        (c, c, c, c)
+    );
+}
+
+#[test]
+fn hash_test() {
+    use core::hash::BuildHasher;
+    use std::collections::hash_map::RandomState;
+    use std::collections::HashSet;
+
+    let a = AesBlock::from(0x000102030405060708090a0b0c0d0e0f);
+    let b = AesBlock::from(0x101112131415161718191a1b1c1d1e1f);
+
+    // equal blocks hash the same however they were built, and the hash is that of the canonical bytes
+    let state = RandomState::new();
+    assert_eq!(
+        state.hash_one(a),
+        state.hash_one(AesBlock::from(
+            *b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f"
+        ))
+    );
+    assert_eq!(state.hash_one(a), state.hash_one(a.to_bytes()));
+
+    let mut set = HashSet::new();
+    assert!(set.insert(a));
+    assert!(!set.insert(AesBlock::new(a.to_bytes())));
+    assert!(set.insert(b));
+    assert!(!set.insert(b ^ AesBlock::zero()));
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn wide_hash_test() {
+    use std::collections::HashSet;
+
+    let a = AesBlock::from(0x000102030405060708090a0b0c0d0e0f);
+    let b = AesBlock::from(0x101112131415161718191a1b1c1d1e1f);
+
+    let mut set = HashSet::new();
+    assert!(set.insert(AesBlockX2::from((a, b))));
+    assert!(!set.insert(AesBlockX2::from_distinct(a, b)));
+    assert!(!set.insert(AesBlockX2::new(
+        <[u8; 32]>::from_hex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f")
+            .unwrap()
+    )));
+    assert!(set.insert(AesBlockX2::from((b, a))));
+    assert_eq!(set.len(), 2);
+
+    let mut set = HashSet::new();
+    assert!(set.insert(AesBlockX4::from((a, b, a, b))));
+    assert!(!set.insert(AesBlockX4::from(AesBlockX2::from((a, b)))));
+    assert!(set.insert(AesBlockX4::from_broadcast(a)));
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn verify_and_release_test() {
+    let tag = [0x5a; 16];
+    let mut buf = [0xaa; 40];
+    assert_eq!(aead::verify_and_release(&mut buf, &tag, &tag), Ok(()));
+    assert_eq!(buf, [0xaa; 40]);
+
+    let mut bad_tag = tag;
+    bad_tag[15] ^= 1;
+    assert_eq!(
+        aead::verify_and_release(&mut buf, &tag, &bad_tag),
+        Err(aead::AuthError)
+    );
+    assert_eq!(buf, [0; 40]);
+
+    let mut buf = [0xaa; 40];
+    assert_eq!(
+        aead::verify_and_release(&mut buf, &tag, &tag[..12]),
+        Err(aead::AuthError)
+    );
+    assert_eq!(buf, [0; 40]);
+
+    let error: &dyn core::error::Error = &aead::AuthError;
+    assert_eq!(std::format!("{error}"), "authentication tag mismatch");
+}
+
+#[test]
+fn ne_u128_test() {
+    for &(pt, ct) in AES_128_VECTORS.iter() {
+        assert_eq!(AesBlock::from_ne_u128(pt.to_ne_u128()), pt);
+        assert_eq!(AesBlock::from_ne_u128(ct.to_ne_u128()), ct);
+    }
+    // the raw layout is backend-dependent, but these are fixed by symmetry
+    assert_eq!(AesBlock::zero().to_ne_u128(), 0);
+    assert_eq!(AesBlock::from([0xff; 16]).to_ne_u128(), u128::MAX);
+    assert_eq!(AesBlock::from_ne_u128(0), AesBlock::zero());
+}
+
+#[test]
+fn const_eq_test() {
+    const A: AesBlock = AesBlock::new(*b"known block 0001");
+    const B: AesBlock = AesBlock::new(*b"known block 0002");
+    const { assert!(A.const_eq(A)) };
+    const { assert!(!A.const_eq(B)) };
+
+    for &(pt, ct) in AES_128_VECTORS.iter() {
+        assert!(pt.const_eq(pt));
+        assert_eq!(pt.const_eq(ct), pt == ct);
+    }
+}
+
+#[test]
+fn u64_pair_test() {
+    let (hi, lo) = (0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210);
+
+    let be = AesBlock::from_high_low(hi, lo);
+    assert_eq!(u128::from(be), (u128::from(hi) << 64) | u128::from(lo));
+    assert_eq!(be.to_high_low(), (hi, lo));
+
+    let le = AesBlock::from_u64_le_pair(lo, hi);
+    assert_eq!(
+        le.to_bytes(),
+        ((u128::from(hi) << 64) | u128::from(lo)).to_le_bytes()
+    );
+    assert_eq!(le.to_u64_le_pair(), (lo, hi));
+
+    // the two conventions are byte reversals of each other, not the same block
+    assert_ne!(le, be);
+    assert_eq!(le, be.reverse_bytes());
+    assert_eq!(le.reverse_bytes(), be);
+    assert_eq!(be.reverse_bytes().reverse_bytes(), be);
+    assert_eq!(le.reverse_bytes().to_high_low(), (hi, lo));
+
+    for &(pt, _) in AES_128_VECTORS.iter() {
+        let (hi, lo) = pt.to_high_low();
+        assert_eq!(AesBlock::from_high_low(hi, lo), pt);
+        let (lo, hi) = pt.to_u64_le_pair();
+        assert_eq!(AesBlock::from_u64_le_pair(lo, hi), pt);
+    }
+}
+
+#[test]
+fn self_test_test() {
+    assert!(Aes128Enc::self_test());
+    assert!(Aes192Enc::self_test());
+    assert!(Aes256Enc::self_test());
+    assert!(self_test());
+}
+
+#[test]
+fn wide_mixcolumns_test() {
+    let (a, b, c, d) = (
+        AES_128_VECTORS[0].0,
+        AES_128_VECTORS[1].0,
+        AES_128_VECTORS[2].0,
+        AES_128_VECTORS[3].0,
+    );
+
+    let x2 = AesBlockX2::from((a, b));
+    assert_eq!(x2.mc(), AesBlockX2::from((a.mc(), b.mc())));
+    assert_eq!(x2.imc(), AesBlockX2::from((a.imc(), b.imc())));
+    assert_eq!(x2.mc().imc(), x2);
+    assert_eq!(x2.imc().mc(), x2);
+
+    let x4 = AesBlockX4::from((a, b, c, d));
+    assert_eq!(x4.mc(), AesBlockX4::from((a.mc(), b.mc(), c.mc(), d.mc())));
+    assert_eq!(
+        x4.imc(),
+        AesBlockX4::from((a.imc(), b.imc(), c.imc(), d.imc()))
+    );
+    assert_eq!(x4.mc().imc(), x4);
+    assert_eq!(x4.imc().mc(), x4);
+}
+
+#[test]
+fn to_bytes_test() {
+    const BYTES: [u8; 16] = AesBlock::new(*b"0123456789abcdef").to_bytes();
+    assert_eq!(&BYTES, b"0123456789abcdef");
+
+    for &(pt, ct) in AES_128_VECTORS.iter() {
+        assert_eq!(pt.to_bytes(), <[u8; 16]>::from(pt));
+        assert_eq!(AesBlock::from(ct.to_bytes()), ct);
+    }
+}
+
+#[test]
+fn counter_increment_test() {
+    use ctr::CounterWidth;
+
+    let block = AesBlock::from(0x01234567_89abcdef_01234567_fffffffe);
+    assert_eq!(
+        CounterWidth::Bits32.increment(block),
+        0x01234567_89abcdef_01234567_ffffffff.into()
+    );
+
+    let block = AesBlock::from(0x01234567_89abcdef_01234567_ffffffff);
+    assert_eq!(
+        CounterWidth::Bits32.increment(block),
+        0x01234567_89abcdef_01234567_00000000.into()
+    );
+    assert_eq!(
+        CounterWidth::Bits64.increment(block),
+        0x01234567_89abcdef_01234568_00000000.into()
+    );
+    assert_eq!(
+        CounterWidth::Bits128.increment(block),
+        0x01234567_89abcdef_01234568_00000000.into()
+    );
+
+    let block = AesBlock::from(0x01234567_89abcdef_ffffffff_ffffffff);
+    assert_eq!(
+        CounterWidth::Bits32.increment(block),
+        0x01234567_89abcdef_ffffffff_00000000.into()
+    );
+    assert_eq!(
+        CounterWidth::Bits64.increment(block),
+        0x01234567_89abcdef_00000000_00000000.into()
+    );
+    assert_eq!(
+        CounterWidth::Bits128.increment(block),
+        0x01234567_89abcdf0_00000000_00000000.into()
+    );
+
+    let block = AesBlock::from(u128::MAX);
+    assert_eq!(
+        CounterWidth::Bits32.increment(block),
+        (u128::MAX << 32).into()
+    );
+    assert_eq!(
+        CounterWidth::Bits64.increment(block),
+        (u128::MAX << 64).into()
+    );
+    assert_eq!(CounterWidth::Bits128.increment(block), AesBlock::zero());
+}
+
+#[test]
+fn from_key_bytes_test() {
+    let key = AesBlock::from_key_bytes(&AES_128_KEY);
+    assert_eq!(u128::from(key), u128::from_be_bytes(*AES_128_KEY));
+    assert_eq!(key, keygen_128(*AES_128_KEY)[0]);
+
+    let key = AesBlock::from_key_bytes(AES_256_KEY[..16].try_into().unwrap());
+    assert_eq!(key, keygen_256(*AES_256_KEY)[0]);
+}
+
+#[test]
+fn chain_enc_with_transform_test() {
+    let keys = keygen_128(*AES_128_KEY);
+    let block = AES_128_VECTORS[0].0;
+
+    for n in 1..=keys.len() {
+        assert_eq!(
+            block.chain_enc_with_transform(&keys[..n], |x| x),
+            block.chain_enc(&keys[..n])
+        );
+    }
+
+    let rotate = |x: AesBlock| AesBlock::from(u128::from(x).rotate_left(8));
+    assert_eq!(
+        block.chain_enc_with_transform(&keys[..3], rotate),
+        rotate((block ^ keys[0]).enc(keys[1])).enc(keys[2])
+    );
+}
+
+#[test]
+fn byte_shift_test() {
+    let arr = <[u8; 16]>::from_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+    let value = u128::from_be_bytes(arr);
+    let block = AesBlock::from(arr);
+
+    assert_eq!(
+        <[u8; 16]>::from(block.shr_bytes::<3>()),
+        (value >> 24).to_be_bytes()
+    );
+    assert_eq!(
+        <[u8; 16]>::from(block.shl_bytes::<3>()),
+        (value << 24).to_be_bytes()
+    );
+
+    assert_eq!(block.shl_bytes::<0>(), block);
+    assert_eq!(block.shr_bytes::<0>(), block);
+    assert_eq!(u128::from(block.shl_bytes::<1>()), value << 8);
+    assert_eq!(u128::from(block.shr_bytes::<15>()), value >> 120);
+    assert_eq!(block.shl_bytes::<16>(), AesBlock::zero());
+    assert_eq!(block.shr_bytes::<16>(), AesBlock::zero());
+    assert_eq!(block.shr_bytes::<100>(), AesBlock::zero());
+
+    #[allow(deprecated)]
+    {
+        assert_eq!(block.shl::<3>(), block.shl_bytes::<3>());
+        assert_eq!(block.shr::<3>(), block.shr_bytes::<3>());
+    }
+}
+
+#[test]
+fn bit_shift_test() {
+    let value = 0x80010203_04050607_08090a0b_0c0d0e0f_u128;
+    let block = AesBlock::from(value);
+
+    assert_eq!(u128::from(block.shl_bits::<1>()), value << 1);
+    assert_eq!(u128::from(block.shr_bits::<1>()), value >> 1);
+    assert_eq!(u128::from(block.shl_bits::<13>()), value << 13);
+    assert_eq!(u128::from(block.shr_bits::<127>()), 1);
+    assert_eq!(block.shl_bits::<0>(), block);
+    assert_eq!(block.shr_bits::<0>(), block);
+    assert_eq!(block.shl_bits::<128>(), AesBlock::zero());
+    assert_eq!(block.shr_bits::<1000>(), AesBlock::zero());
+
+    // bit shifts by whole bytes are byte shifts
+    assert_eq!(block.shl_bits::<24>(), block.shl_bytes::<3>());
+    assert_eq!(block.shr_bits::<24>(), block.shr_bytes::<3>());
+}
+
+#[test]
+fn derive_nonce_test() {
+    let key = Aes128Enc::from(*AES_128_KEY);
+
+    assert_eq!(
+        aead::derive_nonce(&key, b"message 1"),
+        aead::derive_nonce(&key, b"message 1")
+    );
+
+    let contexts: [&[u8]; 6] = [
+        b"",
+        b"\0",
+        b"message 1",
+        b"message 2",
+        b"a context that is longer than one block",
+        b"a context that is longer than one block\0",
+    ];
+    let nonces = contexts.map(|context| aead::derive_nonce(&key, context));
+    for i in 0..nonces.len() {
+        for j in (i + 1)..nonces.len() {
+            assert_ne!(nonces[i], nonces[j]);
+        }
+    }
+
+    let other_key = Aes128Enc::from([0; 16]);
+    assert_ne!(
+        aead::derive_nonce(&key, b"message 1"),
+        aead::derive_nonce(&other_key, b"message 1")
+    );
+}
+
+#[test]
+fn bitor_test() {
+    assert_eq!(
+        AesBlock::from([0xf0; 16]) | AesBlock::from([0x0f; 16]),
+        AesBlock::from([0xff; 16])
+    );
+
+    let a = AES_128_VECTORS[0].0;
+    assert_eq!(a | AesBlock::zero(), a);
+
+    let mut b = AesBlock::zero();
+    b |= a;
+    assert_eq!(b, a);
+}
+
+#[test]
+fn rotate_lanes_test() {
+    let lanes = [1, 2, 3, 4].map(AesBlock::from);
+    let (a, b, c, d) = (lanes[0], lanes[1], lanes[2], lanes[3]);
+    let block = AesBlockX4::from((a, b, c, d));
+
+    let rotated = block.rotate_lanes_left();
+    assert_eq!(<(_, _, _, _)>::from(rotated), (b, c, d, a));
+    assert_eq!(
+        <(_, _, _, _)>::from(rotated.rotate_lanes_left()),
+        (c, d, a, b)
+    );
+    assert_eq!(
+        rotated
+            .rotate_lanes_left()
+            .rotate_lanes_left()
+            .rotate_lanes_left(),
+        block
+    );
+
+    // lanes are moved whole, whatever their contents
+    let block = AesBlockX4::from(core::array::from_fn::<u8, 64, _>(|i| i as u8));
+    let bytes = <[u8; 64]>::from(block.rotate_lanes_left());
+    assert!((0..64).all(|i| bytes[i] == ((i + 16) % 64) as u8));
+}
+
+#[test]
+fn select_lanes_test() {
+    let a: [AesBlock; 4] = core::array::from_fn(|i| AES_128_VECTORS[i].0);
+    let b: [AesBlock; 4] = core::array::from_fn(|i| AES_128_VECTORS[i].1);
+    let wide_a = AesBlockX4::from((a[0], a[1], a[2], a[3]));
+    let wide_b = AesBlockX4::from((b[0], b[1], b[2], b[3]));
+
+    for mask in 0..16_u8 {
+        let pick = |i: usize| if mask & (1 << i) != 0 { b[i] } else { a[i] };
+        let expected = AesBlockX4::from((pick(0), pick(1), pick(2), pick(3)));
+
+        assert_eq!(AesBlockX4::select_lanes(mask, wide_a, wide_b), expected);
+        assert_eq!(
+            AesBlockX4::select_lanes(mask | 0xf0, wide_a, wide_b),
+            expected
+        );
+    }
+}
+
+#[test]
+fn block_buffer_test() {
+    use buffer::BlockBuffer;
+
+    let data: [u8; 100] = core::array::from_fn(|i| i as u8);
+
+    for chunk_size in [1, 3, 7, 15, 16, 17, 33, 100] {
+        let mut buffer = BlockBuffer::new();
+        let mut blocks = [AesBlock::zero(); 7];
+        let mut count = 0;
+
+        for chunk in data.chunks(chunk_size) {
+            buffer.input_block(chunk, |block| {
+                blocks[count] = block;
+                count += 1;
+            });
+            assert!(buffer.len() < 16);
+        }
+
+        assert_eq!(count, 6);
+        for (i, block) in blocks[..6].iter().enumerate() {
+            assert_eq!(*block, AesBlock::try_from(&data[16 * i..]).unwrap());
+        }
+        assert_eq!(buffer.len(), 4);
+
+        let mut last = [0; 16];
+        last[..4].copy_from_slice(&data[96..]);
+        assert_eq!(buffer.pad_and_finalize(), Some(AesBlock::from(last)));
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.pad_and_finalize(), None);
+    }
+
+    let mut buffer = BlockBuffer::new();
+    buffer.input_block(&[], |_| unreachable!());
+    assert_eq!(buffer.pad_and_finalize(), None);
+}
+
+#[test]
+fn reverse_bits_per_byte_test() {
+    for value in [
+        0_u128,
+        u128::MAX,
+        0x01234567_89abcdef_fedcba98_76543210,
+        0x80402010_08040201_0f0f0f0f_f0f0f0f0,
+    ] {
+        let bytes = value.to_be_bytes();
+        let block = AesBlock::from(bytes).reverse_bits_per_byte();
+        assert_eq!(block, AesBlock::from(bytes.map(u8::reverse_bits)));
+        assert_eq!(block.reverse_bits_per_byte(), AesBlock::from(bytes));
+    }
+}
+
+#[test]
+fn ctr128_test() {
+    use ctr::{CounterExhausted, CounterWidth, Ctr128};
+
+    // NIST SP 800-38A, F.5.1
+    let iv = <[u8; 16]>::from_hex("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+    let plaintext = <[u8; 64]>::from_hex(
+        "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+         30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710",
+    )
+    .unwrap();
+    let ciphertext = <[u8; 64]>::from_hex(
+        "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff\
+         5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee",
+    )
+    .unwrap();
+    let cipher = Aes128Enc::from(*AES_128_KEY);
+
+    let mut data = plaintext;
+    Ctr128::new(cipher.clone(), iv)
+        .apply_keystream(&mut data)
+        .unwrap();
+    assert_eq!(data, ciphertext);
+
+    // streaming in odd chunks (including empty ones) matches the single-shot result
+    let mut long = [0_u8; 150];
+    Ctr128::new(cipher.clone(), iv)
+        .apply_keystream(&mut long)
+        .unwrap();
+    for chunk_size in [1, 5, 16, 17, 63, 64, 65, 150] {
+        let mut streamed = [0_u8; 150];
+        let mut ctr = Ctr128::new(cipher.clone(), iv);
+        for chunk in streamed.chunks_mut(chunk_size) {
+            ctr.apply_keystream(chunk).unwrap();
+            ctr.apply_keystream(&mut []).unwrap();
+        }
+        assert_eq!(streamed, long);
+    }
+
+    // the counter refuses to wrap around, and a refused call leaves the data unchanged
+    let mut last = [0_u8; 48];
+    let mut ctr = Ctr128::new(cipher.clone(), [0xff; 16]);
+    assert_eq!(ctr.apply_keystream(&mut last), Err(CounterExhausted));
+    assert_eq!(last, [0; 48]);
+    ctr.apply_keystream(&mut last[..10]).unwrap();
+    ctr.apply_keystream(&mut last[10..16]).unwrap();
+    assert_eq!(
+        AesBlock::try_from(&last[..16]).unwrap(),
+        cipher.encrypt_block(AesBlock::from([0xff; 16]))
+    );
+    assert_eq!(ctr.apply_keystream(&mut []), Ok(()));
+    assert_eq!(
+        ctr.apply_keystream(&mut last[16..17]),
+        Err(CounterExhausted)
+    );
+
+    // a narrower counter is exhausted when its own bits wrap around
+    let mut iv = [0x42; 16];
+    iv[12..].copy_from_slice(&[0xff, 0xff, 0xff, 0xfe]);
+    let mut ctr = Ctr128::with_counter_width(cipher.clone(), iv, CounterWidth::Bits32);
+    assert_eq!(ctr.apply_keystream(&mut [0; 33]), Err(CounterExhausted));
+    assert_eq!(ctr.apply_keystream(&mut [0; 32]), Ok(()));
+    assert_eq!(ctr.apply_keystream(&mut [0; 1]), Err(CounterExhausted));
+
+    let error: &dyn core::error::Error = &CounterExhausted;
+    assert_eq!(std::format!("{error}"), "the counter space is exhausted");
+}
+
+#[test]
+fn wide_schedule_ctr_test() {
+    use ctr::{Aes128Ctr, Aes192Ctr, Aes256Ctr};
+
+    // the carry out of the low 64 bits happens inside the four-block loop
+    const IV: u128 = (1 << 64) - 3;
+
+    fn naive_ctr<const KEY_LEN: usize>(cipher: &impl AesEncrypt<KEY_LEN>, data: &mut [u8]) {
+        let mut counter = AesBlock::from(IV);
+        for chunk in data.chunks_mut(16) {
+            let keystream: [u8; 16] = cipher.encrypt_block(counter).into();
+            for (x, k) in chunk.iter_mut().zip(keystream) {
+                *x ^= k;
+            }
+            counter = counter.wrapping_add_be(AesBlock::from(1_u128));
+        }
+    }
+
+    fn check<const KEY_LEN: usize, E: AesEncrypt<KEY_LEN> + Clone>(
+        cipher: E,
+        mut ctr: impl FnMut(E) -> ctr::Ctr128<E, KEY_LEN>,
+    ) {
+        let blocks = AesBlockX4::from([1, 2, 3, u128::MAX]);
+        let schedule = cipher.wide_schedule();
+        assert_eq!(
+            schedule.encrypt_4_blocks(blocks),
+            cipher.encrypt_4_blocks(blocks)
+        );
+        let blocks = AesBlockX8::from((blocks, blocks ^ blocks.rotate_lanes_left()));
+        assert_eq!(
+            schedule.encrypt_8_blocks(blocks),
+            cipher.encrypt_8_blocks(blocks)
+        );
+
+        let mut data = [0_u8; 200];
+        for (i, x) in data.iter_mut().enumerate() {
+            *x = i as u8;
+        }
+        let mut expected = data;
+        naive_ctr(&cipher, &mut expected);
+        ctr(cipher).apply_keystream(&mut data).unwrap();
+        assert_eq!(data, expected);
+    }
+
+    let iv = IV.to_be_bytes();
+    check(Aes128Enc::from(*AES_128_KEY), |c| Aes128Ctr::new(c, iv));
+    check(Aes192Enc::from(*AES_192_KEY), |c| Aes192Ctr::new(c, iv));
+    check(Aes256Enc::from(*AES_256_KEY), |c| Aes256Ctr::new(c, iv));
+}
+
+#[test]
+fn incr_be_checked_test() {
+    use ctr::CounterWidth;
+
+    assert_eq!(
+        AesBlock::from(u128::MAX).incr_be_checked(),
+        (AesBlock::zero(), true)
+    );
+    assert_eq!(
+        AesBlock::from(u128::MAX - 1).incr_be_checked(),
+        (AesBlock::from(u128::MAX), false)
+    );
+    assert_eq!(
+        AesBlock::from(u128::from(u64::MAX)).incr_be_checked(),
+        (AesBlock::from(1 << 64), false)
+    );
+
+    let max = AesBlock::from([0xab; 16]) | AesBlock::from(u128::from(u32::MAX));
+    assert!(CounterWidth::Bits32.increment_checked(max).1);
+    assert!(!CounterWidth::Bits64.increment_checked(max).1);
+}
+
+#[test]
+fn cbc_test() {
+    use cbc::{cbc_decrypt, cbc_encrypt, cbc_process};
+
+    // NIST SP 800-38A, F.2
+    let iv = AesBlock::from(0x00010203_04050607_08090a0b_0c0d0e0f);
+    let plaintext = [
+        AesBlock::from(0x6bc1bee2_2e409f96_e93d7e11_7393172a),
+        AesBlock::from(0xae2d8a57_1e03ac9c_9eb76fac_45af8e51),
+        AesBlock::from(0x30c81c46_a35ce411_e5fbc119_1a0a52ef),
+        AesBlock::from(0xf69f2445_df4f9b17_ad2b417b_e66c3710),
+    ];
+
+    fn check<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(
+        enc: &E,
+        iv: AesBlock,
+        plaintext: &[AesBlock; 4],
+        ciphertext: &[AesBlock; 4],
+    ) {
+        let dec = enc.decrypter();
+
+        assert_eq!(cbc_process(enc, iv, plaintext), ciphertext[3]);
+        let next_iv = cbc_process(enc, iv, &plaintext[..3]);
+        assert_eq!(next_iv, ciphertext[2]);
+        assert_eq!(cbc_process(enc, next_iv, &plaintext[3..]), ciphertext[3]);
+        assert_eq!(cbc_process(enc, iv, &[]), iv);
+
+        let mut data = *plaintext;
+        assert_eq!(cbc_encrypt(enc, iv, &mut data), ciphertext[3]);
+        assert_eq!(&data, ciphertext);
+        assert_eq!(cbc_decrypt(&dec, iv, &mut data), ciphertext[3]);
+        assert_eq!(&data, plaintext);
+
+        // chaining over a split message, with a tail that is not a multiple of four blocks
+        let mut data = [
+            plaintext[0],
+            plaintext[1],
+            plaintext[2],
+            plaintext[3],
+            plaintext[0],
+        ];
+        let next_iv = cbc_encrypt(enc, iv, &mut data[..1]);
+        let last = cbc_encrypt(enc, next_iv, &mut data[1..]);
+        assert_eq!(&data[..4], ciphertext);
+        assert_eq!(last, data[4]);
+        assert_eq!(cbc_decrypt(&dec, iv, &mut data), last);
+        assert_eq!(&data[..4], plaintext);
+        assert_eq!(data[4], plaintext[0]);
+
+        assert_eq!(cbc_encrypt(enc, iv, &mut []), iv);
+        assert_eq!(cbc_decrypt(&dec, iv, &mut []), iv);
+    }
+
+    check(
+        &Aes128Enc::from(*AES_128_KEY),
+        iv,
+        &plaintext,
+        &[
+            AesBlock::from(0x7649abac_8119b246_cee98e9b_12e9197d),
+            AesBlock::from(0x5086cb9b_507219ee_95db113a_917678b2),
+            AesBlock::from(0x73bed6b8_e3c1743b_7116e69e_22229516),
+            AesBlock::from(0x3ff1caa1_681fac09_120eca30_7586e1a7),
+        ],
+    );
+    check(
+        &Aes192Enc::from(*AES_192_KEY),
+        iv,
+        &plaintext,
+        &[
+            AesBlock::from(0x4f021db2_43bc633d_7178183a_9fa071e8),
+            AesBlock::from(0xb4d9ada9_ad7dedf4_e5e73876_3f69145a),
+            AesBlock::from(0x571b2420_12fb7ae0_7fa9baac_3df102e0),
+            AesBlock::from(0x08b0e279_88598881_d920a9e6_4f5615cd),
+        ],
+    );
+    check(
+        &Aes256Enc::from(*AES_256_KEY),
+        iv,
+        &plaintext,
+        &[
+            AesBlock::from(0xf58c4c04_d6e5f1ba_779eabfb_5f7bfbd6),
+            AesBlock::from(0x9cfc4e96_7edb808d_679f777b_c6702c7d),
+            AesBlock::from(0x39f23369_a9d9bacf_a530e263_04231461),
+            AesBlock::from(0xb2eb05e2_c39be9fc_da6c1907_8c6a9d1b),
+        ],
+    );
+
+    // raw CBC-MAC (zero IV) of the same message, from Python's `cryptography`
+    let enc = Aes128Enc::from(*AES_128_KEY);
+    assert_eq!(
+        cbc_process(&enc, AesBlock::zero(), &plaintext),
+        AesBlock::from(0xa7356e12_07bb4066_39e5e5ce_b9a9ed93)
+    );
+    assert_eq!(
+        cbc_process(&enc, AesBlock::zero(), &plaintext[..2]),
+        AesBlock::from(0xb148c17f_309ee692_287ae57c_f12add49)
+    );
+}
+
+#[test]
+fn encrypt_4_blocks_xor_test() {
+    let cipher = Aes128Enc::from(*AES_128_KEY);
+    let iv = AesBlock::from(0x01234567_89abcdef_01234567_fffffffe);
+
+    let mut counters = AesBlockX4::from_distinct(
+        iv,
+        iv.incr_be_32(),
+        iv.incr_be_32().incr_be_32(),
+        iv.incr_be_32().incr_be_32().incr_be_32(),
+    );
+    let mut data: [u8; 128] = core::array::from_fn(|i| i as u8);
+    let original = data;
+
+    for chunk in data.chunks_exact_mut(64) {
+        cipher.encrypt_4_blocks_xor(&mut counters, chunk.try_into().unwrap());
+    }
+
+    let mut counter = iv;
+    for (i, block) in data.chunks_exact(16).enumerate() {
+        let keystream = cipher.encrypt_block(counter);
+        let expected = AesBlock::try_from(&original[16 * i..]).unwrap() ^ keystream;
+        assert_eq!(AesBlock::try_from(block).unwrap(), expected);
+        counter = counter.incr_be_32();
+    }
+    assert_eq!(<(_, _, _, _)>::from(counters).0, counter);
+}
+
+/// Reference GF(2^8) multiplication, with the AES polynomial
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut result = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            result ^= a;
+        }
+        a = (a << 1) ^ if a & 0x80 != 0 { 0x1b } else { 0 };
+        b >>= 1;
+    }
+    result
+}
+
+#[test]
+fn gf_inv_bytes_test() {
+    let mut table = [0_u8; 256];
+    for a in 1..=255 {
+        table[a as usize] = (1..=255).find(|&b| gf_mul(a, b) == 1).unwrap();
+    }
+
+    for chunk in 0..16 {
+        let bytes: [u8; 16] = core::array::from_fn(|i| (16 * chunk + i) as u8);
+        let inverse = AesBlock::from(bytes).gf_inv_bytes();
+        assert_eq!(inverse, AesBlock::from(bytes.map(|x| table[x as usize])));
+    }
+}
+
+#[test]
+fn sub_bytes_test() {
+    // the first row of the S-box table of FIPS-197
+    let block = AesBlock::from(0x000102030405060708090a0b0c0d0e0f);
+    assert_eq!(
+        block.sub_bytes(),
+        AesBlock::from(0x637c777bf26b6fc53001672bfed7ab76)
+    );
+    assert_eq!(block.sub_bytes().inv_sub_bytes(), block);
+
+    // FIPS-197 Appendix B, the SubBytes step of round 1
+    let state = AesBlock::from(0x193de3bea0f4e22b9ac68d2ae9f84808);
+    let substituted = AesBlock::from(0xd42711aee0bf98f1b8b45de51e415230);
+    assert_eq!(state.sub_bytes(), substituted);
+    assert_eq!(substituted.inv_sub_bytes(), state);
+
+    // the S-box is the affine transform of the inverse, on every byte value
+    for chunk in 0..16 {
+        let bytes: [u8; 16] = core::array::from_fn(|i| (16 * chunk + i) as u8);
+        let block = AesBlock::from(bytes);
+        let inverse: [u8; 16] = block.gf_inv_bytes().into();
+        let expected = inverse.map(|b| {
+            b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63
+        });
+        assert_eq!(block.sub_bytes(), AesBlock::from(expected));
+        assert_eq!(block.sub_bytes().inv_sub_bytes(), block);
+        assert_eq!(block.inv_sub_bytes().sub_bytes(), block);
+    }
+}
+
+#[test]
+fn xtime_test() {
+    // the powers of 2: the eighth one wraps around through the AES polynomial
+    let mut block = AesBlock::from([1; 16]);
+    for expected in [2, 4, 8, 0x10, 0x20, 0x40, 0x80, 0x1b] {
+        block = block.xtime();
+        assert_eq!(block, AesBlock::from([expected; 16]));
+    }
+    // 2 has order 51, so it cycles back to 1 only there
+    for _ in 9..51 {
+        block = block.xtime();
+        assert_ne!(block, AesBlock::from([1; 16]));
+    }
+    assert_eq!(block.xtime(), AesBlock::from([1; 16]));
+
+    fn check<const C: u8>() {
+        for chunk in 0..16 {
+            let bytes: [u8; 16] = core::array::from_fn(|i| (16 * chunk + i) as u8);
+            let block = AesBlock::from(bytes);
+            assert_eq!(
+                block.gf_mul_const::<C>(),
+                AesBlock::from(bytes.map(|x| gf_mul(x, C)))
+            );
+            if C == 2 {
+                assert_eq!(block.xtime(), block.gf_mul_const::<C>());
+            }
+        }
+    }
+    check::<0>();
+    check::<1>();
+    check::<2>();
+    check::<3>();
+    check::<9>();
+    check::<0x0b>();
+    check::<0x0d>();
+    check::<0x0e>();
+    check::<0xff>();
+}
+
+#[test]
+fn nibbles_test() {
+    for chunk in 0..16 {
+        let bytes: [u8; 16] = core::array::from_fn(|i| (16 * chunk + i) as u8);
+        let block = AesBlock::from(bytes);
+        let (lo, hi) = (block.nibbles_lo(), block.nibbles_hi());
+        assert_eq!(lo, AesBlock::from(bytes.map(|x| x & 0x0f)));
+        assert_eq!(hi, AesBlock::from(bytes.map(|x| x >> 4)));
+        assert_eq!(AesBlock::combine_nibbles(lo, hi), block);
+        assert_eq!(
+            AesBlock::combine_nibbles(hi, lo),
+            AesBlock::from(bytes.map(|x| x.rotate_left(4)))
+        );
+
+        // the high nibbles of the inputs are ignored
+        assert_eq!(
+            AesBlock::combine_nibbles(block, block),
+            AesBlock::from(bytes.map(|x| x & 0x0f).map(|x| x | x << 4))
+        );
+    }
+}
+
+#[test]
+fn bulk_encrypt_test() {
+    let enc = Aes192Enc::from(*AES_192_KEY);
+    let dec = enc.decrypter();
+
+    for n in 0..=9 {
+        let plaintext: [AesBlock; 9] = core::array::from_fn(|i| AesBlock::from(i as u128 * 0x1234));
+        let mut blocks = plaintext;
+
+        enc.encrypt_blocks(&mut blocks[..n]);
+        for i in 0..9 {
+            let expected = if i < n {
+                enc.encrypt_block(plaintext[i])
+            } else {
+                plaintext[i]
+            };
+            assert_eq!(blocks[i], expected);
+        }
+
+        dec.decrypt_blocks(&mut blocks[..n]);
+        assert_eq!(blocks, plaintext);
+    }
+}
+
+#[test]
+fn encrypt_par_test() {
+    let enc = Aes256Enc::from(*AES_256_KEY);
+    let plaintext: [AesBlock; 21] = core::array::from_fn(|i| AesBlock::from(i as u128 * 0x1234));
+
+    // every combination of the 8-, 4-, 2- and 1-block paths
+    for n in 0..=21 {
+        let mut out = [AesBlock::zero(); 21];
+        enc.encrypt_par(&plaintext[..n], &mut out[..n]);
+        for i in 0..21 {
+            let expected = if i < n {
+                enc.encrypt_block(plaintext[i])
+            } else {
+                AesBlock::zero()
+            };
+            assert_eq!(out[i], expected);
+        }
+
+        let mut blocks = plaintext;
+        enc.encrypt_blocks(&mut blocks[..n]);
+        assert_eq!(out[..n], blocks[..n]);
+    }
+}
+
+#[test]
+fn clmul_test() {
+    // the GHASH multiplication of NIST SP 800-38D, Algorithm 1
+    fn gf128_mul_reference(x: u128, y: u128) -> u128 {
+        let (mut z, mut v) = (0, y);
+        for i in (0..128).rev() {
+            if (x >> i) & 1 != 0 {
+                z ^= v;
+            }
+            v = (v >> 1) ^ if v & 1 != 0 { 0xe1 << 120 } else { 0 };
+        }
+        z
+    }
+
+    let values = [
+        0_u128,
+        1,
+        u128::MAX,
+        0x66e94bd4_ef8a2c3b_884cfa59_ca342b2e,
+        0x0388dace_60b6a392_f328c2b9_71b2fe78,
+        0x80000000_00000000_00000000_00000001,
+    ];
+    for a in values {
+        for b in values {
+            let (hi, lo) = AesBlock::from(a).clmul(AesBlock::from(b));
+            assert_eq!((u128::from(hi), u128::from(lo)), clmul_soft(a, b));
+
+            let product = AesBlock::from(a).gf128_mul(AesBlock::from(b));
+            assert_eq!(u128::from(product), gf128_mul_reference(a, b));
+        }
+    }
+
+    // squaring is linear over GF(2), so it spreads the bits out
+    let (hi, lo) = clmul_soft(u128::MAX, u128::MAX);
+    assert_eq!(hi, 0x55555555_55555555_55555555_55555555);
+    assert_eq!(lo, 0x55555555_55555555_55555555_55555555);
+
+    // GHASH of NIST GCM test case 2
+    let h = AesBlock::from(0x66e94bd4_ef8a2c3b_884cfa59_ca342b2e);
+    let c = AesBlock::from(0x0388dace_60b6a392_f328c2b9_71b2fe78);
+    let ghash = (c.gf128_mul(h) ^ AesBlock::from(0x80)).gf128_mul(h);
+    assert_eq!(ghash, AesBlock::from(0xf38cbb1a_d69223dc_c3457ae5_b6b0f885));
+}
+
+#[test]
+fn wide_u128_conversion_test() {
+    let lanes = [
+        0x000102030405060708090a0b0c0d0e0f,
+        0x101112131415161718191a1b1c1d1e1f,
+        0x202122232425262728292a2b2c2d2e2f,
+        0x303132333435363738393a3b3c3d3e3f,
+    ];
+    let mut bytes = [0; 64];
+    for (i, byte) in (0..).zip(&mut bytes) {
+        *byte = i;
+    }
+
+    let x2 = AesBlockX2::from([lanes[0], lanes[1]]);
+    assert_eq!(x2, AesBlockX2::try_from(&bytes[..32]).unwrap());
+    assert_eq!(x2, AesBlockX2::from((lanes[0], lanes[1])));
+    assert_eq!(<[u128; 2]>::from(x2), [lanes[0], lanes[1]]);
+
+    let x4 = AesBlockX4::from(lanes);
+    assert_eq!(x4, AesBlockX4::from(bytes));
+    assert_eq!(<[u128; 4]>::from(x4), lanes);
+    assert_eq!(x4.to_gcm_order(), lanes.map(AesBlock::from));
+}
+
+#[test]
+fn x2_counter_block_test() {
+    for base in [
+        0x01234567_89abcdef_01234567_00000000_u128,
+        0x01234567_89abcdef_01234567_000000ff,
+        0x01234567_89abcdef_01234567_fffffffe,
+        0x01234567_89abcdef_01234567_ffffffff,
+        u128::MAX,
+    ] {
+        let base = AesBlock::from(base);
+        let ctr = AesBlockX2::counter_block(base);
+        assert_eq!(<(_, _)>::from(ctr), (base, base.incr_be_32()));
+    }
+}
+
+#[test]
+fn as_blocks_mut_test() {
+    #[repr(align(16))]
+    struct Buffer([u8; 96]);
+
+    let mut buffer = Buffer([0; 96]);
+    for (i, byte) in (0..).zip(&mut buffer.0) {
+        *byte = i;
+    }
+
+    for offset in [0, 1, 8, 16] {
+        for len in [0, 15, 16, 64, 70] {
+            let data = &mut buffer.0[offset..offset + len];
+            let expected = data.to_vec();
+            let (blocks, rest) = as_blocks_mut(data);
+
+            if offset % 16 == 0 && BLOCKS_ARE_BYTES {
+                assert_eq!(blocks.len(), len / 16);
+                assert_eq!(rest, &expected[len / 16 * 16..]);
+                for (block, chunk) in blocks.iter_mut().zip(expected.chunks_exact(16)) {
+                    assert_eq!(block.to_bytes(), chunk);
+                    *block = !*block;
+                }
+            } else {
+                assert!(blocks.is_empty());
+                assert_eq!(rest, &expected[..]);
+            }
+
+            // changes through the block view are visible in the bytes
+            let data = &mut buffer.0[offset..offset + len];
+            let whole = if offset % 16 == 0 && BLOCKS_ARE_BYTES {
+                len / 16 * 16
+            } else {
+                0
+            };
+            assert!(data[..whole].iter().zip(&expected).all(|(&x, &y)| x == !y));
+            assert_eq!(&data[whole..], &expected[whole..]);
+            for (byte, &original) in data.iter_mut().zip(&expected) {
+                *byte = original;
+            }
+        }
+    }
+}
+
+#[test]
+fn gcm_test() {
+    use gcm::AesGcm;
+    use std::vec::Vec;
+
+    fn hex_vec(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn check(key: [u8; 16], nonce: &str, aad: &str, plaintext: &str, ciphertext: &str, tag: &str) {
+        let gcm = AesGcm::new(Aes128Enc::from(key));
+        let nonce = hex_vec(nonce);
+        let aad = hex_vec(aad);
+        let plaintext = hex_vec(plaintext);
+        let ciphertext = hex_vec(ciphertext);
+        let tag = <[u8; 16]>::from_hex(tag).unwrap();
+
+        let mut out = std::vec![0; plaintext.len()];
+        let mut computed = [0; 16];
+        gcm.encrypt(&nonce, &aad, &plaintext, &mut out, &mut computed);
+        assert_eq!(out, ciphertext);
+        assert_eq!(computed, tag);
+
+        assert_eq!(gcm.decrypt(&nonce, &aad, &mut out, &tag), Ok(()));
+        assert_eq!(out, plaintext);
+
+        for piece_len in [1, 5, 16, 17, 64] {
+            let mut out = ciphertext.clone();
+            let mut decryptor = gcm.decryptor(&nonce, &aad).unwrap();
+            for piece in out.chunks_mut(piece_len) {
+                decryptor.update(piece);
+            }
+            assert_eq!(decryptor.verify(&tag), Ok(()));
+            assert_eq!(out, plaintext);
+        }
+
+        let mut out = ciphertext.clone();
+        let mut bad_tag = tag;
+        bad_tag[15] ^= 1;
+        assert_eq!(
+            gcm.decrypt(&nonce, &aad, &mut out, &bad_tag),
+            Err(aead::AuthError)
+        );
+        assert!(out.iter().all(|&x| x == 0));
+    }
+
+    // the AES-128 test cases of the GCM specification
+    let key = <[u8; 16]>::from_hex("feffe9928665731c6d6a8f9467308308").unwrap();
+    let plaintext = "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+                     1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39";
+    let aad = "feedfacedeadbeeffeedfacedeadbeefabaddad2";
+
+    check(
+        [0; 16],
+        "000000000000000000000000",
+        "",
+        "",
+        "",
+        "58e2fccefa7e3061367f1d57a4e7455a",
+    );
+    check(
+        [0; 16],
+        "000000000000000000000000",
+        "",
+        "00000000000000000000000000000000",
+        "0388dace60b6a392f328c2b971b2fe78",
+        "ab6e47d42cec13bdf53a67b21257bddf",
+    );
+    check(
+        key,
+        "cafebabefacedbaddecaf888",
+        "",
+        "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+         1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b391aafd255",
+        "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+         21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091473f5985",
+        "4d5c2af327cd64a62cf35abd2ba6fab4",
+    );
+    check(
+        key,
+        "cafebabefacedbaddecaf888",
+        aad,
+        plaintext,
+        "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+         21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091",
+        "5bc94fbc3221a5db94fae95ae7121a47",
+    );
+    check(
+        key,
+        "cafebabefacedbad",
+        aad,
+        plaintext,
+        "61353b4c2806934a777ff51fa22a4755699b2a714fcdc6f83766e5f97b6c7423\
+         73806900e49f24b22b097544d4896b424989b5e1ebac0f07c23f4598",
+        "3612d2e79e3b0785561be14aaca2fccb",
+    );
+    check(
+        key,
+        "9313225df88406e555909c5aff5269aa6a7a9538534f7da1e4c303d2a318a728\
+         c3c0c95156809539fcf0e2429a6b525416aedbf5a0de6a57a637b39b",
+        aad,
+        plaintext,
+        "8ce24998625615b603a033aca13fb894be9112a5c3a211a8ba262a3cca7e2ca7\
+         01e4a9a4fba43c90ccdcb281d48c7c6fd62875d2aca417034c34aee5",
+        "619cc5aefffe0bfa462af43c1699d050",
+    );
+    // AAD only
+    check(
+        key,
+        "cafebabefacedbaddecaf888",
+        aad,
+        "",
+        "",
+        "346434fd51d5cd0c5887ec63e39b907a",
+    );
+
+    // a large message, decrypted in uneven pieces
+    let gcm = AesGcm::new(Aes128Enc::from(key));
+    let plaintext: Vec<u8> = (0..100_000_u32).map(|i| (i * 7 + i / 256) as u8).collect();
+    let mut ciphertext = std::vec![0; plaintext.len()];
+    let mut tag = [0; 16];
+    gcm.encrypt(
+        &[3; 12],
+        b"file header",
+        &plaintext,
+        &mut ciphertext,
+        &mut tag,
+    );
+
+    let decrypt_in_pieces = |data: &mut [u8], aad: &[u8], tag: &[u8; 16]| {
+        let mut decryptor = gcm.decryptor(&[3; 12], aad).unwrap();
+        let mut rest = data;
+        for piece_len in [1, 4095, 4097, 16, 31].iter().cycle() {
+            let (piece, tail) = rest.split_at_mut((*piece_len).min(rest.len()));
+            decryptor.update(piece);
+            rest = tail;
+            if rest.is_empty() {
+                break;
+            }
+        }
+        decryptor.verify(tag)
+    };
+
+    let mut data = ciphertext.clone();
+    assert_eq!(decrypt_in_pieces(&mut data, b"file header", &tag), Ok(()));
+    assert_eq!(data, plaintext);
+
+    let mut data = ciphertext.clone();
+    data[54_321] ^= 0x10;
+    assert_eq!(
+        decrypt_in_pieces(&mut data, b"file header", &tag),
+        Err(aead::AuthError)
+    );
+    let mut data = ciphertext.clone();
+    assert_eq!(
+        decrypt_in_pieces(&mut data, b"file headeR", &tag),
+        Err(aead::AuthError)
+    );
+    let mut data = ciphertext[..99_999].to_vec();
+    assert_eq!(
+        decrypt_in_pieces(&mut data, b"file header", &tag),
+        Err(aead::AuthError)
+    );
+
+    // an empty nonce is rejected when decrypting, as it can come from an attacker
+    let mut data = ciphertext[..64].to_vec();
+    assert_eq!(gcm.decrypt(&[], b"", &mut data, &tag), Err(aead::AuthError));
+    assert_eq!(data, [0; 64]);
+    assert!(gcm.decryptor(&[], b"").is_err());
+}
+
+#[test]
+fn write_to_slice_test() {
+    let block = AesBlock::from(0x00010203_04050607_08090a0b_0c0d0e0f);
+
+    let mut dst = [0xff; 20];
+    assert_eq!(block.write_to_slice(&mut dst), Ok(()));
+    assert_eq!(dst[..16], <[u8; 16]>::from(block));
+    assert_eq!(dst[16..], [0xff; 4]);
+
+    let mut dst = [0xff; 16];
+    assert_eq!(block.write_to_slice(&mut dst), Ok(()));
+    assert_eq!(dst, <[u8; 16]>::from(block));
+
+    let mut dst = [0xff; 15];
+    assert_eq!(block.write_to_slice(&mut dst), Err(16));
+    assert_eq!(dst, [0xff; 15]);
+    assert_eq!(block.write_to_slice(&mut []), Err(16));
+}
+
+#[test]
+fn xts_test() {
+    use xts::AesXts;
+
+    // IEEE 1619, vector 2
+    let xts = AesXts::new(Aes128Enc::from([0x11; 16]), Aes128Enc::from([0x22; 16]));
+    let mut data = [0x44; 32];
+    xts.encrypt_sector(0x33_3333_3333, &mut data).unwrap();
+    assert_eq!(
+        data,
+        <[u8; 32]>::from_hex("c454185e6a16936e39334038acef838bfb186fff7480adc4289382ecd6d394f0")
+            .unwrap()
+    );
+    xts.decrypt_sector(0x33_3333_3333, &mut data).unwrap();
+    assert_eq!(data, [0x44; 32]);
+
+    // ciphertext stealing, checked against OpenSSL
+    let xts = AesXts::new(
+        Aes128Enc::from(<[u8; 16]>::from_hex("fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0").unwrap()),
+        Aes128Enc::from(<[u8; 16]>::from_hex("bfbebdbcbbbab9b8b7b6b5b4b3b2b1b0").unwrap()),
+    );
+    let plaintext: [u8; 100] = core::array::from_fn(|i| i as u8);
+    let mut data = plaintext;
+    xts.encrypt_sector(0x9a_7856_3412, &mut data[..17]).unwrap();
+    assert_eq!(
+        data[..17],
+        <[u8; 17]>::from_hex("641610679dcbf92e505c41333fb06c2a95").unwrap()
+    );
+    xts.decrypt_sector(0x9a_7856_3412, &mut data[..17]).unwrap();
+    assert_eq!(data, plaintext);
+
+    xts.encrypt_sector(0x9a_7856_3412, &mut data).unwrap();
+    assert_eq!(
+        data,
+        <[u8; 100]>::from_hex(
+            "95c871f6522469cc737109594ab0feda383a90c3320b91b5ba5bc8bcf089a09e\
+             dd10a73b5638ee924f7abd223fc634cfe3de81cd3d1f9b719bb79a9f8d0b027b\
+             0d8926b138e276d588a5805726ba517272da93028d04e04d939ae59e8b700f66\
+             39e6ad02"
+        )
+        .unwrap()
+    );
+    xts.decrypt_sector(0x9a_7856_3412, &mut data).unwrap();
+    assert_eq!(data, plaintext);
+
+    for len in 16..=100 {
+        xts.encrypt_sector(5, &mut data[..len]).unwrap();
+        assert_ne!(data[..len], plaintext[..len]);
+        xts.decrypt_sector(5, &mut data[..len]).unwrap();
+        assert_eq!(data, plaintext);
+    }
+
+    assert_eq!(
+        xts.encrypt_sector(0, &mut data[..15]),
+        Err(InvalidLength { got: 15, need: 16 })
+    );
+    assert_eq!(
+        xts.decrypt_sector(0, &mut []),
+        Err(InvalidLength { got: 0, need: 16 })
+    );
+    assert_eq!(data, plaintext);
+}
+
+#[test]
+fn rotate_diagonal_test() {
+    let bytes: [u8; 16] = core::array::from_fn(|i| 0x10 * i as u8 + 1);
+    let block = AesBlock::from(bytes);
+
+    // the byte in row r and column c moves to row (r + c) % 4
+    let mut expected = [0; 16];
+    for c in 0..4 {
+        for r in 0..4 {
+            expected[(r + c) % 4 + 4 * c] = bytes[r + 4 * c];
+        }
+    }
+
+    let rotated = block.rotate_diagonal();
+    assert_eq!(rotated, AesBlock::from(expected));
+    assert_eq!(rotated.inv_rotate_diagonal(), block);
+    assert_eq!(block.inv_rotate_diagonal().rotate_diagonal(), block);
+}
+
+#[test]
+fn cmac_test() {
+    use cmac::Cmac;
+
+    // RFC 4493, section 4
+    let message = <[u8; 64]>::from_hex(
+        "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+         30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710",
+    )
+    .unwrap();
+    let cipher = Aes128Enc::from(*AES_128_KEY);
+
+    for (len, tag) in [
+        (0, "bb1d6929e95937287fa37d129b756746"),
+        (16, "070a16b46b4d4144f79bdd9dd04a287c"),
+        (40, "dfa66747de9ae63030ca32611497c827"),
+        (64, "51f0bebf7e3b9d92fc49741779363cfe"),
+    ] {
+        let tag = <[u8; 16]>::from_hex(tag).unwrap();
+
+        for chunk_size in [1, 7, 16, 17, 64] {
+            let mut mac = Cmac::new(cipher.clone());
+            for chunk in message[..len].chunks(chunk_size) {
+                mac.update(chunk);
+                mac.update(&[]);
+            }
+            assert!(mac.verify(&tag));
+            assert_eq!(mac.finalize(), tag);
+        }
+
+        let mut mac = Cmac::new(cipher.clone());
+        mac.update(&message[..len]);
+        let mut bad_tag = tag;
+        bad_tag[0] ^= 1;
+        assert!(!mac.verify(&bad_tag));
+    }
+}
+
+#[test]
+fn native_width_test() {
+    let enc = Aes256Enc::from(*AES_256_KEY);
+    let plaintext: [AesBlock; 23] = core::array::from_fn(|i| AesBlock::from(!(i as u128)));
+    for n in 0..=23 {
+        let mut blocks = plaintext;
+        enc.encrypt_blocks(&mut blocks[..n]);
+        enc.decrypter().decrypt_blocks(&mut blocks[..n]);
+        assert_eq!(blocks, plaintext);
+
+        enc.encrypt_blocks(&mut blocks[..n]);
+        for i in 0..n {
+            assert_eq!(blocks[i], enc.encrypt_block(plaintext[i]));
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn zeroizing_test() {
+    let secret = Zeroizing::new(<[u8; 16]>::from(AesBlock::from(0x1234)));
+    assert_eq!(*secret, 0x1234_u128.to_be_bytes());
+    assert_eq!(AesBlock::from(*secret), AesBlock::from(0x1234));
+
+    let enc = Aes128Enc::from(*AES_128_KEY);
+    let dec = enc.decrypter();
+    let round_keys: Zeroizing<[AesBlock; 11]> = enc.round_keys_zeroizing();
+    assert_eq!(round_keys[..], *enc.round_keys());
+    assert_eq!(
+        *enc.equiv_inv_round_keys_zeroizing(),
+        enc.equiv_inv_round_keys()
+    );
+    assert_eq!(dec.round_keys_zeroizing()[..], *dec.round_keys());
+    assert_eq!(
+        Aes256Enc::from(*AES_256_KEY).round_keys_zeroizing().len(),
+        15
+    );
+}
+
+#[test]
+fn x8_test() {
+    let blocks: [AesBlock; 8] = core::array::from_fn(|i| AesBlock::from(0x0101 * i as u128));
+    let [a, b, c, d, e, f, g, h] = blocks;
+    let x8 = AesBlockX8::from((a, b, c, d, e, f, g, h));
+
+    assert_eq!(
+        <(_, _, _, _, _, _, _, _)>::from(x8),
+        (a, b, c, d, e, f, g, h)
+    );
+    assert_eq!(
+        <(_, _)>::from(x8),
+        (
+            AesBlockX4::from((a, b, c, d)),
+            AesBlockX4::from((e, f, g, h))
+        )
+    );
+
+    let mut bytes = [0; 128];
+    x8.store_to(&mut bytes);
+    assert_eq!(AesBlockX8::new(bytes), x8);
+    assert_eq!(AesBlockX8::try_from(&bytes[..]), Ok(x8));
+
+    let rk = AesBlock::from(0x0f0e0d0c_0b0a0908_07060504_03020100);
+    let (e0, .., e7) = <(_, _, _, _, _, _, _, _)>::from(x8.enc(rk.into()));
+    assert_eq!((e0, e7), (a.enc(rk), h.enc(rk)));
+    let (d0, .., d7) = <(_, _, _, _, _, _, _, _)>::from(x8.dec_last(rk.into()));
+    assert_eq!((d0, d7), (a.dec_last(rk), h.dec_last(rk)));
+
+    let enc = Aes128Enc::from(*AES_128_KEY);
+    let ciphertext = enc.encrypt_8_blocks(x8);
+    let (c0, c1, c2, c3, c4, c5, c6, c7) = ciphertext.into();
+    assert_eq!(
+        [c0, c1, c2, c3, c4, c5, c6, c7],
+        blocks.map(|block| enc.encrypt_block(block))
+    );
+    assert_eq!(enc.decrypter().decrypt_8_blocks(ciphertext), x8);
+}
+
+#[test]
+#[allow(clippy::cast_possible_truncation)]
+fn openssl_schedule_test() {
+    fn to_words(round_keys: &[AesBlock]) -> std::vec::Vec<u32> {
+        round_keys
+            .iter()
+            .flat_map(|&rk| {
+                let value = u128::from(rk);
+                [96, 64, 32, 0].map(|shift| (value >> shift) as u32)
+            })
+            .collect()
+    }
+
+    // the FIPS-197 schedule of the 128-bit key, as OpenSSL stores it
+    let words = [
+        0x2b7e1516,
+        0x28aed2a6,
+        0xabf71588,
+        0x09cf4f3c,
+        0xa0fafe17,
+        0x88542cb1,
+        0x23a33939,
+        0x2a6c7605,
+        0xf2c295f2,
+        0x7a96b943,
+        0x5935807a,
+        0x7359f67f,
+        0x3d80477d,
+        0x4716fe3e,
+        0x1e237e44,
+        0x6d7a883b,
+        0xef44a541,
+        0xa8525b7f,
+        0xb671253b,
+        0xdb0bad00,
+        0xd4d1c6f8,
+        0x7c839d87,
+        0xcaf2b8bc,
+        0x11f915bc,
+        0x6d88a37a,
+        0x110b3efd,
+        0xdbf98641,
+        0xca0093fd,
+        0x4e54f70e,
+        0x5f5fc9f3,
+        0x84a64fb2,
+        0x4ea6dc4f,
+        0xead27321,
+        0xb58dbad2,
+        0x312bf560,
+        0x7f8d292f,
+        0xac7766f3,
+        0x19fadc21,
+        0x28d12941,
+        0x575c006e,
+        0xd014f9a8,
+        0xc9ee2589,
+        0xe13f0cc8,
+        0xb6630ca6_u32,
+    ];
+    let enc = Aes128Enc::from_openssl_schedule(&words);
+    aes_test!(enc: enc, AES_128_VECTORS);
+
+    // OpenSSL's decryption schedule is the reversed schedule with InvMixColumns applied to the inner keys
+    let enc_keys = keygen_128(*AES_128_KEY);
+    let mut dec_keys = enc_keys;
+    dec_keys.reverse();
+    for rk in &mut dec_keys[1..10] {
+        *rk = rk.imc();
+    }
+    let dec = Aes128Dec::from_openssl_schedule(&to_words(&dec_keys));
+    aes_test!(dec: dec, AES_128_VECTORS);
+
+    // the same layout for the longer keys
+    let enc = Aes256Enc::from_openssl_schedule(&to_words(&keygen_256(*AES_256_KEY)));
+    aes_test!(enc: enc, AES_256_VECTORS);
+}
+
+#[test]
+fn runtime_key_size_test() {
+    let aes = Aes::new(&AES_128_KEY[..]).unwrap();
+    assert!(matches!(aes, Aes::Aes128(_)));
+    aes_test!(enc: aes, AES_128_VECTORS);
+
+    let aes = Aes::new(&AES_192_KEY[..]).unwrap();
+    assert!(matches!(aes, Aes::Aes192(_)));
+    aes_test!(enc: aes, AES_192_VECTORS);
+
+    let dec = AesDec::new(&AES_256_KEY[..]).unwrap();
+    assert!(matches!(dec, AesDec::Aes256(_)));
+    aes_test!(dec: dec, AES_256_VECTORS);
+    let enc = dec.encrypter();
+    aes_test!(enc: enc, AES_256_VECTORS);
+
+    for len in [0, 15, 17, 23, 31, 33, 64] {
+        assert_eq!(Aes::new(&[0; 64][..len]).err(), Some(InvalidKeyLength(len)));
+        assert_eq!(
+            AesDec::new(&[0; 64][..len]).err(),
+            Some(InvalidKeyLength(len))
+        );
+    }
+
+    let error: &dyn core::error::Error = &InvalidKeyLength(20);
+    assert_eq!(
+        std::format!("{error}"),
+        "invalid AES key length 20, expected 16, 24 or 32 bytes"
+    );
+}
+
+#[test]
+fn store_masked_test() {
+    let bytes: [u8; 64] = core::array::from_fn(|i| i as u8 + 1);
+    let block = AesBlockX4::from(bytes);
+
+    for n in [0, 1, 15, 16, 17, 33, 63, 64] {
+        let mask = if n == 64 { u64::MAX } else { (1 << n) - 1 };
+        let mut dst = [0; 64];
+        block.store_masked(mask, &mut dst[..n]);
+        assert_eq!(dst[..n], bytes[..n]);
+        assert!(dst[n..].iter().all(|&x| x == 0));
+    }
+
+    for mask in [
+        0x8000_0000_0000_0001,
+        0x5555_5555_5555_5555,
+        0x00ff_0000_ff00_f00f,
+    ] {
+        let mut dst = [0; 64];
+        block.store_masked(mask, &mut dst);
+        for i in 0..64 {
+            let expected = if (mask >> i) & 1 != 0 { bytes[i] } else { 0 };
+            assert_eq!(dst[i], expected);
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn zeroize_test() {
+    use zeroize::Zeroize;
+
+    let mut enc = Aes192Enc::from(*AES_192_KEY);
+    let mut dec = enc.decrypter();
+    enc.zeroize();
+    dec.zeroize();
+    assert!(enc.round_keys.iter().all(|rk| rk.is_zero()));
+    assert!(dec.round_keys.iter().all(|rk| rk.is_zero()));
+
+    let mut block = AesBlockX4::from(AesBlock::from(0x1234));
+    block.zeroize();
+    assert!(block.is_zero());
+}
+
+#[test]
+fn backend_info_test() {
+    let info = backend_info();
+
+    assert_eq!(info.nightly, cfg!(feature = "nightly"));
+    assert_eq!(info.has_native_x2, HAS_NATIVE_X2);
+    assert_eq!(info.has_native_x4, HAS_NATIVE_X4);
+    assert_eq!(info.is_constant_time, IS_CONSTANT_TIME);
+
+    // only the table-based software implementation (possibly as a runtime fallback) is not constant-time
+    assert_eq!(
+        info.is_constant_time,
+        !info.aes_impl.ends_with("Software AES") || cfg!(feature = "constant-time")
+    );
+    if cfg!(feature = "constant-time") {
+        assert!(info.is_constant_time);
+    }
+    if info.has_native_x4 {
+        assert!(info.nightly && info.aes_impl == "AES-NI");
+    }
+
+    let line = std::format!("{info}");
+    assert!(line.contains(info.aes_impl) && line.contains(info.aes_x4_impl));
+}
+
+#[cfg(feature = "subtle")]
+#[test]
+fn ct_eq_test() {
+    use subtle::ConstantTimeEq;
+
+    let a = AesBlock::from(0x000102030405060708090a0b0c0d0e0f);
+    for i in 0..128 {
+        let b = a ^ AesBlock::from(1u128 << i);
+        assert!(!bool::from(a.ct_eq(&b)));
+        assert!(!bool::from(ConstantTimeEq::ct_eq(&a, &b)));
+    }
+    assert!(bool::from(a.ct_eq(&a)));
+
+    let x4 = AesBlockX4::from((a, a, a, a));
+    assert!(bool::from(x4.ct_eq(&x4)));
+    for i in 0..4 {
+        let mut lanes = [a; 4];
+        lanes[i] = a ^ AesBlock::from(1);
+        let y4 = AesBlockX4::from((lanes[0], lanes[1], lanes[2], lanes[3]));
+        assert!(!bool::from(x4.ct_eq(&y4)));
+    }
+
+    let x2 = AesBlockX2::from((a, a));
+    assert!(bool::from(x2.ct_eq(&x2)));
+    assert!(!bool::from(
+        x2.ct_eq(&AesBlockX2::from((a, a ^ AesBlock::from(1))))
+    ));
+}
+
+#[test]
+fn polyval_test() {
+    use crate::polyval::Polyval;
+
+    // the four-block path has to agree with block-by-block hashing
+    let h = AesBlock::from(0x25629347589242761d31f826ba4b757b);
+    let data: std::vec::Vec<u8> = (0..=255).cycle().take(16 * 9 + 7).collect();
+    let mut expected = Polyval::new(h);
+    for chunk in data.chunks(16) {
+        let mut block = [0; 16];
+        block[..chunk.len()].copy_from_slice(chunk);
+        expected.update(block.into());
+    }
+    let mut polyval = Polyval::new(h);
+    polyval.update_padded(&data);
+    assert_eq!(polyval.finalize(), expected.finalize());
+
+    // the example of RFC 8452, appendix A
+    let mut polyval = Polyval::new(h);
+    polyval.update_padded(
+        &<[u8; 32]>::from_hex("4f4f95668c83dfb6401762bb2d01a262d1a24ddd2721d006bbe45f20d3c9f362")
+            .unwrap(),
+    );
+    assert_eq!(
+        polyval.finalize(),
+        AesBlock::from(0xf7a3b47b846119fae5b7866cf5e5b77e)
+    );
+}
+
+#[test]
+fn gcm_siv_test() {
+    use gcm_siv::{Aes128GcmSiv, Aes256GcmSiv, AesGcmSiv};
+    use std::vec::Vec;
+
+    fn hex_vec(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn check<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(
+        siv: &AesGcmSiv<E, KEY_LEN>,
+        nonce: &str,
+        aad: &str,
+        plaintext: &str,
+        result: &str,
+    ) {
+        let nonce = <[u8; 12]>::from_hex(nonce).unwrap();
+        let aad = hex_vec(aad);
+        let plaintext = hex_vec(plaintext);
+        let result = hex_vec(result);
+        let (ciphertext, tag) = result.split_at(plaintext.len());
+        let tag = <[u8; 16]>::try_from(tag).unwrap();
+
+        let mut out = std::vec![0; plaintext.len()];
+        let mut computed = [0; 16];
+        siv.encrypt(&nonce, &aad, &plaintext, &mut out, &mut computed);
+        assert_eq!(out, ciphertext);
+        assert_eq!(computed, tag);
+
+        assert_eq!(siv.decrypt(&nonce, &aad, &mut out, &tag), Ok(()));
+        assert_eq!(out, plaintext);
+
+        let mut out = ciphertext.to_vec();
+        let mut bad_tag = tag;
+        bad_tag[0] ^= 1;
+        assert_eq!(
+            siv.decrypt(&nonce, &aad, &mut out, &bad_tag),
+            Err(aead::AuthError)
+        );
+        assert!(out.iter().all(|&x| x == 0));
+
+        if !ciphertext.is_empty() {
+            let mut out = ciphertext.to_vec();
+            out[0] ^= 1;
+            assert!(siv.decrypt(&nonce, &aad, &mut out, &tag).is_err());
+        }
+    }
+
+    // test vectors of RFC 8452, appendix C
+    let siv = Aes128GcmSiv::new(Aes128Enc::from(
+        <[u8; 16]>::from_hex("01000000000000000000000000000000").unwrap(),
+    ));
+    let nonce = "030000000000000000000000";
+    check(&siv, nonce, "", "", "dc20e2d83f25705bb49e439eca56de25");
+    check(
+        &siv,
+        nonce,
+        "",
+        "0100000000000000",
+        "b5d839330ac7b786578782fff6013b815b287c22493a364c",
+    );
+    check(
+        &siv,
+        nonce,
+        "",
+        "010000000000000000000000",
+        "7323ea61d05932260047d942a4978db357391a0bc4fdec8b0d106639",
+    );
+    check(
+        &siv,
+        nonce,
+        "",
+        "01000000000000000000000000000000",
+        "743f7c8077ab25f8624e2e948579cf77303aaf90f6fe21199c6068577437a0c4",
+    );
+    check(
+        &siv,
+        nonce,
+        "01",
+        "0200000000000000",
+        "1e6daba35669f4273b0a1a2560969cdf790d99759abd1508",
+    );
+
+    let siv = Aes256GcmSiv::new(Aes256Enc::from(
+        <[u8; 32]>::from_hex("0100000000000000000000000000000000000000000000000000000000000000")
+            .unwrap(),
+    ));
+    check(&siv, nonce, "", "", "07f5f4169bbf55a8400cd47ea6fd400f");
+    check(
+        &siv,
+        nonce,
+        "",
+        "0100000000000000",
+        "c2ef328e5c71c83b843122130f7364b761e0b97427e3df28",
+    );
+
+    // long enough for the four-block paths, with partial final blocks (checked against OpenSSL)
+    let aad = "010000000000000000000000";
+    let plaintext = "0100000000000000000000000000000002000000000000000000000000000000\
+                     030000000000000000000000000000000400000000000000000000000000000005000000";
+    check(
+        &Aes128GcmSiv::new(Aes128Enc::from(
+            <[u8; 16]>::from_hex("01000000000000000000000000000000").unwrap(),
+        )),
+        nonce,
+        aad,
+        plaintext,
+        "0050e8ebd70bedf8f95ad983d30fe8c6dcc32e42ef8e816d14072ed613ba752958a1e49e2c8aaa133750535ca560bdc8\
+         0e7dda69b3e4a0f63d4301a9a3dbc4c1c8dde096e18e881de25d19e6c2792980ce1f9cf0",
+    );
+    check(
+        &siv,
+        nonce,
+        aad,
+        plaintext,
+        "71e686efd5a724c26d0c5baedf1607f61b34dbf9050049d913cf16e15c8d8f3bf4414176dfccd1ee83e15ef7f3b8c0f4\
+         8ea555b3dc09a1bf7280ce3fad258bb7f1006b574ef32cb006486e89ef1d492008d2264d",
+    );
+
+    // the tag starts with ffffffff, so the 32-bit little-endian counter wraps around after the first block
+    check(
+        &Aes128GcmSiv::new(Aes128Enc::from([0; 16])),
+        "000000000000000000000000",
+        "",
+        "0000000000000000000000000000000013c635c26c86ef7f6402976842f34f30",
+        "d3165b5b1a183b5429ea0d33ad4eb0eb79ab692dbb7f0ea20b06b24de0b95bd9ffffffff000000000000000000000000",
+    );
+}
+
+#[test]
+fn ghash_table_test() {
+    use crate::ghash::GhashKey;
+
+    let mut x = 0x66e94bd4ef8a2c3b884cfa59ca342b2e_u128;
+    let mut h = 0x0388dace60b6a392f328c2b971b2fe78_u128;
+    for _ in 0..64 {
+        let key = GhashKey::new(h.into());
+        let expected = AesBlock::from(x).gf128_mul(h.into());
+        assert_eq!(key.mul_table(x.into()), expected);
+        assert_eq!(key.mul(x.into()), expected);
+
+        (x, h) = (h.rotate_left(13) ^ x, x.wrapping_mul(0x9e3779b97f4a7c15));
+    }
+
+    // the identity of the reflected convention is the block with only its first bit set
+    let key = GhashKey::new((1 << 127).into());
+    assert_eq!(u128::from(key.mul_table(x.into())), x);
+}
+
+#[test]
+fn ghash_test() {
+    use crate::ghash::GHash;
+
+    // GCM spec test case 4, with the intermediate values X_1..X_7 of the GHASH
+    let h = AesBlock::from(0xb83b533708bf535d0aa6e52980d53b78);
+    let aad = <[u8; 20]>::from_hex("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+    let ciphertext = <[u8; 60]>::from_hex(
+        "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+         21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091",
+    )
+    .unwrap();
+    let lengths = AesBlock::from(((20 * 8) << 64) | (60 * 8));
+    let intermediates = [
+        0xed56aaf8a72d67049fdb9228edba1322,
+        0xcd47221ccef0554ee4bb044c88150352,
+        0x54f5e1b2b5a8f9525c23924751a3ca51,
+        0x324f585c6ffc1359ab371565d6c45f93,
+        0xca7dd446af4aa70cc3c0cd5abba6aa1c,
+        0x1590df9b2eb6768289e57d56274c8570,
+        0x698e57f70e6ecc7fd9463b7260a9ae5f,
+    ]
+    .map(AesBlock::from);
+
+    let mut padded_aad = [0; 32];
+    padded_aad[..20].copy_from_slice(&aad);
+    let mut padded = [0; 64];
+    padded[..60].copy_from_slice(&ciphertext);
+    let blocks = padded_aad
+        .chunks(16)
+        .chain(padded.chunks(16))
+        .map(|block| AesBlock::try_from(block).unwrap());
+
+    let mut ghash = GHash::new(h);
+    for (block, &expected) in blocks.chain([lengths]).zip(&intermediates) {
+        ghash.update(block);
+        assert_eq!(ghash.clone().finalize(), expected);
+    }
+    assert_eq!(ghash.finalize(), intermediates[6]);
+
+    // the ciphertext blocks through the 4-way path, and both partial final blocks through the padding
+    let mut ghash = GHash::new(h);
+    ghash.update_padded(&aad);
+    assert_eq!(ghash.clone().finalize(), intermediates[1]);
+    ghash.update_4_blocks(AesBlockX4::from(padded));
+    assert_eq!(ghash.clone().finalize(), intermediates[5]);
+    ghash.update(lengths);
+    assert_eq!(ghash.finalize(), intermediates[6]);
+
+    let mut ghash = GHash::new(h);
+    ghash.update_padded(&aad);
+    ghash.update_padded(&ciphertext);
+    ghash.update(lengths);
+    assert_eq!(ghash.finalize(), intermediates[6]);
+
+    // long inputs mix the 4-way and single-block paths, and have to agree with block-by-block hashing
+    let data: std::vec::Vec<u8> = (0..=255).cycle().take(16 * 23 + 5).collect();
+    let mut expected = GHash::new(h);
+    for chunk in data.chunks(16) {
+        let mut block = [0; 16];
+        block[..chunk.len()].copy_from_slice(chunk);
+        expected.update(block.into());
+    }
+    let mut ghash = GHash::new(h);
+    ghash.update_padded(&data);
+    assert_eq!(ghash.finalize(), expected.finalize());
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn heapless_aead_test() {
+    use aead::HeaplessError;
+    use gcm::AesGcm;
+    use gcm_siv::Aes128GcmSiv;
+
+    let gcm = AesGcm::new(Aes128Enc::from(*AES_128_KEY));
+    let plaintext = b"a message for an allocator-free target";
+
+    let mut ciphertext = [0; 38];
+    let mut tag = [0; 16];
+    gcm.encrypt(&[1; 12], b"aad", plaintext, &mut ciphertext, &mut tag);
+
+    // the output is the ciphertext followed by the tag, and fits exactly
+    let sealed = gcm
+        .encrypt_to_heapless::<54>(&[1; 12], b"aad", plaintext)
+        .unwrap();
+    assert_eq!(&sealed[..38], &ciphertext);
+    assert_eq!(&sealed[38..], &tag);
+
+    let opened = gcm
+        .decrypt_to_heapless::<38>(&[1; 12], b"aad", &sealed)
+        .unwrap();
+    assert_eq!(&opened[..], plaintext);
+
+    // one byte short of the capacity needed
+    assert_eq!(
+        gcm.encrypt_to_heapless::<53>(&[1; 12], b"aad", plaintext),
+        Err(HeaplessError::Capacity)
+    );
+    assert_eq!(
+        gcm.decrypt_to_heapless::<37>(&[1; 12], b"aad", &sealed),
+        Err(HeaplessError::Capacity)
+    );
+
+    // a wrong tag or AAD, and inputs too short to hold a tag, are rejected
+    let mut tampered = sealed.clone();
+    tampered[53] ^= 1;
+    assert_eq!(
+        gcm.decrypt_to_heapless::<64>(&[1; 12], b"aad", &tampered),
+        Err(HeaplessError::Auth)
+    );
+    assert_eq!(
+        gcm.decrypt_to_heapless::<64>(&[1; 12], b"AAD", &sealed),
+        Err(HeaplessError::Auth)
+    );
+    assert_eq!(
+        gcm.decrypt_to_heapless::<64>(&[1; 12], b"aad", &sealed[..15]),
+        Err(HeaplessError::Auth)
+    );
+    assert_eq!(
+        gcm.decrypt_to_heapless::<64>(&[], b"aad", &sealed),
+        Err(HeaplessError::Auth)
+    );
+
+    let siv = Aes128GcmSiv::new(Aes128Enc::from(*AES_128_KEY));
+    let sealed = siv.encrypt_to_heapless::<16>(&[2; 12], b"", b"").unwrap();
+    assert_eq!(sealed.len(), 16);
+    assert!(siv
+        .decrypt_to_heapless::<0>(&[2; 12], b"", &sealed)
+        .unwrap()
+        .is_empty());
+    let sealed = siv
+        .encrypt_to_heapless::<64>(&[2; 12], b"aad", plaintext)
+        .unwrap();
+    assert_eq!(
+        &siv.decrypt_to_heapless::<64>(&[2; 12], b"aad", &sealed)
+            .unwrap()[..],
+        plaintext
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_test() {
+    use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Token};
+
+    let block = AesBlock::from(0x000102030405060708090a0b0c0d0e0f);
+    assert_tokens(
+        &block.readable(),
+        &[Token::Str("000102030405060708090a0b0c0d0e0f")],
+    );
+
+    let mut tokens = std::vec![Token::Tuple { len: 16 }];
+    tokens.extend((0..16).map(Token::U8));
+    tokens.push(Token::TupleEnd);
+    assert_tokens(&block.compact(), &tokens);
+
+    let x2 = AesBlockX2::from((block, block ^ u128::MAX.into()));
+    assert_tokens(
+        &x2.readable(),
+        &[Token::Str(
+            "000102030405060708090a0b0c0d0e0ffffefdfcfbfaf9f8f7f6f5f4f3f2f1f0",
+        )],
+    );
+
+    let mut tokens = std::vec![Token::Tuple { len: 64 }];
+    tokens.extend((0..64).map(Token::U8));
+    tokens.push(Token::TupleEnd);
+    let bytes: [u8; 64] = core::array::from_fn(|i| i as u8);
+    assert_tokens(&AesBlockX4::from(bytes).compact(), &tokens);
+
+    assert_de_tokens_error::<serde_test::Readable<AesBlock>>(
+        &[Token::Str("0001")],
+        "invalid length 4, expected 16 bytes, or a string of 32 hex digits",
+    );
+    assert_de_tokens_error::<serde_test::Readable<AesBlock>>(
+        &[Token::Str("000102030405060708090a0b0c0d0e0g")],
+        "invalid value: string \"000102030405060708090a0b0c0d0e0g\", expected 16 bytes, or a string of 32 hex digits",
+    );
+    assert_de_tokens_error::<serde_test::Compact<AesBlock>>(
+        &[Token::Tuple { len: 16 }, Token::U8(0), Token::TupleEnd],
+        "invalid length 1, expected 16 bytes, or a string of 32 hex digits",
+    );
+}
+
+#[test]
+fn wide_hex_fmt_test() {
+    let a = AesBlock::from(0x000102030405060708090a0b0c0d0e0f);
+    let b = AesBlock::from(0xf0e0d0c0b0a090807060504030201000);
+
+    let x2 = AesBlockX2::from((a, b));
+    assert_eq!(std::format!("{x2:x}"), std::format!("{a:x}{b:x}"));
+    assert_eq!(std::format!("{x2:X}"), std::format!("{a:X}{b:X}"));
+    assert_eq!(std::format!("{x2:#x}"), std::format!("{a:#x}{b:x}"));
+    assert_eq!(std::format!("{x2}"), std::format!("{x2:x}"));
+    assert_eq!(std::format!("{x2:#}"), std::format!("{x2:X}"));
+
+    let x4 = AesBlockX4::from((a, b, b, a));
+    assert_eq!(
+        std::format!("{x4:#X}"),
+        std::format!("{a:#X}{b:X}{b:X}{a:X}")
+    );
+    assert_eq!(std::format!("{x4:x}").len(), 128);
+}
+
+#[cfg(feature = "subtle")]
+#[test]
+fn conditional_swap_test() {
+    use subtle::{Choice, ConditionallySelectable};
+
+    let (a, b) = (AesBlock::from(1), AesBlock::from(u128::MAX << 64));
+
+    let (mut x, mut y) = (a, b);
+    AesBlock::conditional_swap(&mut x, &mut y, Choice::from(0));
+    assert_eq!((x, y), (a, b));
+    AesBlock::conditional_swap(&mut x, &mut y, Choice::from(1));
+    assert_eq!((x, y), (b, a));
+
+    let (a4, b4) = (AesBlockX4::from(a), AesBlockX4::from((a, b, a, b)));
+    let (mut x4, mut y4) = (a4, b4);
+    AesBlockX4::conditional_swap(&mut x4, &mut y4, Choice::from(0));
+    assert_eq!((x4, y4), (a4, b4));
+    AesBlockX4::conditional_swap(&mut x4, &mut y4, Choice::from(1));
+    assert_eq!((x4, y4), (b4, a4));
+
+    assert_eq!(
+        AesBlockX2::conditional_select(&a.into(), &b.into(), Choice::from(1)),
+        b.into()
+    );
+}
+
+#[test]
+fn from_slice_blocks_test() {
+    let blocks: [AesBlock; 8] = core::array::from_fn(|i| AesBlock::from(i as u128 + 1));
+
+    let x2 = AesBlockX2::from_slice_blocks(&blocks[2..4]);
+    assert_eq!(x2, AesBlockX2::from((blocks[2], blocks[3])));
+    assert_eq!(x2, AesBlockX2::from([blocks[2], blocks[3]]));
+
+    for chunk in blocks.chunks_exact(4) {
+        let x4 = AesBlockX4::from_slice_blocks(chunk);
+        assert_eq!(
+            x4,
+            AesBlockX4::from((chunk[0], chunk[1], chunk[2], chunk[3]))
+        );
+        assert_eq!(
+            x4,
+            AesBlockX4::from(<[AesBlock; 4]>::try_from(chunk).unwrap())
+        );
+    }
+
+    assert_eq!(
+        AesBlockX8::from_slice_blocks(&blocks),
+        AesBlockX8::from(blocks)
+    );
+}
+
+#[test]
+#[should_panic = "expected exactly 4 blocks"]
+fn from_slice_blocks_wrong_length_test() {
+    let _ = AesBlockX4::from_slice_blocks(&[AesBlock::zero(); 3]);
+}
+
+#[test]
+fn round_keys_test() {
+    let enc = Aes128Enc::from(*AES_128_KEY);
+    let dec = enc.decrypter();
+
+    // FIPS-197 Appendix A.1
+    assert_eq!(enc.round_keys().len(), 11);
+    assert_eq!(enc.round_keys()[0], AesBlock::from(*AES_128_KEY));
+    assert_eq!(
+        enc.round_keys()[10],
+        AesBlock::from(0xd014f9a8c9ee2589e13f0cc8b6630ca6)
+    );
+    assert_eq!(dec.round_keys()[0], enc.round_keys()[10]);
+    assert_eq!(dec.round_keys()[10], enc.round_keys()[0]);
+
+    let enc2 = Aes128Enc::from_round_keys(enc.round_keys().try_into().unwrap());
+    let dec2 = Aes128Dec::from_round_keys(dec.round_keys().try_into().unwrap());
+    aes_test!(enc: enc2, AES_128_VECTORS);
+    aes_test!(dec: dec2, AES_128_VECTORS);
+
+    let enc = Aes256Enc::from(*AES_256_KEY);
+    let enc2 = Aes256Enc::from_round_keys(enc.round_keys().try_into().unwrap());
+    assert_eq!(enc2.round_keys(), enc.round_keys());
+    aes_test!(enc: enc2, AES_256_VECTORS);
+}
+
+// `new_const` only exists on the software backends, and every other backend needs one of these
+#[cfg(not(any(
+    target_feature = "aes",
+    target_feature = "gfni",
+    target_feature = "simd128",
+    target_feature = "zkne",
+    feature = "runtime-detect"
+)))]
+#[test]
+fn new_const_test() {
+    static KEY_128: Aes128Enc =
+        Aes128Enc::new_const(0x2b7e151628aed2a6abf7158809cf4f3c_u128.to_be_bytes());
+    static KEY_192: Aes192Enc = Aes192Enc::new_const([0x42; 24]);
+    static KEY_256: Aes256Enc = Aes256Enc::new_const([0x42; 32]);
+
+    assert_eq!(
+        KEY_128.round_keys(),
+        Aes128Enc::from(*AES_128_KEY).round_keys()
+    );
+    assert_eq!(
+        KEY_192.round_keys(),
+        Aes192Enc::from([0x42; 24]).round_keys()
+    );
+    assert_eq!(
+        KEY_256.round_keys(),
+        Aes256Enc::from([0x42; 32]).round_keys()
+    );
+    aes_test!(enc: KEY_128, AES_128_VECTORS);
+}
+
+#[test]
+fn cfb_ofb_test() {
+    use cfb::{cfb_decrypt, cfb_encrypt, Cfb128};
+    use ofb::{ofb_apply, Ofb};
+
+    // NIST SP 800-38A, F.3.13, F.3.17, F.4.1 and F.4.5
+    let iv = <[u8; 16]>::from_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+    let plaintext = <[u8; 64]>::from_hex(
+        "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+         30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710",
+    )
+    .unwrap();
+
+    fn check<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize>(
+        enc: &E,
+        iv: [u8; 16],
+        plaintext: &[u8; 64],
+        cfb: &str,
+        ofb: &str,
+    ) {
+        let cfb = <[u8; 64]>::from_hex(cfb).unwrap();
+        let ofb = <[u8; 64]>::from_hex(ofb).unwrap();
+
+        let mut data = *plaintext;
+        cfb_encrypt(enc, iv.into(), &mut data);
+        assert_eq!(data, cfb);
+        cfb_decrypt(enc, iv.into(), &mut data);
+        assert_eq!(&data, plaintext);
+
+        let mut data = *plaintext;
+        ofb_apply(enc, iv.into(), &mut data);
+        assert_eq!(data, ofb);
+        ofb_apply(enc, iv.into(), &mut data);
+        assert_eq!(&data, plaintext);
+
+        // streaming in uneven chunks, including empty ones, with a message of 4.5 blocks so that the
+        // decryption takes both the 4-block path and the bytewise one
+        for split in [0, 1, 7, 16, 20, 33, 64] {
+            let mut long = [0; 72];
+            long[..64].copy_from_slice(plaintext);
+            long[64..].copy_from_slice(&plaintext[..8]);
+
+            let mut expected = long;
+            cfb_encrypt(enc, iv.into(), &mut expected);
+            assert_eq!(expected[..64], cfb);
+
+            let mut data = long;
+            let mut stream = Cfb128::new(enc.clone(), iv);
+            stream.encrypt(&mut data[..split]);
+            stream.encrypt(&mut []);
+            stream.encrypt(&mut data[split..]);
+            assert_eq!(data, expected);
+
+            let mut stream = Cfb128::new(enc.clone(), iv);
+            stream.decrypt(&mut data[..split]);
+            stream.decrypt(&mut []);
+            stream.decrypt(&mut data[split..]);
+            assert_eq!(data, long);
+
+            let mut stream = Ofb::new(enc.clone(), iv);
+            stream.apply_keystream(&mut data[..split]);
+            stream.apply_keystream(&mut []);
+            stream.apply_keystream(&mut data[split..]);
+            assert_eq!(data[..64], ofb);
+        }
+    }
+
+    check(
+        &Aes128Enc::from(*AES_128_KEY),
+        iv,
+        &plaintext,
+        "3b3fd92eb72dad20333449f8e83cfb4ac8a64537a0b3a93fcde3cdad9f1ce58b\
+         26751f67a3cbb140b1808cf187a4f4dfc04b05357c5d1c0eeac4c66f9ff7f2e6",
+        "3b3fd92eb72dad20333449f8e83cfb4a7789508d16918f03f53c52dac54ed825\
+         9740051e9c5fecf64344f7a82260edcc304c6528f659c77866a510d9c1d6ae5e",
+    );
+    check(
+        &Aes256Enc::from(*AES_256_KEY),
+        iv,
+        &plaintext,
+        "dc7e84bfda79164b7ecd8486985d386039ffed143b28b1c832113c6331e5407b\
+         df10132415e54b92a13ed0a8267ae2f975a385741ab9cef82031623d55b1e471",
+        "dc7e84bfda79164b7ecd8486985d38604febdc6740d20b3ac88f6ad82a4fb08d\
+         71ab47a086e86eedf39d1c5bba97c4080126141d67f37be8538f5a8be740e484",
+    );
+}
+
+#[test]
+fn neg_test() {
+    // the operators have to form the group GF(2)^128 together, as code generic over numeric types assumes
+    fn check_group<T>(x: T, y: T, zero: T)
+    where
+        T: Copy + PartialEq + Debug + Neg<Output = T> + core::ops::BitXor<Output = T>,
+    {
+        assert_eq!(x ^ -x, zero);
+        assert_eq!(-(-x), x);
+        assert_eq!(-(x ^ y), -x ^ -y);
+        assert_eq!((x ^ y) ^ -y, x);
+    }
+
+    let values = [0, 1, 0x000102030405060708090a0b0c0d0e0f, u128::MAX];
+    for value in values {
+        let block = AesBlock::from(value);
+        assert_eq!(-block, block);
+        assert!((block ^ -block).is_zero());
+        for other in values {
+            check_group(block, AesBlock::from(other), AesBlock::zero());
+        }
+    }
+}
+
+#[test]
+fn bit_mask_test() {
+    assert_eq!(AesBlock::broadcast_bit(true), AesBlock::from([0xff; 16]));
+    assert_eq!(AesBlock::broadcast_bit(false), AesBlock::zero());
+
+    assert_eq!(
+        AesBlock::from([1; 16]).expand_lsb_to_mask(),
+        AesBlock::from([0xff; 16])
+    );
+    assert_eq!(
+        AesBlock::from([0xff; 16]).expand_lsb_to_mask(),
+        AesBlock::from([0xff; 16])
+    );
+    assert!(AesBlock::zero().expand_lsb_to_mask().is_zero());
+    assert!(AesBlock::from([0xfe; 16]).expand_lsb_to_mask().is_zero());
+
+    let alternating = AesBlock::from(0x0100_0100_0100_0100_0100_0100_0100_0100);
+    assert_eq!(
+        alternating.expand_lsb_to_mask(),
+        AesBlock::from(0xff00_ff00_ff00_ff00_ff00_ff00_ff00_ff00)
+    );
+    assert_eq!(
+        (!alternating & AesBlock::from([1; 16])).expand_lsb_to_mask(),
+        AesBlock::from(0x00ff_00ff_00ff_00ff_00ff_00ff_00ff_00ff)
+    );
+
+    // only the first and last bytes set, to check the bytes at both ends of the integer
+    let ends = AesBlock::from(0x0100_0000_0000_0000_0000_0000_0000_0001);
+    assert_eq!(
+        ends.expand_lsb_to_mask(),
+        AesBlock::from(0xff00_0000_0000_0000_0000_0000_0000_00ff)
+    );
+}
+
+#[test]
+fn enc2_test() {
+    let enc = Aes128Enc::from(*AES_128_KEY);
+    let rk = enc.round_keys();
+    let block = AesBlock::from(0x00112233445566778899aabbccddeeff);
+
+    let mut state = block ^ rk[0];
+    for i in (1..9).step_by(2) {
+        state = state.enc2(rk[i], rk[i + 1]);
+    }
+    assert_eq!(state.enc(rk[9]).enc_last(rk[10]), enc.encrypt_block(block));
+
+    let (a, b) = (AesBlock::from(1), AesBlock::from(2));
+    assert_eq!(block.dec2(a, b), block.dec(a).dec(b));
+    let x2 = AesBlockX2::from((block, a));
+    let (a2, b2) = (AesBlockX2::from(a), AesBlockX2::from(b));
+    assert_eq!(x2.enc2(a2, b2), x2.enc(a2).enc(b2));
+    assert_eq!(x2.dec2(a2, b2), x2.dec(a2).dec(b2));
+    let x4 = AesBlockX4::from((block, a, b, block));
+    let (a4, b4) = (AesBlockX4::from(a), AesBlockX4::from(b));
+    assert_eq!(x4.enc2(a4, b4), x4.enc(a4).enc(b4));
+    assert_eq!(x4.dec2(a4, b4), x4.dec(a4).dec(b4));
+}
+
+#[test]
+fn round_assign_test() {
+    let key = AesBlock::from(0x0f0e0d0c0b0a09080706050403020100);
+    for value in [0, 1, 0x000102030405060708090a0b0c0d0e0f, u128::MAX] {
+        let block = AesBlock::from(value);
+        let mut assigned = [block; 4];
+        assigned[0].enc_assign(key);
+        assigned[1].dec_assign(key);
+        assigned[2].mc_assign();
+        assigned[3].imc_assign();
+        assert_eq!(
+            assigned,
+            [block.enc(key), block.dec(key), block.mc(), block.imc()]
+        );
+
+        let (block, key) = (AesBlockX4::from(block), AesBlockX4::from(key));
+        let mut assigned = [block; 4];
+        assigned[0].enc_assign(key);
+        assigned[1].dec_assign(key);
+        assigned[2].mc_assign();
+        assigned[3].imc_assign();
+        assert_eq!(
+            assigned,
+            [block.enc(key), block.dec(key), block.mc(), block.imc()]
+        );
+    }
+}
+
+#[test]
+fn from_checked_test() {
+    assert_eq!(
+        Aes128Enc::from_checked([0; 16]).err(),
+        Some(WeakKeyError::Zero)
+    );
+    assert_eq!(
+        Aes256Dec::from_checked([0; 32]).err(),
+        Some(WeakKeyError::Zero)
+    );
+    assert_eq!(
+        Aes192Enc::from_checked_with([0; 24], KeyCheck::RepeatedByte).err(),
+        Some(WeakKeyError::Zero)
+    );
+
+    // a repeated nonzero byte only fails the stricter check
+    assert!(Aes128Enc::from_checked([0xff; 16]).is_ok());
+    assert_eq!(
+        Aes128Enc::from_checked_with([0xff; 16], KeyCheck::RepeatedByte).err(),
+        Some(WeakKeyError::RepeatedByte(0xff))
+    );
+
+    // a normal key (or one that differs only in its last byte) gives the same schedule as `from`
+    let mut almost_zero = [0; 16];
+    almost_zero[15] = 1;
+    for key in [*AES_128_KEY, almost_zero] {
+        let enc = Aes128Enc::from_checked_with(key, KeyCheck::RepeatedByte).unwrap();
+        assert_eq!(enc.round_keys(), Aes128Enc::from(key).round_keys());
+        let dec = Aes128Dec::from_checked(key).unwrap();
+        assert_eq!(dec.round_keys(), Aes128Dec::from(key).round_keys());
+    }
+}
+
+#[test]
+fn debug_hides_keys_test() {
+    use std::format;
+
+    let enc = Aes128Enc::from(*AES_128_KEY);
+    assert_eq!(format!("{enc:?}"), "Aes128Enc { .. }");
+    assert_eq!(format!("{:?}", enc.decrypter()), "Aes128Dec { .. }");
+    assert_eq!(
+        format!("{:?}", Aes192Enc::from(*AES_192_KEY)),
+        "Aes192Enc { .. }"
+    );
+    assert_eq!(
+        format!("{:#?}", Aes256Enc::from(*AES_256_KEY).decrypter()),
+        "Aes256Dec { .. }"
+    );
+
+    // the wrappers print the cipher, so they are covered too, and the GHASH key is hidden as well. The modes
+    // with derived keys or keystream in their state print only their name
+    let gcm = gcm::AesGcm::new(enc.clone());
+    let h = enc.encrypt_block(AesBlock::zero());
+    // after one known block, the accumulator is that block times `H`
+    let mut ghash = ghash::GHash::new(h);
+    ghash.update(AesBlock::from(1));
+    let mut buffer = buffer::BlockBuffer::new();
+    buffer.input_block(b"secret", |_| {});
+    let outputs = [
+        format!("{gcm:?}"),
+        format!("{:?}", Aes::Aes128(enc.clone())),
+        format!("{:?}", ctr::Ctr128::new(enc.clone(), [0; 16])),
+        format!("{:?}", cmac::Cmac::new(enc.clone())),
+        format!("{:?}", cfb::Cfb128::new(enc.clone(), [0; 16])),
+        format!("{:?}", ofb::Ofb::new(enc.clone(), [0; 16])),
+        format!("{ghash:?}"),
+        format!("{:?}", polyval::Polyval::new(h)),
+        format!("{:?}", gcm.decryptor(&[0; 12], b"").unwrap()),
+        format!(
+            "{:?}",
+            drbg::Aes128CtrDrbg::instantiate(&*AES_128_KEY, &[0; 8], b"").unwrap()
+        ),
+    ];
+    assert_eq!(outputs[3], "Cmac { .. }");
+    assert_eq!(outputs[5], "Ofb { .. }");
+    assert_eq!(outputs[6], "GHash { .. }");
+    assert_eq!(outputs[7], "Polyval { .. }");
+    assert_eq!(outputs[9], "AesCtrDrbg { .. }");
+    assert_eq!(format!("{buffer:?}"), "BlockBuffer { .. }");
+    for output in outputs {
+        for round_key in enc.round_keys() {
+            assert!(!output.contains(&format!("{round_key}")), "{output}");
+            assert!(!output.contains(&format!("{round_key:#}")), "{output}");
+        }
+        assert!(!output.contains(&format!("{h}")), "{output}");
+        assert!(!output.contains("2b7e"), "{output}");
+    }
+}
+
+#[test]
+fn as_bytes_test() {
+    let bytes = <[u8; 16]>::from_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+    for block in [
+        AesBlock::from(bytes),
+        AesBlock::from(0x000102030405060708090a0b0c0d0e0f),
+    ] {
+        // the same order as `to_bytes`, whether the result is borrowed or copied
+        assert_eq!(&block.as_bytes()[..], &bytes[..]);
+        assert_eq!(block.as_bytes()[0], 0);
+        assert_eq!(block.as_bytes()[15], 15);
+        assert!(block.as_bytes().iter().copied().eq(0..16));
+    }
+}
+
+#[test]
+fn ord_test() {
+    use std::vec::Vec;
+
+    // values whose native-endian and big-endian orders differ
+    let mut values = [
+        u128::MAX,
+        0x0100,
+        0x01,
+        0,
+        1 << 127,
+        0xff,
+        0x8000_0000_0000_0000,
+        0x0f0e0d0c0b0a09080706050403020100,
+        0x000102030405060708090a0b0c0d0e0f,
+    ];
+    let mut blocks: Vec<AesBlock> = values.iter().map(|&x| x.into()).collect();
+    values.sort_unstable();
+    blocks.sort_unstable();
+    assert!(blocks.iter().map(|&x| u128::from(x)).eq(values));
+    for &x in &values {
+        assert_eq!(blocks.binary_search(&x.into()), values.binary_search(&x));
+    }
+
+    // the wide types compare lane by lane, starting from the first
+    let a = AesBlockX4::from([1, 0, u128::MAX, u128::MAX]);
+    let b = AesBlockX4::from([1, 1, 0, 0]);
+    assert!(a < b);
+    assert_eq!(a.cmp(&a), core::cmp::Ordering::Equal);
+    assert!(
+        AesBlockX2::from((AesBlock::from(2), AesBlock::zero())) > AesBlockX2::from((1, u128::MAX))
+    );
+    let c = AesBlockX8::from((a, b));
+    assert!(c < AesBlockX8::from((b, a)));
+}
+
+#[test]
+fn wrapping_add_sub_be_test() {
+    let values = [
+        0,
+        1,
+        0xff,
+        u64::MAX.into(),
+        0x000102030405060708090a0b0c0d0e0f,
+        1 << 127,
+        u128::MAX - 1,
+        u128::MAX,
+    ];
+    for a in values {
+        for b in values {
+            let (x, y) = (AesBlock::from(a), AesBlock::from(b));
+            assert_eq!(x.wrapping_add_be(y), AesBlock::from(a.wrapping_add(b)));
+            assert_eq!(x.wrapping_sub_be(y), AesBlock::from(a.wrapping_sub(b)));
+            assert_eq!(x.wrapping_add_be(y).wrapping_sub_be(y), x);
+        }
+    }
+
+    // carries propagate across bytes, and the all-ones block wraps around to zero
+    let block = AesBlock::from(0x000102030405060708090a0b0c0d0eff);
+    assert_eq!(block.wrapping_add_be(AesBlock::from(1)), block.incr_be());
+    assert!(AesBlock::from(u128::MAX)
+        .wrapping_add_be(AesBlock::from(1))
+        .is_zero());
+    assert_eq!(
+        AesBlock::zero().wrapping_sub_be(AesBlock::from(1)),
+        AesBlock::from(u128::MAX)
+    );
+}
+
+#[test]
+fn inc_test() {
+    let block = AesBlock::from(0x000102030405060708090a0b0c0d0eff);
+    assert_eq!(
+        block.incr_be(),
+        AesBlock::from(0x000102030405060708090a0b0c0d0f00)
+    );
+    assert_eq!(
+        block.inc_le(),
+        AesBlock::from(0x010102030405060708090a0b0c0d0eff)
+    );
+    assert_eq!(
+        block.inc_be_by(0x101),
+        AesBlock::from(0x000102030405060708090a0b0c0d1000)
+    );
+
+    // carries propagate through every byte, and all-ones wraps around to zero
+    let max = AesBlock::from(u128::MAX);
+    assert_eq!(max.incr_be(), AesBlock::zero());
+    assert_eq!(max.inc_le(), AesBlock::zero());
+    assert_eq!(max.inc_be_by(2), AesBlock::from(1));
+    assert_eq!(
+        AesBlock::from(0xffff << 112).inc_le(),
+        AesBlock::from(1 << 104)
+    );
+    assert_eq!(
+        AesBlock::from(u128::from(u64::MAX)).inc_be_by(u64::MAX),
+        AesBlock::from((1 << 65) - 2)
+    );
+
+    let mut counter = block;
+    for _ in 0..300 {
+        counter = counter.incr_be();
+    }
+    assert_eq!(counter, block.inc_be_by(300));
+    assert_eq!(max.inc_be(), max.incr_be());
+}
+
+#[test]
+fn wide_lane_consistency_test() {
+    // a simple xorshift generator, so that every backend sees the same pseudo-random inputs
+    let mut state = 0x243f6a88_85a308d3_13198a2e_03707344_u128;
+    let mut random = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        AesBlock::from(state)
+    };
+
+    for _ in 0..32 {
+        let x: [AesBlock; 4] = core::array::from_fn(|_| random());
+        let k: [AesBlock; 4] = core::array::from_fn(|_| random());
+        let (x2, k2) = (
+            AesBlockX2::from([x[0], x[1]]),
+            AesBlockX2::from([k[0], k[1]]),
+        );
+        let (x4, k4) = (AesBlockX4::from(x), AesBlockX4::from(k));
+
+        let lanes = |f: fn(AesBlock, AesBlock) -> AesBlock| -> [AesBlock; 4] {
+            core::array::from_fn(|i| f(x[i], k[i]))
+        };
+        let check = |x2: AesBlockX2, x4: AesBlockX4, expected: [AesBlock; 4]| {
+            assert_eq!(<(_, _)>::from(x2), (expected[0], expected[1]));
+            assert_eq!(
+                <(_, _, _, _)>::from(x4),
+                (expected[0], expected[1], expected[2], expected[3])
+            );
+        };
+
+        check(x2.enc(k2), x4.enc(k4), lanes(AesBlock::enc));
+        check(x2.dec(k2), x4.dec(k4), lanes(AesBlock::dec));
+        check(x2.enc_last(k2), x4.enc_last(k4), lanes(AesBlock::enc_last));
+        check(x2.dec_last(k2), x4.dec_last(k4), lanes(AesBlock::dec_last));
+        check(x2.mc(), x4.mc(), lanes(|x, _| x.mc()));
+        check(x2.imc(), x4.imc(), lanes(|x, _| x.imc()));
+    }
+}
+
+#[test]
+fn xts_mul_x_test() {
+    // the tweaks of IEEE 1619 vector 2, for blocks 0, 1, 2, 3 and 31 of the sector
+    let mut tweak =
+        Aes128Enc::from([0x22; 16]).encrypt_block(0x33_3333_3333_u128.to_le_bytes().into());
+    let expected = [
+        (0, "3f803bcd0d7fd2b37558419f59d5cda6"),
+        (1, "f900779a1bfea467ebb0823eb3aa9b4d"),
+        (2, "f201ee3437fc49cfd661057d6655379b"),
+        (3, "6303dc696ef8939eadc30afaccaa6e36"),
+        (31, "44c3458b37c09de6863fe9d93aaca0cf"),
+    ];
+    let mut j = 0;
+    for (index, hex) in expected {
+        while j < index {
+            tweak = tweak.xts_mul_x();
+            j += 1;
+        }
+        assert_eq!(tweak, <[u8; 16]>::from_hex(hex).unwrap().into());
+    }
+
+    // the doubling carries across bytes, and the top bit wraps around into 0x87
+    let mut block = AesBlock::from(1_u128.to_le_bytes());
+    for i in 1..128 {
+        block = block.xts_mul_x();
+        assert_eq!(block, AesBlock::from((1_u128 << i).to_le_bytes()));
+    }
+    assert_eq!(block.xts_mul_x(), AesBlock::from(0x87_u128.to_le_bytes()));
+}
+
+#[test]
+fn xor_not_test() {
+    // every combination of input bits: (0, 0) -> 1, (0, 1) -> 0, (1, 0) -> 0, (1, 1) -> 1
+    let a = AesBlock::from([0b1100; 16]);
+    let b = AesBlock::from([0b1010; 16]);
+    let expected = AesBlock::from([0b1111_1001; 16]);
+
+    assert_eq!(a.xor_not(b), expected);
+    assert_eq!(b.xor_not(a), expected);
+    assert_eq!(a.xor_not(a), AesBlock::from(u128::MAX));
+    assert_eq!(a.xor_not(!a), AesBlock::zero());
+
+    assert_eq!(
+        AesBlockX2::from(a).xor_not(AesBlockX2::from(b)),
+        AesBlockX2::from(expected)
+    );
+    assert_eq!(
+        AesBlockX4::from(a).xor_not(AesBlockX4::from(b)),
+        AesBlockX4::from(expected)
+    );
+    assert_eq!(
+        AesBlockX8::from(a).xor_not(AesBlockX8::from(b)),
+        AesBlockX8::from(expected)
+    );
+}
+
+#[test]
+fn encrypt_with_test() {
+    use crate::iter::AesBlockIteratorExt;
+
+    let enc = Aes128Enc::from(*AES_128_KEY);
+    let plaintext: [AesBlock; 11] = core::array::from_fn(|i| AesBlock::from(i as u128 * 0x0101));
+
+    for n in 0..=plaintext.len() {
+        let mut expected = plaintext;
+        enc.encrypt_blocks(&mut expected[..n]);
+
+        let mut encrypted = plaintext[..n].iter().copied().encrypt_with(&enc);
+        assert_eq!(encrypted.size_hint(), (n, Some(n)));
+
+        let mut collected = [AesBlock::zero(); 11];
+        let mut count = 0;
+        for (dst, block) in collected.iter_mut().zip(&mut encrypted) {
+            *dst = block;
+            count += 1;
+        }
+        assert_eq!(count, n);
+        assert_eq!(encrypted.next(), None);
+        assert_eq!(collected[..n], expected[..n]);
+    }
+}
+
+#[test]
+#[cfg(feature = "subtle")]
+fn ct_order_test() {
+    let pairs = [
+        (0, 1),
+        (0x00ff, 0x0100),
+        (1 << 64, (1 << 64) + 1),
+        (u128::MAX >> 1, 1 << 127),
+        (0x0001_0203_0405_0607_0809_0a0b_0c0d_0e0f, u128::MAX),
+        (u128::MAX - 1, u128::MAX),
+    ];
+    for (x, y) in pairs {
+        let (a, b) = (AesBlock::from(x), AesBlock::from(y));
+
+        // ordered
+        assert!(bool::from(a.ct_lt(&b)));
+        assert!(!bool::from(a.ct_gt(&b)));
+        assert!(!bool::from(a.ct_eq(&b)));
+
+        // reversed
+        assert!(bool::from(b.ct_gt(&a)));
+        assert!(!bool::from(b.ct_lt(&a)));
+
+        // equal
+        assert!(!bool::from(a.ct_lt(&a)));
+        assert!(!bool::from(a.ct_gt(&a)));
+        assert!(bool::from(a.ct_eq(&a)));
+    }
+}
+
+#[test]
+fn invalid_length_test() {
+    let bytes = [0x5a; 128];
+
+    assert_eq!(
+        AesBlock::try_from(&bytes[..15]),
+        Err(InvalidLength { got: 15, need: 16 })
+    );
+    assert_eq!(
+        AesBlockX2::from_slice(&bytes[..16]),
+        Err(InvalidLength { got: 16, need: 32 })
+    );
+    assert_eq!(
+        AesBlockX4::try_from(&bytes[..0]),
+        Err(InvalidLength { got: 0, need: 64 })
+    );
+    assert_eq!(
+        AesBlockX8::from_slice(&bytes[..127]),
+        Err(InvalidLength {
+            got: 127,
+            need: 128
+        })
+    );
+
+    // longer slices are accepted, and only the prefix is loaded
+    assert_eq!(AesBlock::from_slice(&bytes), Ok(AesBlock::from([0x5a; 16])));
+    assert_eq!(
+        AesBlockX4::from_slice(&bytes),
+        AesBlockX4::try_from(&bytes[..64])
+    );
+
+    let error = InvalidLength { got: 3, need: 16 };
+    assert_eq!(
+        std::format!("{error}"),
+        "invalid slice length 3, expected at least 16 bytes"
+    );
+
+    fn load(bytes: &[u8]) -> Result<AesBlock, std::boxed::Box<dyn core::error::Error>> {
+        Ok(AesBlock::try_from(bytes)?)
+    }
+    assert!(load(&bytes[..4]).is_err());
+}
+
+#[test]
+#[cfg(feature = "ffi")]
+fn ffi_test() {
+    use crate::ffi::*;
+
+    // the schedules are `#[repr(C)]` arrays of bytes, so they can be read back as bytes
+    fn bytes<T>(value: &T) -> &[u8] {
+        unsafe {
+            core::slice::from_raw_parts((value as *const T).cast(), core::mem::size_of::<T>())
+        }
+    }
+
+    macro_rules! ffi_test {
+        (
+            $new:ident, $decrypter:ident, $encrypt:ident, $decrypt:ident, $clear:ident, $decrypter_clear:ident,
+            $key:expr, $vectors:expr
+        ) => {
+            let mut enc = $new(&$key);
+            let mut dec = $decrypter(&enc);
+            for &(plaintext, ciphertext) in $vectors.iter() {
+                assert_eq!(
+                    $encrypt(&enc, plaintext.into()),
+                    AesBlockC::from(ciphertext)
+                );
+                assert_eq!(AesBlock::from($decrypt(&dec, ciphertext.into())), plaintext);
+            }
+
+            assert!(bytes(&enc).iter().any(|&b| b != 0));
+            $clear(&mut enc);
+            $decrypter_clear(&mut dec);
+            assert!(bytes(&enc).iter().all(|&b| b == 0));
+            assert!(bytes(&dec).iter().all(|&b| b == 0));
+        };
+    }
+
+    ffi_test!(
+        aes128_new,
+        aes128_decrypter,
+        aes128_encrypt_block,
+        aes128_decrypt_block,
+        aes128_clear,
+        aes128_decrypter_clear,
+        *AES_128_KEY,
+        AES_128_VECTORS
+    );
+    ffi_test!(
+        aes192_new,
+        aes192_decrypter,
+        aes192_encrypt_block,
+        aes192_decrypt_block,
+        aes192_clear,
+        aes192_decrypter_clear,
+        *AES_192_KEY,
+        AES_192_VECTORS
+    );
+    ffi_test!(
+        aes256_new,
+        aes256_decrypter,
+        aes256_encrypt_block,
+        aes256_decrypt_block,
+        aes256_clear,
+        aes256_decrypter_clear,
+        *AES_256_KEY,
+        AES_256_VECTORS
+    );
+
+    // the C layout is exactly the round keys
+    assert_eq!(core::mem::size_of::<Aes128EncC>(), 11 * 16);
+    assert_eq!(core::mem::align_of::<AesBlockC>(), 1);
+}
+
+#[test]
+fn ctr_drbg_test() {
+    use crate::drbg::*;
+    use std::vec::Vec;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        let mut buf = std::vec![0; s.len() / 2];
+        hex::decode_to_slice(s, &mut buf).unwrap();
+        buf
+    }
+
+    // Known-answer tests in the layout of the CAVP `CTR_DRBG` tests (512 bits of output)
+    macro_rules! drbg_instantiate {
+        ($drbg:ty, $df:literal, $entropy:literal, $nonce:literal, $pers:literal) => {
+            if $df {
+                <$drbg>::instantiate(&unhex($entropy), &unhex($nonce), &unhex($pers)).unwrap()
+            } else {
+                <$drbg>::instantiate_no_df(&unhex($entropy), &unhex($pers)).unwrap()
+            }
+        };
+    }
+
+    // Without prediction resistance: instantiate, reseed, generate twice and check the second output
+    macro_rules! drbg_test {
+        ($drbg:ty, $df:literal, $entropy:literal, $nonce:literal, $pers:literal, $entropy_reseed:literal, $add_reseed:literal, $add1:literal, $add2:literal, $out:literal) => {{
+            let mut drbg = drbg_instantiate!($drbg, $df, $entropy, $nonce, $pers);
+            drbg.reseed(&unhex($entropy_reseed), &unhex($add_reseed))
+                .unwrap();
+
+            let mut out = [0; 64];
+            drbg.generate(&mut out, &unhex($add1)).unwrap();
+            drbg.generate(&mut out, &unhex($add2)).unwrap();
+            assert_eq!(out[..], unhex($out)[..]);
+        }};
+    }
+
+    // With prediction resistance, every generate call first reseeds with fresh entropy and the additional
+    // input, and then generates without additional input (SP 800-90A, 9.3.1)
+    macro_rules! drbg_pr_test {
+        ($drbg:ty, $df:literal, $entropy:literal, $nonce:literal, $pers:literal, $add1:literal, $entropy_pr1:literal, $add2:literal, $entropy_pr2:literal, $out:literal) => {{
+            let mut drbg = drbg_instantiate!($drbg, $df, $entropy, $nonce, $pers);
+
+            let mut out = [0; 64];
+            drbg.reseed(&unhex($entropy_pr1), &unhex($add1)).unwrap();
+            drbg.generate(&mut out, &[]).unwrap();
+            drbg.reseed(&unhex($entropy_pr2), &unhex($add2)).unwrap();
+            drbg.generate(&mut out, &[]).unwrap();
+            assert_eq!(out[..], unhex($out)[..]);
+        }};
+    }
+
+    // Without reseeding, as in the CAVP `no_reseed` file: instantiate, generate twice and check the second
+    // output
+    macro_rules! drbg_no_reseed_test {
+        ($drbg:ty, $df:literal, $entropy:literal, $nonce:literal, $out:literal) => {{
+            let mut drbg = drbg_instantiate!($drbg, $df, $entropy, $nonce, "");
+
+            let mut out = [0; 64];
+            drbg.generate(&mut out, &[]).unwrap();
+            drbg.generate(&mut out, &[]).unwrap();
+            assert_eq!(out[..], unhex($out)[..]);
+        }};
+    }
+
+    // NIST CAVP `CTR_DRBG` vectors (drbgvectors_no_reseed), COUNT = 0 of the AES-128 and AES-256 sections
+    // without prediction resistance, personalization string or additional input
+    drbg_no_reseed_test!(
+        Aes128CtrDrbg,
+        true,
+        "890eb067acf7382eff80b0c73bc872c6",
+        "aad471ef3ef1d203",
+        "a5514ed7095f64f3d0d3a5760394ab42062f373a25072a6ea6bcfd8489e94af6cf18659fea22ed1ca0a9e33f718b115ee536b12809c31b72b08ddd8be1910fa3"
+    );
+    drbg_no_reseed_test!(
+        Aes128CtrDrbg,
+        false,
+        "ce50f33da5d4c1d3d4004eb35244b7f2cd7f2e5076fbf6780a7ff634b249a5fc",
+        "",
+        "6545c0529d372443b392ceb3ae3a99a30f963eaf313280f1d1a1e87f9db373d361e75d18018266499cccd64d9bbb8de0185f213383080faddec46bae1f784e5a"
+    );
+    drbg_no_reseed_test!(
+        Aes256CtrDrbg,
+        true,
+        "36401940fa8b1fba91a1661f211d78a0b9389a74e5bccfece8d766af1a6d3b14",
+        "496f25b0f1301b4f501be30380a137eb",
+        "5862eb38bd558dd978a696e6df164782ddd887e7e9a6c9f3f1fbafb78941b535a64912dfd224c6dc7454e5250b3d97165e16260c2faf1cc7735cb75fb4f07e1d"
+    );
+    drbg_no_reseed_test!(
+        Aes256CtrDrbg,
+        false,
+        "df5d73faa468649edda33b5cca79b0b05600419ccb7a879ddfec9db32ee494e5531b51de16a30f769262474c73bec010",
+        "",
+        "d1c07cd95af8a7f11012c84ce48bb8cb87189e99d40fccb1771c619bdf82ab2280b1dc2f2581f39164f7ac0c510494b3a43c41b7db17514c87b107ae793e01c5"
+    );
+
+    // The same layout with reseeding, personalization strings, additional input and prediction resistance, for
+    // every key size. The expected outputs were computed with OpenSSL's `CTR-DRBG`, fed the entropy and nonces
+    // through its `TEST-RAND` generator.
+
+    // AES-128, with derivation function, no prediction resistance
+    drbg_test!(
+        Aes128CtrDrbg,
+        true,
+        "13fc8799ab641b4a0a2ef0e0d0a62f2d",
+        "cc0cf9b37466b261",
+        "b7445e72f7e1b526e7a76f37eb7ec469",
+        "21788b71e429afc5277d93ad464ce465",
+        "7f95964283b9b8f96651eb617da89fe8",
+        "72dd3de461ef50a01e604c15f0127939",
+        "e9b50eb9ae586852fcab11451b0b82ea",
+        "2563e0f11f98951d98d9be3b96634bb99ff5927456a786dc723df25f82cab87db70d0ee78b5d5d19e8fd4cb3f726333e79f5e0dd26614d8da325fb7da4bd76a4"
+    );
+    // AES-192, with derivation function, no prediction resistance
+    drbg_test!(
+        Aes192CtrDrbg,
+        true,
+        "9678c86de718c8fcf884e2f5f001775f21fa9c1242edd73d",
+        "86e5c40de958a18aab4e4940",
+        "711fc2e16608f6a54f8621ac633668851ff334d421322b1e",
+        "a5b539a004f92075302a7fe044b4903a614f9a388d66b6c1",
+        "1bdd0656ae0c66614fafd31fbdd6b8ae8271fe007800925e",
+        "17d6b5ad0407f4d199c78684a8160edf0efeafdad644b537",
+        "6c8fc68340f589c8304e25070ab8b6bc22ba7c16d07f4c80",
+        "9a10d2816acc02bc45bf926de5a1f3c47c8dfe7f29a29eb6f0a25c6a702ccb398d48c192a57d0b68d5d45dc296dbb28ae9e1585917f9183f73d3d683426ae5ee"
+    );
+    // AES-256, with derivation function, no prediction resistance
+    drbg_test!(
+        Aes256CtrDrbg,
+        true,
+        "c309e316cb1cf1844e301855d8576c6ae884119e2974017da3a4220e03c8a393",
+        "bf178b26750ce70eac3bb41e647fd193",
+        "217a914b0556cddba884d869836fe3af82098dedd64bdd48842856e745a6b773",
+        "da815a617e9097e48a02540093e52a969399f8ee21e3d33cd25b1e013594ff9d",
+        "0db76358da084c627aa66e2a39a00b13afa7c517e24fbd1ff1bddda66b3f9a13",
+        "70ce95b155f2b776464278534af124d25d862bc4b6b2fcbb68874cd45fd2e1af",
+        "ed8bd13d8d3cf6fa46008b926493e77d92eba255830aaf0c66f702a221ae39d2",
+        "2a9890086f86e095e4c3d9abd2609bc42856f128467f29f479cc4fd71a3e2ec555862d9c4880d3f03156bad8971ca54e9283387a9e070e1c2021233b3e5106a4"
+    );
+    // AES-128, with derivation function, with prediction resistance
+    drbg_pr_test!(
+        Aes128CtrDrbg,
+        true,
+        "c1eeb98b9135e13094c705edd167f26a",
+        "ca74d6a3efc556c6",
+        "f736ddd88ccbbd4b59d38651d7bce4fe",
+        "822abd578a81c4fa457f894816d3a900",
+        "2693d462a2eb93c592d6cc1e3828b7c0",
+        "ceba957e9868956124f0080451947052",
+        "a30be2c71fbed76ba60578ed8d835f12",
+        "f5fee28b979f9a5bbb1e504c3071119619a3ab08b8557b7d22192bca6011c4052d050c12e62dd60e7f9b135ec5c56fc2830d29e98754957cd5e3f79f0224c617"
+    );
+    // AES-192, with derivation function, with prediction resistance
+    drbg_pr_test!(
+        Aes192CtrDrbg,
+        true,
+        "7a7fdbb811a1c2d2cef5c8740aa94cf83884f884459e0560",
+        "3c06d7cda314fa07f58d78b2",
+        "2c1496d07f5c7499293fdd1738b59d1adce402e5ad4b391c",
+        "b7a61079f9f2d0363b981431a49c8768c7239586a5850afd",
+        "627e3dd993157be101186c325ee9b6a1cbec526bd3dfae7f",
+        "dbadf8aa60c88111209e20b61f2f1e355a746816a0a5397d",
+        "ffd55a20fcc089405c2127693348312aef8b1b44b96160fd",
+        "59f6100fd16cb4d7321417e2010cd4eb7706d4f57d785e6913c54c352f2230eb66d8cd237d77395e0bf1452de119c3333c9c00c331312e7b70a2158b2a6d9261"
+    );
+    // AES-256, with derivation function, with prediction resistance
+    drbg_pr_test!(
+        Aes256CtrDrbg,
+        true,
+        "621dae6108bd6084e831f6b67614c9c2695f548e0a39d8488ca28c19dede5c2b",
+        "c002a37b255f52c40594f470a954f5fd",
+        "80cc2002ef5507426175f5f021fbcf12ab0010fe1737a1faf3337e63fc1258fa",
+        "ee71a320aff5fc1c979c4d19e515b17511ee18cb5948fe56adbe850f6778ceaa",
+        "33840197e1feb198745e0573302a7e5afb4c4a03458abe3cda88aae230b49bb0",
+        "271d4ac6db867445ae27873cefc38f815eadf9b926e619573dad0162c6851ac6",
+        "1b770eddfe78a42b28739a2c11405fda5f34a5931db07c78cf59b7a02f9ae6e5",
+        "19f379b2f3c5fc2ea2d3fd722f357c0793b639b9f72d790575e74a02c933e862226def778d05a06961eaa4c761744d029737bb5610c7a8040a8c853b0d961e38"
+    );
+    // AES-128, no derivation function, no prediction resistance
+    drbg_test!(
+        Aes128CtrDrbg,
+        false,
+        "a4ed2063d06407ab720c1c20c3dadcdf5abb8bfa8b2bf1e487b0094ba1b59110",
+        "",
+        "06890879aa2a36b2d47eedcd7a06406d657f78fca5d92bbbe4b024dbdd965601",
+        "a4d30868ef27e41df76ac3987112519acb73759707f8f3b265e7268e65b523af",
+        "76ae1fe2a8e2600d1a9784a18463aab2c4d1442bb6377bcaa8217f96a95a777f",
+        "e0deaede26a0f53ac1893f4cdf8420caaa1319f31a60739f5893d36a8263eb11",
+        "9d3552dea3464230f00a50523a10d99d3f0b94176026f996449b3ce89c5e660f",
+        "3862803ebdb19bcfbf6e5e6ea8cbbbac9dbb45e1b5333e8f7956206788be8ecd62683ed9059fda4d21ac465f647fe761d047cc04e0ade4c889c02c32ec241472"
+    );
+    // AES-192, no derivation function, no prediction resistance
+    drbg_test!(
+        Aes192CtrDrbg,
+        false,
+        "20e61d50701653021a8c94dfca4d154f244b016bc8d39758d588af11e5041e36dbe4865ded61bd0f",
+        "",
+        "2f321653a68e92d01d4dca49e919d6bb8319feb352a42270c4e8b9b3860e930432bb941605adf428",
+        "4101090487bfd34f7fa9a3fe5cad9d27e6232ded7e703bd9d5a387e9c7b8fb0a3b2d9226fdcec24d",
+        "e7e949cef249fd4cdcc5835b0418d6a38960ebe72f4ae143c7b60777807770ab71c9ff49451f4fd8",
+        "325fb6e8a2b44df865fe43503b25746d58dc9cb3405e46ac90908c661cf5434df773dae38f81f452",
+        "a61f7059d38e5d5dd8c907173340263a80a4e3a6f672fae2325c087c301e092f3adebf85db396163",
+        "054675728d7955e36e08703297e4ac82c984a52482e392a5383d3ba19ae5521dbcd5d73be385f3400b52560ee73d013b382dfd8ad772f9d108cd955fd74067f9"
+    );
+    // AES-256, no derivation function, no prediction resistance
+    drbg_test!(
+        Aes256CtrDrbg,
+        false,
+        "c6be98d22bcf96c8fc7df0bbb48f8bfd3990ef2ff7678c92872945717eadd1a10b920035ff7f71c8bbe761a92e290f92",
+        "",
+        "4a004336f4a8323a0ffd2e0060c04df7e048f1e5adcfc529f499ab73007a2eb92cb049570355a565b601ee5478d0302e",
+        "931c8f801bfdfaa2488201dfc12f8ef64f22dec6fde9e6c962b56205008f9ef86cc27057b827b6067713d6a01f1e47b9",
+        "bb3b4fbe30d7abea899ee3cc04d376b80b8dfb78a1143d6d96255819b2fccbdbc58609bb7109c5fd354d5129269f230a",
+        "2bc238a2e19274ed9b77622b0f1ff4cd04c542370acc35da4e811a63f0b8dc858822aa0c7a99d4193dd33eb70e4af2bd",
+        "711beb2e2172a02ea379ecc7568826e6229e53cafb0de725dde348a73774c643715832025a20eb93bcdb898b10d28b38",
+        "3e9386adba260d0768f701791bac7b9d96ce7d028e7cac18099916d4e500ecd45933f6438be0cb5158906eeb8374f81a5de3b9ae73f2d7860ec3ce0598f59640"
+    );
+    // AES-128, no derivation function, with prediction resistance
+    drbg_pr_test!(
+        Aes128CtrDrbg,
+        false,
+        "68f8483383ee0d0967311c33b41b2d3a247a9daec366691e0f7d3acd29037446",
+        "",
+        "0a4f9ae159bc4e94eba16667fae0ee2f10b8ee911586e62699dfb0d30e83199c",
+        "9899cafd0a8b5af7927967178aa1e1d5915a79e6798e4b52e03274ebc0574d9c",
+        "ed263ebe9aa17ef5be9da048eb9f07317a9212d132b753c7a8881d46812b9971",
+        "31bc83c69e11385c059dcce05f15c7fa84a32f3d62af5ad4f0b823237e202033",
+        "cf14ba8b8db2e9414d64db620379a9121764de033d34a071ddacbb88d0ad4f77",
+        "6a02d99c5a48964962d70f5fa7e6cb6f68d256aa1677f4ff0baf08d770d26e0970bef4fd326bb4f32757cf2ae38365ae893f9ddb7f70e96d1966b51a1d23f297"
+    );
+    // AES-192, no derivation function, with prediction resistance
+    drbg_pr_test!(
+        Aes192CtrDrbg,
+        false,
+        "af520be33f1d007eb6ca0b1574d289db9b607bfe18d84ee2014aa5da90af65d180613212d869a97c",
+        "",
+        "4066bdc9d8f8ebdaf22c7cf4b3a6c15d9205ff9d90a5b4267b1ef056ba6d66ef9d0718b8e6d5d1ec",
+        "f198a1147ff7b09c46c1d1c543cafc2685dde83732d09189491df00211ca0a849246deb62b5c5fff",
+        "bec293b502a0d7caf10e00c75b6e0468b420e2adb8269223f483a820a6cf81cbaa9adf1af0584b09",
+        "8f5b35638ccc2ce7f5f4e72f92eeb6db5fb1742a4d3f361ba8a2ebfcf27fce22b94df612b735a9b2",
+        "13bfbffa361367064ac3af0eb7cae04f096c88ec2fe57acfd97a897a04f3c61fe610174e37ec68d4",
+        "76d527e265e3fe3fc3a3fdcb60f06305ce20a6077c47aaa0b78afe1e3dcf8023d04884c325285896335cb8832ec5b5151e8bc1b96419a81d18b2bba5e2f5d7ff"
+    );
+    // AES-256, no derivation function, with prediction resistance
+    drbg_pr_test!(
+        Aes256CtrDrbg,
+        false,
+        "d50abb8d799aa00e4325dfc0151b114755d46913e5bee39d93c0223ba97c31848c2fe3ca0c97e6fb7095c28cd8ad1556",
+        "",
+        "ccdef17932de588ff861a1879b21507dfaf978e2ae160a5495e68ac15df4eef65ca758408893eb012341b1d14d846779",
+        "fa07d26e3276d10ed4aa262bdb93523812d407eede3b497a4a73a882b3fbaadf096b1fa08e0054de65d438588dcb15d1",
+        "a02c35c0620824ea5d8ada36cd71b87160e9d19cf0327547a6da80bafb944d99a761bfd7a464429a391501c30911dc6d",
+        "df022919e151cf914c35d49ba1e54f03f0964ffa2939c7512f5178dc1d349a8f3944f1ec689f475e5a81ecdde35bc61d",
+        "0d109addc44517b8a0548d75bea298e8eb2d9871d1ded96afda7acbd4c9b60d400fc7875a3567164e163bd1707bb81d1",
+        "0a6dbfa3b1b6ae5ec6508e1158c0abf9743c8e0b00a48bc8691137cf5c525e0a3f615b4e1d36556b5a1d65ea11fb4ef35bdb0faacde7b7b3365e19b0382124ac"
+    );
+
+    // the reseed counter limit
+    let mut drbg = Aes128CtrDrbg::instantiate_no_df(&[0; 32], &[])
+        .unwrap()
+        .with_reseed_interval(2);
+    let mut out = [0; 16];
+    assert_eq!(drbg.generate(&mut out, &[]), Ok(()));
+    assert_eq!(drbg.generate(&mut out, &[]), Ok(()));
+    let before = out;
+    assert_eq!(drbg.generate(&mut out, &[]), Err(DrbgError::ReseedRequired));
+    assert_eq!(out, before);
+    drbg.reseed(&[1; 32], &[]).unwrap();
+    assert_eq!(drbg.generate(&mut out, &[]), Ok(()));
+
+    // input lengths
+    assert_eq!(
+        Aes128CtrDrbg::instantiate_no_df(&[0; 31], &[]).unwrap_err(),
+        DrbgError::InvalidEntropyLength
+    );
+    assert_eq!(
+        Aes256CtrDrbg::instantiate_no_df(&[0; 48], &[0; 49]).unwrap_err(),
+        DrbgError::InputTooLong
+    );
+    assert_eq!(
+        Aes256CtrDrbg::instantiate(&[0; 31], &[], &[]).unwrap_err(),
+        DrbgError::InvalidEntropyLength
+    );
+    assert_eq!(
+        drbg.generate(&mut [0; MAX_REQUEST_LEN + 1], &[]),
+        Err(DrbgError::RequestTooLong)
+    );
+}
+
+#[test]
+fn aegis_test() {
+    // test vectors of the AEGIS-128L section of the CFRG draft
+    let key = <[u8; 16]>::from_hex("10010000000000000000000000000000").unwrap();
+    let nonce = <[u8; 16]>::from_hex("10000200000000000000000000000000").unwrap();
+
+    let mut tag = [0; 16];
+    let mut out = [0; 16];
+    aegis::encrypt(&key, &nonce, &[], &[0; 16], &mut out, &mut tag);
+    assert_eq!(
+        out,
+        <[u8; 16]>::from_hex("c1c0e58bd913006feba00f4b3cc3594e").unwrap()
+    );
+    assert_eq!(
+        tag,
+        <[u8; 16]>::from_hex("abe0ece80c24868a226a35d16bdae37a").unwrap()
+    );
+
+    aegis::encrypt(&key, &nonce, &[], &[], &mut [], &mut tag);
+    assert_eq!(
+        tag,
+        <[u8; 16]>::from_hex("c2b879a67def9d74e6c14f708bbcc9b4").unwrap()
+    );
+
+    let aad = <[u8; 8]>::from_hex("0001020304050607").unwrap();
+    let msg =
+        <[u8; 32]>::from_hex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f")
+            .unwrap();
+    let mut out = [0; 32];
+    aegis::encrypt(&key, &nonce, &aad, &msg, &mut out, &mut tag);
+    assert_eq!(
+        out,
+        <[u8; 32]>::from_hex("79d94593d8c2119d7e8fd9b8fc77845c5c077a05b2528b6ac54b563aed8efe84")
+            .unwrap()
+    );
+    assert_eq!(
+        tag,
+        <[u8; 16]>::from_hex("cc6f3372f6aa1bb82388d695c3962d9a").unwrap()
+    );
+    assert_eq!(aegis::decrypt(&key, &nonce, &aad, &mut out, &tag), Ok(()));
+    assert_eq!(out, msg);
+
+    // partial blocks round-trip, and any change is rejected with the plaintext wiped
+    let plaintext: [u8; 77] = core::array::from_fn(|i| i as u8);
+    for len in [0, 1, 15, 16, 31, 32, 33, 64, 77] {
+        let mut data = [0; 77];
+        aegis::encrypt(
+            &key,
+            &nonce,
+            &aad[..3],
+            &plaintext[..len],
+            &mut data[..len],
+            &mut tag,
+        );
+
+        let mut copy = data;
+        assert_eq!(
+            aegis::decrypt(&key, &nonce, &aad[..3], &mut copy[..len], &tag),
+            Ok(())
+        );
+        assert_eq!(copy[..len], plaintext[..len]);
+
+        let mut bad_tag = tag;
+        bad_tag[15] ^= 1;
+        let mut copy = data;
+        assert_eq!(
+            aegis::decrypt(&key, &nonce, &aad[..3], &mut copy[..len], &bad_tag),
+            Err(aead::AuthError)
+        );
+        assert!(copy[..len].iter().all(|&b| b == 0));
+
+        if len > 0 {
+            let mut copy = data;
+            copy[len - 1] ^= 0x80;
+            assert_eq!(
+                aegis::decrypt(&key, &nonce, &aad[..3], &mut copy[..len], &tag),
+                Err(aead::AuthError)
+            );
+        }
+    }
+}
+
+#[test]
+fn to_gcm_order_test() {
+    let enc = Aes128Enc::from(*AES_128_KEY);
+    let gcm = gcm::AesGcm::new(enc.clone());
+    let nonce = [0x5a; 12];
+
+    // with an all-zero plaintext, the ciphertext is the keystream in the order GCM consumes it
+    let mut ciphertext = [0; 64];
+    gcm.encrypt(&nonce, &[], &[0; 64], &mut ciphertext, &mut [0; 16]);
+
+    let mut j0 = [0; 16];
+    j0[..12].copy_from_slice(&nonce);
+    j0[15] = 1;
+    let ctr = AesBlock::from(j0).incr_be_32();
+    let counters = AesBlockX4::from_distinct(
+        ctr,
+        ctr.incr_be_32(),
+        ctr.incr_be_32().incr_be_32(),
+        ctr.incr_be_32().incr_be_32().incr_be_32(),
+    );
+
+    let lanes = enc.encrypt_4_blocks(counters).to_gcm_order();
+    for (lane, chunk) in lanes.iter().zip(ciphertext.chunks_exact(16)) {
+        assert_eq!(*lane, AesBlock::try_from(chunk).unwrap());
+    }
+}
+
+#[test]
+fn count_eq_test() {
+    let [a, b, c, d, _] = AES_128_VECTORS.map(|(pt, _)| pt);
+    let target = AES_128_VECTORS[0].1;
+
+    assert_eq!(AesBlockX4::from((a, b, c, d)).count_eq(target), 0);
+    assert_eq!(AesBlockX4::from((a, b, c, d)).lane_eq_mask(target), 0);
+    assert_eq!(AesBlockX4::from((a, b, target, d)).count_eq(target), 1);
+    assert_eq!(
+        AesBlockX4::from((a, b, target, d)).lane_eq_mask(target),
+        0b0100
+    );
+    assert_eq!(AesBlockX4::from((target, b, c, target)).count_eq(target), 2);
+    assert_eq!(
+        AesBlockX4::from((target, b, c, target)).lane_eq_mask(target),
+        0b1001
+    );
+    assert_eq!(AesBlockX4::from(target).count_eq(target), 4);
+    assert_eq!(AesBlockX4::from(target).lane_eq_mask(target), 0b1111);
+
+    // the mask selects exactly the matching lanes
+    let x = AesBlockX4::from((a, target, c, target));
+    let replaced = AesBlockX4::select_lanes(x.lane_eq_mask(target), x, AesBlockX4::from(d));
+    assert_eq!(replaced, AesBlockX4::from((a, d, c, d)));
+}
+
+#[test]
+fn compression_test() {
+    use crate::compress::*;
+
+    let h = AesBlock::from(0x0011_2233_4455_6677_8899_aabb_ccdd_eeff);
+    let message = *AES_128_KEY;
+
+    let dm = davies_meyer::<Aes128Enc, 16>(h, message);
+    assert_eq!(dm, davies_meyer::<Aes128Enc, 16>(h, message));
+    assert_eq!(dm, Aes128Enc::from(message).encrypt_block(h) ^ h);
+
+    let enc = Aes128Enc::from(<[u8; 16]>::from(h));
+    let mmo = matyas_meyer_oseas(&enc, message.into());
+    assert_eq!(mmo, matyas_meyer_oseas(&enc, message.into()));
+    assert_eq!(mmo, enc.encrypt_block(message.into()) ^ message.into());
+
+    // flipping any single message bit changes about half of the output bits
+    for bit in 0..128 {
+        let mut flipped = message;
+        flipped[bit / 8] ^= 1 << (bit % 8);
+
+        let dm_diff = u128::from(dm ^ davies_meyer::<Aes128Enc, 16>(h, flipped)).count_ones();
+        let mmo_diff = u128::from(mmo ^ matyas_meyer_oseas(&enc, flipped.into())).count_ones();
+        assert!((32..=96).contains(&dm_diff), "{dm_diff} bits changed");
+        assert!((32..=96).contains(&mmo_diff), "{mmo_diff} bits changed");
+    }
+}
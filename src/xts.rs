@@ -0,0 +1,155 @@
+use crate::{AesBlock, AesBlockX4, AesDecrypt, AesEncrypt, InvalidLength};
+
+/// AES-XTS (IEEE 1619) for encrypting disk sectors, with ciphertext stealing for sectors that are not
+/// a multiple of 16 bytes long
+///
+/// ```
+/// # use aes_crypto::{xts::AesXts, Aes128Enc};
+/// let xts = AesXts::new(Aes128Enc::from([1; 16]), Aes128Enc::from([2; 16]));
+/// let mut sector = *b"not a multiple of 16 bytes";
+///
+/// xts.encrypt_sector(7, &mut sector).unwrap();
+/// xts.decrypt_sector(7, &mut sector).unwrap();
+/// assert_eq!(&sector, b"not a multiple of 16 bytes");
+/// ```
+#[derive(Debug, Clone)]
+pub struct AesXts<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> {
+    enc: E,
+    dec: E::Decrypter,
+    tweak_enc: E,
+}
+
+impl<E: AesEncrypt<KEY_LEN>, const KEY_LEN: usize> AesXts<E, KEY_LEN> {
+    /// Creates the cipher from the data key and the tweak key, which should be independent
+    #[inline]
+    pub fn new(data_key: E, tweak_key: E) -> Self {
+        Self {
+            dec: data_key.decrypter(),
+            enc: data_key,
+            tweak_enc: tweak_key,
+        }
+    }
+
+    /// Encrypts every full block of `data` with its tweak, returning the tweak of the next block
+    #[inline(always)]
+    fn process_blocks(
+        data: &mut [u8],
+        mut tweak: AesBlock,
+        f1: impl Fn(AesBlock) -> AesBlock,
+        f4: impl Fn(AesBlockX4) -> AesBlockX4,
+    ) -> AesBlock {
+        let mut chunks = data.chunks_exact_mut(64);
+        for chunk in &mut chunks {
+            let t1 = tweak.xts_mul_x();
+            let t2 = t1.xts_mul_x();
+            let t3 = t2.xts_mul_x();
+            let tweaks = AesBlockX4::from_distinct(tweak, t1, t2, t3);
+            tweak = t3.xts_mul_x();
+
+            let block = AesBlockX4::try_from(&chunk[..]).unwrap() ^ tweaks;
+            (f4(block) ^ tweaks).store_to(chunk);
+        }
+
+        for chunk in chunks.into_remainder().chunks_exact_mut(16) {
+            let block = AesBlock::try_from(&chunk[..]).unwrap() ^ tweak;
+            (f1(block) ^ tweak).store_to(chunk);
+            tweak = tweak.xts_mul_x();
+        }
+        tweak
+    }
+
+    /// Encrypts a sector in place. The sector number `tweak` is encoded as a little-endian 128-bit
+    /// integer, as in IEEE 1619
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidLength`] if `data` is shorter than one block, leaving `data` unchanged
+    pub fn encrypt_sector(&self, tweak: u128, data: &mut [u8]) -> Result<(), InvalidLength> {
+        if data.len() < 16 {
+            return Err(InvalidLength {
+                got: data.len(),
+                need: 16,
+            });
+        }
+        let tweak = self.tweak_enc.encrypt_block(tweak.to_le_bytes().into());
+
+        let rem = data.len() % 16;
+        let bulk = if rem == 0 {
+            data.len()
+        } else {
+            data.len() - 16 - rem
+        };
+        let (head, tail) = data.split_at_mut(bulk);
+
+        let tweak = Self::process_blocks(
+            head,
+            tweak,
+            |block| self.enc.encrypt_block(block),
+            |blocks| self.enc.encrypt_4_blocks(blocks),
+        );
+
+        if rem != 0 {
+            let (last, partial) = tail.split_at_mut(16);
+            let stolen = self
+                .enc
+                .encrypt_block(AesBlock::try_from(&*last).unwrap() ^ tweak)
+                ^ tweak;
+
+            let mut block: [u8; 16] = stolen.into();
+            block[..rem].copy_from_slice(partial);
+            partial.copy_from_slice(&stolen.to_bytes()[..rem]);
+
+            let tweak = tweak.xts_mul_x();
+            (self.enc.encrypt_block(AesBlock::from(block) ^ tweak) ^ tweak).store_to(last);
+        }
+        Ok(())
+    }
+
+    /// Decrypts a sector in place. The sector number `tweak` is encoded as a little-endian 128-bit
+    /// integer, as in IEEE 1619
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidLength`] if `data` is shorter than one block, leaving `data` unchanged
+    pub fn decrypt_sector(&self, tweak: u128, data: &mut [u8]) -> Result<(), InvalidLength> {
+        if data.len() < 16 {
+            return Err(InvalidLength {
+                got: data.len(),
+                need: 16,
+            });
+        }
+        let tweak = self.tweak_enc.encrypt_block(tweak.to_le_bytes().into());
+
+        let rem = data.len() % 16;
+        let bulk = if rem == 0 {
+            data.len()
+        } else {
+            data.len() - 16 - rem
+        };
+        let (head, tail) = data.split_at_mut(bulk);
+
+        let tweak = Self::process_blocks(
+            head,
+            tweak,
+            |block| self.dec.decrypt_block(block),
+            |blocks| self.dec.decrypt_4_blocks(blocks),
+        );
+
+        if rem != 0 {
+            // the last two blocks are processed with their tweaks swapped
+            let (last, partial) = tail.split_at_mut(16);
+            let next_tweak = tweak.xts_mul_x();
+            let stolen = self
+                .dec
+                .decrypt_block(AesBlock::try_from(&*last).unwrap() ^ next_tweak)
+                ^ next_tweak;
+
+            let mut block: [u8; 16] = stolen.into();
+            block[..rem].copy_from_slice(partial);
+            partial.copy_from_slice(&stolen.to_bytes()[..rem]);
+
+            (self.dec.decrypt_block(AesBlock::from(block) ^ tweak) ^ tweak).store_to(last);
+        }
+        Ok(())
+    }
+}